@@ -0,0 +1,2 @@
+-- Persist session classification so reports don't recompute it
+ALTER TABLE sessions ADD COLUMN session_type TEXT;
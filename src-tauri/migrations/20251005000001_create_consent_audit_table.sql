@@ -0,0 +1,10 @@
+-- Create consent_audit table: one row per consent change
+CREATE TABLE IF NOT EXISTS consent_audit (
+    id TEXT PRIMARY KEY NOT NULL,
+    feature_name TEXT NOT NULL,
+    granted INTEGER NOT NULL,
+    changed_at INTEGER NOT NULL
+);
+
+-- Create index for per-feature history lookups
+CREATE INDEX IF NOT EXISTS idx_consent_audit_feature ON consent_audit(feature_name, changed_at);
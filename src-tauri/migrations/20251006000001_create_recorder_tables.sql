@@ -0,0 +1,106 @@
+-- Recorder tables previously created at runtime by each recorder's init_schema.
+-- IF NOT EXISTS keeps this safe on databases where those tables already exist.
+
+-- Application usage per session (OS activity recorder)
+CREATE TABLE IF NOT EXISTS app_usage (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    bundle_id TEXT NOT NULL,
+    process_id INTEGER NOT NULL,
+    start_timestamp INTEGER NOT NULL,
+    end_timestamp INTEGER,
+    focus_duration_ms INTEGER DEFAULT 0,
+    background_duration_ms INTEGER DEFAULT 0,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_app_usage_session ON app_usage(session_id);
+CREATE INDEX IF NOT EXISTS idx_app_usage_app ON app_usage(app_name);
+CREATE INDEX IF NOT EXISTS idx_app_usage_time ON app_usage(start_timestamp);
+
+-- Individual focus spans, for time-of-day reporting
+CREATE TABLE IF NOT EXISTS app_focus_spans (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    bundle_id TEXT NOT NULL,
+    start_timestamp INTEGER NOT NULL,
+    end_timestamp INTEGER NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_focus_spans_session ON app_focus_spans(session_id);
+CREATE INDEX IF NOT EXISTS idx_focus_spans_time ON app_focus_spans(start_timestamp);
+
+-- Clipboard changes (type and size only, never contents)
+CREATE TABLE IF NOT EXISTS clipboard_events (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_clipboard_session ON clipboard_events(session_id);
+CREATE INDEX IF NOT EXISTS idx_clipboard_time ON clipboard_events(timestamp);
+
+-- Keyboard events, written by both the input recorder (ui_element) and the
+-- keyboard recorder (is_sensitive)
+CREATE TABLE IF NOT EXISTS keyboard_events (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    key_code INTEGER NOT NULL,
+    key_char TEXT,
+    modifiers TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    window_title TEXT NOT NULL,
+    process_id INTEGER NOT NULL,
+    ui_element TEXT,
+    is_sensitive INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_keyboard_session ON keyboard_events(session_id);
+CREATE INDEX IF NOT EXISTS idx_keyboard_timestamp ON keyboard_events(timestamp);
+CREATE INDEX IF NOT EXISTS idx_keyboard_app ON keyboard_events(app_name);
+
+-- Mouse events
+CREATE TABLE IF NOT EXISTS mouse_events (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    position_x INTEGER NOT NULL,
+    position_y INTEGER NOT NULL,
+    app_name TEXT NOT NULL,
+    window_title TEXT NOT NULL,
+    process_id INTEGER NOT NULL,
+    ui_element TEXT,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_mouse_session ON mouse_events(session_id);
+CREATE INDEX IF NOT EXISTS idx_mouse_timestamp ON mouse_events(timestamp);
+CREATE INDEX IF NOT EXISTS idx_mouse_position ON mouse_events(position_x, position_y);
+
+-- Keyboard shortcuts recognized by the command analyzer
+CREATE TABLE IF NOT EXISTS commands (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    shortcut TEXT NOT NULL,
+    command_type TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_commands_session ON commands(session_id);
+CREATE INDEX IF NOT EXISTS idx_commands_timestamp ON commands(timestamp);
+CREATE INDEX IF NOT EXISTS idx_commands_shortcut ON commands(shortcut);
+CREATE INDEX IF NOT EXISTS idx_commands_app ON commands(app_name);
@@ -0,0 +1,20 @@
+-- ocr_fts is an external-content table, so rows must be removed with the
+-- 'delete' command and the old values. A plain DELETE reads the text back from
+-- ocr_results, which no longer holds it inside an AFTER trigger.
+DROP TRIGGER IF EXISTS ocr_fts_delete;
+DROP TRIGGER IF EXISTS ocr_fts_update;
+
+CREATE TRIGGER IF NOT EXISTS ocr_fts_delete AFTER DELETE ON ocr_results BEGIN
+    INSERT INTO ocr_fts(ocr_fts, rowid, text, session_id, timestamp)
+    VALUES ('delete', old.rowid, old.text, old.session_id, old.timestamp);
+END;
+
+CREATE TRIGGER IF NOT EXISTS ocr_fts_update AFTER UPDATE ON ocr_results BEGIN
+    INSERT INTO ocr_fts(ocr_fts, rowid, text, session_id, timestamp)
+    VALUES ('delete', old.rowid, old.text, old.session_id, old.timestamp);
+    INSERT INTO ocr_fts(rowid, text, session_id, timestamp)
+    VALUES (new.rowid, new.text, new.session_id, new.timestamp);
+END;
+
+-- Repair any index entries left stale by the previous triggers
+INSERT INTO ocr_fts(ocr_fts) VALUES ('rebuild');
@@ -0,0 +1,6 @@
+-- Redacted keystrokes used to keep their key code, from which some platforms
+-- derive the character. Rows with the character dropped lose the code too.
+-- 4294967295 is REDACTED_KEY_CODE in input_storage.rs.
+UPDATE keyboard_events
+SET key_code = 4294967295
+WHERE is_sensitive = 1 AND key_char IS NULL;
@@ -0,0 +1,4 @@
+-- Captured display size before capture_scale was applied, so downscaled
+-- recordings can be mapped back to screen coordinates
+ALTER TABLE sessions ADD COLUMN source_width INTEGER;
+ALTER TABLE sessions ADD COLUMN source_height INTEGER;
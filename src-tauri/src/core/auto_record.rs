@@ -0,0 +1,215 @@
+// Automatic screen recording driven by which app has focus
+
+use serde::{Deserialize, Serialize};
+
+/// Which apps start recording automatically, and how quickly it reacts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutoRecordConfig {
+    /// App names that start a recording when focused (case-insensitive)
+    #[serde(default)]
+    pub trigger_apps: Vec<String>,
+    /// How long a trigger app must keep focus before recording starts
+    #[serde(default = "default_focus_debounce_secs")]
+    pub focus_debounce_secs: u64,
+    /// How long no trigger app may have focus before recording stops
+    #[serde(default = "default_stop_after_secs")]
+    pub stop_after_secs: u64,
+}
+
+fn default_focus_debounce_secs() -> u64 {
+    5
+}
+
+fn default_stop_after_secs() -> u64 {
+    300
+}
+
+impl Default for AutoRecordConfig {
+    fn default() -> Self {
+        Self {
+            trigger_apps: Vec::new(),
+            focus_debounce_secs: default_focus_debounce_secs(),
+            stop_after_secs: default_stop_after_secs(),
+        }
+    }
+}
+
+impl AutoRecordConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.trigger_apps.is_empty()
+    }
+
+    pub fn is_trigger(&self, app_name: &str) -> bool {
+        self.trigger_apps
+            .iter()
+            .any(|app| app.eq_ignore_ascii_case(app_name))
+    }
+}
+
+/// Payload of the auto-recording-started and auto-recording-stopped events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoRecordEvent {
+    pub session_id: String,
+    /// Trigger app that started the recording; None when stopping
+    pub app_name: Option<String>,
+}
+
+/// What the auto-recorder wants done
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutoRecordAction {
+    Start { app_name: String },
+    Stop,
+}
+
+/// Focus-driven start/stop decisions, independent of the recorder itself
+///
+/// Feed it focus changes and periodic ticks (timestamps in milliseconds).
+/// A trigger app has to hold focus for the debounce period before a start is
+/// requested, so alt-tabbing past it does nothing. Once started, recording
+/// stops after no trigger app has had focus for `stop_after_secs`.
+#[derive(Debug, Default)]
+pub struct AutoRecordPolicy {
+    /// Trigger app currently in focus and when it gained focus
+    focused_trigger: Option<(String, i64)>,
+    /// When focus last left a trigger app
+    left_trigger_at: Option<i64>,
+    /// Whether the current recording was started by this policy
+    active: bool,
+}
+
+impl AutoRecordPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn on_focus(&mut self, config: &AutoRecordConfig, app_name: &str, timestamp: i64) {
+        if config.is_trigger(app_name) {
+            // Switching between trigger apps keeps the original focus time
+            if self.focused_trigger.is_none() {
+                self.focused_trigger = Some((app_name.to_string(), timestamp));
+            }
+            self.left_trigger_at = None;
+        } else if self.focused_trigger.take().is_some() || self.left_trigger_at.is_none() {
+            self.left_trigger_at = Some(timestamp);
+        }
+    }
+
+    pub fn on_tick(&mut self, config: &AutoRecordConfig, now: i64) -> Option<AutoRecordAction> {
+        if !self.active {
+            let (app_name, since) = self.focused_trigger.as_ref()?;
+            if now - since >= (config.focus_debounce_secs * 1000) as i64 {
+                return Some(AutoRecordAction::Start { app_name: app_name.clone() });
+            }
+            return None;
+        }
+
+        if !config.is_enabled() {
+            return Some(AutoRecordAction::Stop);
+        }
+
+        let left_at = self.left_trigger_at?;
+        if self.focused_trigger.is_none() && now - left_at >= (config.stop_after_secs * 1000) as i64 {
+            return Some(AutoRecordAction::Stop);
+        }
+
+        None
+    }
+
+    /// Record that a requested start succeeded
+    pub fn started(&mut self) {
+        self.active = true;
+    }
+
+    /// Record that the auto-started recording ended, or was never started
+    pub fn stopped(&mut self) {
+        self.active = false;
+        // Wait for a fresh focus before starting again
+        self.focused_trigger = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AutoRecordConfig {
+        AutoRecordConfig {
+            trigger_apps: vec!["Code".to_string()],
+            focus_debounce_secs: 5,
+            stop_after_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_start_after_debounce() {
+        let config = config();
+        let mut policy = AutoRecordPolicy::new();
+
+        policy.on_focus(&config, "code", 0);
+        assert_eq!(policy.on_tick(&config, 4_000), None);
+        assert_eq!(
+            policy.on_tick(&config, 5_000),
+            Some(AutoRecordAction::Start { app_name: "code".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_focus_flick_ignored() {
+        let config = config();
+        let mut policy = AutoRecordPolicy::new();
+
+        policy.on_focus(&config, "Code", 0);
+        policy.on_focus(&config, "Safari", 1_000);
+        assert_eq!(policy.on_tick(&config, 10_000), None);
+    }
+
+    #[test]
+    fn test_stop_after_leaving_trigger() {
+        let config = config();
+        let mut policy = AutoRecordPolicy::new();
+
+        policy.on_focus(&config, "Code", 0);
+        assert!(policy.on_tick(&config, 5_000).is_some());
+        policy.started();
+
+        // Briefly leaving and coming back resets the stop timer
+        policy.on_focus(&config, "Safari", 10_000);
+        policy.on_focus(&config, "Code", 20_000);
+        assert_eq!(policy.on_tick(&config, 100_000), None);
+
+        policy.on_focus(&config, "Safari", 100_000);
+        policy.on_focus(&config, "Terminal", 130_000);
+        assert_eq!(policy.on_tick(&config, 159_000), None);
+        assert_eq!(policy.on_tick(&config, 160_000), Some(AutoRecordAction::Stop));
+
+        policy.stopped();
+        assert!(!policy.is_active());
+        assert_eq!(policy.on_tick(&config, 200_000), None);
+    }
+
+    #[test]
+    fn test_no_restart_after_user_takes_over() {
+        let config = config();
+        let mut policy = AutoRecordPolicy::new();
+
+        policy.on_focus(&config, "Code", 0);
+        assert!(policy.on_tick(&config, 5_000).is_some());
+        policy.started();
+
+        // The user stopped the recording by hand while Code kept focus
+        policy.stopped();
+        assert_eq!(policy.on_tick(&config, 100_000), None);
+
+        // Coming back to Code later starts a new one
+        policy.on_focus(&config, "Safari", 110_000);
+        policy.on_focus(&config, "Code", 120_000);
+        assert_eq!(
+            policy.on_tick(&config, 125_000),
+            Some(AutoRecordAction::Start { app_name: "Code".to_string() })
+        );
+    }
+}
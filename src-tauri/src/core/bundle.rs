@@ -0,0 +1,898 @@
+// Session bundles - everything recorded for one session in a single zip
+
+use crate::core::database::Database;
+use crate::core::input_storage::{InputStorage, InputTimeline};
+use crate::core::ocr_storage::{OcrStorage, ProcessedOcrResult, StoredOcrResult};
+use crate::core::session_manager::{Session, SessionManager, SessionMetrics};
+use crate::core::storage::RecordingStorage;
+use crate::core::video_encoder::VideoSegment;
+use crate::models::input::{AppContext, KeyEventType, KeyboardEvent, MouseEvent, Point, UiElement};
+use crate::models::ocr::{OcrResult, TextBlock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Manifest layout written by this version; bumped on incompatible changes
+pub const BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const OCR_ENTRY: &str = "ocr.json";
+const INPUT_ENTRY: &str = "input.csv";
+const SEGMENTS_DIR: &str = "segments";
+
+const INPUT_CSV_HEADER: &str = "kind,timestamp,event_type,key_code,key_char,modifiers,position_x,position_y,app_name,window_title,process_id,ui_element,is_sensitive";
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Describes a bundle; stored as manifest.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub exported_at: i64,
+    pub session: Session,
+    pub metrics: SessionMetrics,
+    /// Video segments, with `path` pointing at the segment's bundle entry
+    pub segments: Vec<VideoSegment>,
+    /// Bundle entries besides the manifest
+    pub files: Vec<String>,
+}
+
+/// Writes sessions to bundles and reads them back
+pub struct SessionBundler {
+    db: Arc<Database>,
+    recording_storage: Arc<RecordingStorage>,
+    ocr_storage: OcrStorage,
+    input_storage: InputStorage,
+}
+
+impl SessionBundler {
+    pub async fn new(db: Arc<Database>, recording_storage: Arc<RecordingStorage>) -> Result<Self> {
+        Ok(Self {
+            recording_storage,
+            ocr_storage: OcrStorage::new(db.clone()),
+            input_storage: InputStorage::new(db.clone()).await?,
+            db,
+        })
+    }
+
+    /// Write a zip of the session's manifest, video segments, OCR text and input timeline
+    ///
+    /// Data types the session has none of are left out of the bundle, as are
+    /// segments whose file no longer exists. There is no transcript entry
+    /// because nothing records audio yet.
+    pub async fn export(
+        &self,
+        session_manager: &SessionManager,
+        session_id: &str,
+        output_path: &Path,
+    ) -> Result<BundleManifest> {
+        let uuid = Uuid::parse_str(session_id)?;
+        let session = session_manager.get_session_by_id(session_id).await?;
+        let metrics = session_manager.calculate_session_metrics(session_id).await?;
+        let segments = self.recording_storage.get_session_segments(uuid).await?;
+        let ocr_results = self.ocr_storage.get_session_ocr_results(uuid, None).await?;
+        let timeline = self.input_storage.get_input_timeline(session_id.to_string()).await?;
+
+        let output_path = output_path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            write_bundle(&output_path, session, metrics, segments, &ocr_results, &timeline)
+        })
+        .await?
+    }
+
+    /// Read a bundle back in as a new session, returning the new session's id
+    ///
+    /// The session gets a fresh id so a bundle can sit next to the data it came
+    /// from. Room for the video files is made under the storage quota before
+    /// they are copied. All rows go in one transaction; if it fails, the video
+    /// files copied into storage are removed again.
+    pub async fn import(&self, bundle_path: &Path) -> Result<String> {
+        let bundle_path = bundle_path.to_path_buf();
+        let BundleContents {
+            archive,
+            manifest,
+            ocr_results,
+            keyboard_events,
+            mouse_events,
+            segment_bytes,
+        } = tokio::task::spawn_blocking(move || read_bundle(&bundle_path)).await??;
+
+        self.recording_storage.reserve_space(segment_bytes).await?;
+
+        let session_id = Uuid::new_v4();
+        let session_path = self.recording_storage.get_session_path(&session_id);
+
+        let imported = async {
+            let segments = {
+                let bundled = manifest.segments.clone();
+                let session_path = session_path.clone();
+                tokio::task::spawn_blocking(move || copy_segments(archive, &bundled, &session_path)).await??
+            };
+
+            let mut tx = self.db.pool().begin().await?;
+
+            sqlx::query(
+                "INSERT INTO sessions (id, start_timestamp, end_timestamp, device_id, created_at, session_type, recording_path)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(session_id.to_string())
+            .bind(manifest.session.start_timestamp)
+            .bind(manifest.session.end_timestamp)
+            .bind(&manifest.session.device_id)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(&manifest.session.session_type)
+            .bind((!segments.is_empty()).then(|| session_path.to_string_lossy().to_string()))
+            .execute(&mut *tx)
+            .await?;
+
+            for segment in &segments {
+                RecordingStorage::insert_segment(&mut tx, &session_id, segment).await?;
+            }
+            for event in &keyboard_events {
+                // Redaction already happened when the events were first recorded
+                InputStorage::insert_keyboard_event(&mut tx, &session_id.to_string(), event, false).await?;
+            }
+            for event in &mouse_events {
+                InputStorage::insert_mouse_event(&mut tx, &session_id.to_string(), event).await?;
+            }
+            for stored in ocr_results {
+                OcrStorage::insert_result(&mut tx, &imported_ocr_result(session_id, stored)).await?;
+            }
+
+            tx.commit().await?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        }
+        .await;
+
+        if let Err(e) = imported {
+            let _ = std::fs::remove_dir_all(&session_path);
+            return Err(e);
+        }
+
+        // Only counted once the session owns the files; a failed import removes them
+        self.recording_storage.record_written(segment_bytes);
+
+        Ok(session_id.to_string())
+    }
+}
+
+/// Write the bundle zip; blocking, so run off the async runtime
+fn write_bundle(
+    output_path: &Path,
+    session: Session,
+    metrics: SessionMetrics,
+    segments: Vec<VideoSegment>,
+    ocr_results: &[StoredOcrResult],
+    timeline: &InputTimeline,
+) -> Result<BundleManifest> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut zip = ZipWriter::new(File::create(output_path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut files = Vec::new();
+    let mut bundled_segments = Vec::new();
+
+    for segment in segments {
+        let Some(file_name) = segment.path.file_name() else {
+            continue;
+        };
+        if !segment.path.exists() {
+            continue;
+        }
+
+        let entry = format!("{}/{}", SEGMENTS_DIR, file_name.to_string_lossy());
+        zip.start_file(entry.as_str(), options)?;
+        std::io::copy(&mut File::open(&segment.path)?, &mut zip)?;
+
+        bundled_segments.push(VideoSegment {
+            path: PathBuf::from(&entry),
+            ..segment
+        });
+        files.push(entry);
+    }
+
+    if !ocr_results.is_empty() {
+        zip.start_file(OCR_ENTRY, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(ocr_results)?)?;
+        files.push(OCR_ENTRY.to_string());
+    }
+
+    if !timeline.keyboard_events.is_empty() || !timeline.mouse_events.is_empty() {
+        zip.start_file(INPUT_ENTRY, options)?;
+        zip.write_all(input_timeline_csv(timeline)?.as_bytes())?;
+        files.push(INPUT_ENTRY.to_string());
+    }
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        session,
+        metrics,
+        segments: bundled_segments,
+        files,
+    };
+    zip.start_file(MANIFEST_ENTRY, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+    zip.finish()?;
+
+    Ok(manifest)
+}
+
+/// Everything read from a bundle ahead of importing it
+struct BundleContents {
+    /// Still open, for copying the segment files out
+    archive: ZipArchive<File>,
+    manifest: BundleManifest,
+    ocr_results: Vec<StoredOcrResult>,
+    keyboard_events: Vec<KeyboardEvent>,
+    mouse_events: Vec<MouseEvent>,
+    /// Uncompressed size of the segment files
+    segment_bytes: u64,
+}
+
+/// Open a bundle and read everything but the segment files, checking it can be imported
+fn read_bundle(bundle_path: &Path) -> Result<BundleContents> {
+    let mut archive = ZipArchive::new(File::open(bundle_path)?)?;
+
+    let manifest: BundleManifest = serde_json::from_reader(archive.by_name(MANIFEST_ENTRY)?)?;
+    if manifest.version != BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported bundle version {}; this version reads {}",
+            manifest.version, BUNDLE_VERSION
+        )
+        .into());
+    }
+
+    // Segments are copied by file name, so two with the same name would overwrite each other
+    let mut file_names = HashSet::new();
+    let mut segment_bytes = 0u64;
+    for segment in &manifest.segments {
+        let file_name = segment
+            .path
+            .file_name()
+            .ok_or_else(|| format!("Invalid segment entry: {}", segment.path.display()))?;
+        if !file_names.insert(file_name.to_owned()) {
+            return Err(format!("Duplicate segment file name: {}", file_name.to_string_lossy()).into());
+        }
+
+        let entry = segment.path.to_string_lossy().replace('\\', "/");
+        segment_bytes += archive.by_name(&entry)?.size();
+    }
+
+    let has_entry = |entry: &str| manifest.files.iter().any(|file| file == entry);
+
+    let ocr_results: Vec<StoredOcrResult> = if has_entry(OCR_ENTRY) {
+        serde_json::from_reader(archive.by_name(OCR_ENTRY)?)?
+    } else {
+        Vec::new()
+    };
+
+    let (keyboard_events, mouse_events) = if has_entry(INPUT_ENTRY) {
+        let mut csv = String::new();
+        archive.by_name(INPUT_ENTRY)?.read_to_string(&mut csv)?;
+        parse_input_csv(&csv)?
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    Ok(BundleContents {
+        archive,
+        manifest,
+        ocr_results,
+        keyboard_events,
+        mouse_events,
+        segment_bytes,
+    })
+}
+
+/// Copy the bundle's segment files into a session directory, pointing the segments at the copies
+fn copy_segments(
+    mut archive: ZipArchive<File>,
+    segments: &[VideoSegment],
+    session_path: &Path,
+) -> Result<Vec<VideoSegment>> {
+    if segments.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let segments_path = session_path.join(SEGMENTS_DIR);
+    std::fs::create_dir_all(&segments_path)?;
+    std::fs::create_dir_all(session_path.join("frames"))?;
+
+    segments
+        .iter()
+        .map(|segment| {
+            // Only the file name is trusted, so entries can't write outside the session
+            let file_name = segment
+                .path
+                .file_name()
+                .ok_or_else(|| format!("Invalid segment entry: {}", segment.path.display()))?;
+            let entry = segment.path.to_string_lossy().replace('\\', "/");
+
+            let destination = segments_path.join(file_name);
+            std::io::copy(&mut archive.by_name(&entry)?, &mut File::create(&destination)?)?;
+
+            Ok(VideoSegment {
+                path: destination,
+                ..segment.clone()
+            })
+        })
+        .collect()
+}
+
+/// An exported OCR row as a single-block result for the new session
+fn imported_ocr_result(session_id: Uuid, stored: StoredOcrResult) -> ProcessedOcrResult {
+    let block = TextBlock {
+        text: stored.text,
+        confidence: stored.confidence,
+        bounding_box: stored.bounding_box,
+        language: stored.language,
+    };
+
+    ProcessedOcrResult {
+        session_id,
+        timestamp: stored.timestamp,
+        // Frames are not bundled, so there is nothing to point at
+        frame_path: None,
+        ocr_result: OcrResult::new(stored.timestamp, vec![block], stored.processing_time_ms.unwrap_or(0)),
+    }
+}
+
+/// Keyboard and mouse events as CSV rows, in timestamp order
+///
+/// Mouse event types are written as JSON so drags and scrolls keep their data.
+fn input_timeline_csv(timeline: &InputTimeline) -> Result<String> {
+    let mut rows: Vec<(i64, String)> = Vec::new();
+
+    for event in &timeline.keyboard_events {
+        let ui_element = event.ui_element.as_ref().map(serde_json::to_string).transpose()?;
+        rows.push((event.timestamp, csv_row(&[
+            "keyboard",
+            &event.timestamp.to_string(),
+            event.event_type.to_string(),
+            &event.key_code.to_string(),
+            &event.key_char.map(String::from).unwrap_or_default(),
+            &serde_json::to_string(&event.modifiers)?,
+            "",
+            "",
+            &event.app_context.app_name,
+            &event.app_context.window_title,
+            &event.app_context.process_id.to_string(),
+            ui_element.as_deref().unwrap_or(""),
+            if event.is_sensitive { "1" } else { "0" },
+        ])));
+    }
+
+    for event in &timeline.mouse_events {
+        let ui_element = event.ui_element.as_ref().map(serde_json::to_string).transpose()?;
+        rows.push((event.timestamp, csv_row(&[
+            "mouse",
+            &event.timestamp.to_string(),
+            &serde_json::to_string(&event.event_type)?,
+            "",
+            "",
+            "",
+            &event.position.x.to_string(),
+            &event.position.y.to_string(),
+            &event.app_context.app_name,
+            &event.app_context.window_title,
+            &event.app_context.process_id.to_string(),
+            ui_element.as_deref().unwrap_or(""),
+            "",
+        ])));
+    }
+
+    rows.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut csv = String::from(INPUT_CSV_HEADER);
+    csv.push('\n');
+    for (_, row) in rows {
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+
+    Ok(csv)
+}
+
+/// Rebuild keyboard and mouse events from an input.csv written by `input_timeline_csv`
+fn parse_input_csv(text: &str) -> Result<(Vec<KeyboardEvent>, Vec<MouseEvent>)> {
+    let mut records = parse_csv(text)?.into_iter();
+    let header = records.next().ok_or("input.csv is empty")?;
+    if header.join(",") != INPUT_CSV_HEADER {
+        return Err("input.csv has unrecognized columns".into());
+    }
+
+    let mut keyboard_events = Vec::new();
+    let mut mouse_events = Vec::new();
+
+    for record in records {
+        let [kind, timestamp, event_type, key_code, key_char, modifiers, position_x, position_y, app_name, window_title, process_id, ui_element, is_sensitive] =
+            record.as_slice()
+        else {
+            return Err(format!("input.csv row has {} fields, expected {}", record.len(), header.len()).into());
+        };
+
+        let timestamp: i64 = timestamp.parse()?;
+        let app_context = AppContext::new(app_name.clone(), window_title.clone(), process_id.parse()?);
+        let ui_element: Option<UiElement> = if ui_element.is_empty() {
+            None
+        } else {
+            Some(serde_json::from_str(ui_element)?)
+        };
+
+        match kind.as_str() {
+            "keyboard" => keyboard_events.push(KeyboardEvent {
+                timestamp,
+                event_type: match event_type.as_str() {
+                    "key_down" => KeyEventType::KeyDown,
+                    "key_up" => KeyEventType::KeyUp,
+                    other => return Err(format!("Unknown key event type in input.csv: {}", other).into()),
+                },
+                key_code: key_code.parse()?,
+                key_char: key_char.chars().next(),
+                modifiers: serde_json::from_str(modifiers)?,
+                app_context,
+                ui_element,
+                is_sensitive: is_sensitive == "1",
+            }),
+            "mouse" => mouse_events.push(MouseEvent {
+                timestamp,
+                event_type: serde_json::from_str(event_type)?,
+                position: Point {
+                    x: position_x.parse()?,
+                    y: position_y.parse()?,
+                },
+                app_context,
+                ui_element,
+            }),
+            other => return Err(format!("Unknown row kind in input.csv: {}", other).into()),
+        }
+    }
+
+    Ok((keyboard_events, mouse_events))
+}
+
+/// Split CSV text into records of fields, undoing `csv_field` quoting
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+        } else {
+            match c {
+                '"' => in_quotes = true,
+                ',' => record.push(std::mem::take(&mut field)),
+                '\r' => {}
+                '\n' => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                _ => field.push(c),
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err("input.csv ends inside a quoted field".into());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn csv_row(fields: &[&str]) -> String {
+    fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",")
+}
+
+/// Quote a field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::input_storage::REDACTED_KEY_CODE;
+    use crate::core::session_manager::SessionConfig;
+    use crate::models::input::{ModifierState, MouseEventType};
+    use crate::models::ocr::BoundingBox;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_db() -> Arc<Database> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db
+    }
+
+    fn key_event(timestamp: i64, key_char: char) -> KeyboardEvent {
+        KeyboardEvent {
+            timestamp,
+            event_type: KeyEventType::KeyDown,
+            key_code: key_char as u32,
+            key_char: Some(key_char),
+            modifiers: ModifierState::new(),
+            app_context: AppContext::new("Editor".to_string(), "notes, draft".to_string(), 42),
+            ui_element: None,
+            is_sensitive: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_omits_missing_data_types() {
+        let db = test_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_bundle_export");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let recording_storage = Arc::new(RecordingStorage::new(temp_dir.join("recordings"), db.clone()).await.unwrap());
+        let session_manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string())
+            .await
+            .unwrap();
+
+        // A video segment and some typing, but no OCR or mouse input
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 1_000, "test-device").await.unwrap();
+        db.end_session(&session_id.to_string(), 61_000).await.unwrap();
+
+        recording_storage.attach_session(session_id).await.unwrap();
+        let segment_path = recording_storage.get_segment_path(&session_id, 0);
+        std::fs::write(&segment_path, b"video bytes").unwrap();
+        recording_storage
+            .save_segment(&session_id, &VideoSegment {
+                path: segment_path,
+                start_timestamp: 1_000,
+                end_timestamp: 61_000,
+                frame_count: 60,
+                duration_ms: 60_000,
+                file_size_bytes: 11,
+            })
+            .await
+            .unwrap();
+
+        let input_storage = InputStorage::new(db.clone()).await.unwrap();
+        for (i, c) in "hi".chars().enumerate() {
+            input_storage.store_keyboard_event(session_id.to_string(), key_event(2_000 + i as i64, c)).await.unwrap();
+        }
+        // Typed into a password field, so exported without the key
+        input_storage
+            .store_keyboard_event(session_id.to_string(), KeyboardEvent { is_sensitive: true, ..key_event(3_000, 's') })
+            .await
+            .unwrap();
+        input_storage.flush_buffers().await.unwrap();
+
+        let bundler = SessionBundler::new(db.clone(), recording_storage.clone()).await.unwrap();
+        let output_path = temp_dir.join("export").join("bundle.zip");
+        let manifest = bundler
+            .export(&session_manager, &session_id.to_string(), &output_path)
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output_path).unwrap()).unwrap();
+        let mut entries: Vec<&str> = archive.file_names().collect();
+        entries.sort();
+        assert_eq!(entries, vec!["input.csv", "manifest.json", "segments/segment_0000.mp4"]);
+
+        assert_eq!(manifest.version, BUNDLE_VERSION);
+        assert_eq!(manifest.session.id, session_id.to_string());
+        assert_eq!(manifest.segments.len(), 1);
+        assert_eq!(manifest.segments[0].path, PathBuf::from("segments/segment_0000.mp4"));
+
+        let mut csv = String::new();
+        archive.by_name(INPUT_ENTRY).unwrap().read_to_string(&mut csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], INPUT_CSV_HEADER);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("keyboard,2000,key_down,104,h,"));
+        // Fields holding commas are quoted
+        assert!(lines[1].contains(",\"notes, draft\","));
+        assert!(lines[3].starts_with(&format!("keyboard,3000,key_down,{},,", REDACTED_KEY_CODE)));
+        assert!(lines[3].ends_with(",1"));
+
+        let mut video = Vec::new();
+        archive.by_name("segments/segment_0000.mp4").unwrap().read_to_end(&mut video).unwrap();
+        assert_eq!(video, b"video bytes");
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_import_round_trips_export() {
+        let db = test_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_bundle_import");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let recording_storage = Arc::new(RecordingStorage::new(temp_dir.join("recordings"), db.clone()).await.unwrap());
+        let session_manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string())
+            .await
+            .unwrap();
+
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 1_000, "test-device").await.unwrap();
+        db.end_session(&session_id.to_string(), 61_000).await.unwrap();
+
+        recording_storage.attach_session(session_id).await.unwrap();
+        let segment_path = recording_storage.get_segment_path(&session_id, 0);
+        std::fs::write(&segment_path, b"video bytes").unwrap();
+        recording_storage
+            .save_segment(&session_id, &VideoSegment {
+                path: segment_path,
+                start_timestamp: 1_000,
+                end_timestamp: 61_000,
+                frame_count: 60,
+                duration_ms: 60_000,
+                file_size_bytes: 11,
+            })
+            .await
+            .unwrap();
+
+        let input_storage = InputStorage::new(db.clone()).await.unwrap();
+        for (i, c) in "a,\"b".chars().enumerate() {
+            input_storage.store_keyboard_event(session_id.to_string(), key_event(2_000 + i as i64, c)).await.unwrap();
+        }
+        for (timestamp, event_type) in [
+            (3_000, MouseEventType::LeftClick),
+            (4_000, MouseEventType::Move { target: Point { x: 300, y: 400 } }),
+        ] {
+            input_storage
+                .store_mouse_event(session_id.to_string(), MouseEvent {
+                    timestamp,
+                    event_type,
+                    position: Point { x: 10, y: 20 },
+                    app_context: AppContext::new("Browser".to_string(), "Search\nResults".to_string(), 7),
+                    ui_element: None,
+                })
+                .await
+                .unwrap();
+        }
+        input_storage.flush_buffers().await.unwrap();
+
+        let ocr_storage = OcrStorage::new(db.clone());
+        let blocks = vec![
+            TextBlock::new("Hello".to_string(), 0.9, BoundingBox::new(0, 0, 50, 10), "en".to_string()),
+            TextBlock::new("world".to_string(), 0.8, BoundingBox::new(60, 0, 50, 10), "en".to_string()),
+        ];
+        ocr_storage
+            .save_ocr_result(ProcessedOcrResult {
+                session_id,
+                timestamp: 5_000,
+                frame_path: None,
+                ocr_result: OcrResult::new(5_000, blocks, 120),
+            })
+            .await
+            .unwrap();
+
+        let bundler = SessionBundler::new(db.clone(), recording_storage.clone()).await.unwrap();
+        let bundle_path = temp_dir.join("bundle.zip");
+        bundler
+            .export(&session_manager, &session_id.to_string(), &bundle_path)
+            .await
+            .unwrap();
+
+        let imported_id = bundler.import(&bundle_path).await.unwrap();
+        assert_ne!(imported_id, session_id.to_string());
+        let imported_uuid = Uuid::parse_str(&imported_id).unwrap();
+
+        let original = session_manager.get_session_by_id(&session_id.to_string()).await.unwrap();
+        let imported = session_manager.get_session_by_id(&imported_id).await.unwrap();
+        assert_eq!(imported.start_timestamp, original.start_timestamp);
+        assert_eq!(imported.end_timestamp, original.end_timestamp);
+        assert_eq!(imported.device_id, original.device_id);
+
+        let original_timeline = input_storage.get_input_timeline(session_id.to_string()).await.unwrap();
+        let imported_timeline = input_storage.get_input_timeline(imported_id.clone()).await.unwrap();
+        assert_eq!(imported_timeline.keyboard_events.len(), 4);
+        assert_eq!(imported_timeline.mouse_events.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&imported_timeline).unwrap(),
+            serde_json::to_value(&original_timeline).unwrap()
+        );
+
+        let ocr_fields = |results: Vec<StoredOcrResult>| {
+            let mut fields: Vec<_> = results
+                .into_iter()
+                .map(|r| (r.timestamp, r.text, r.confidence, r.bounding_box.x, r.language, r.processing_time_ms))
+                .collect();
+            fields.sort_by(|a, b| a.1.cmp(&b.1));
+            fields
+        };
+        let original_ocr = ocr_storage.get_session_ocr_results(session_id, None).await.unwrap();
+        let imported_ocr = ocr_storage.get_session_ocr_results(imported_uuid, None).await.unwrap();
+        assert_eq!(imported_ocr.len(), 2);
+        assert_eq!(ocr_fields(imported_ocr), ocr_fields(original_ocr));
+
+        let segments = recording_storage.get_session_segments(imported_uuid).await.unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!((segments[0].start_timestamp, segments[0].end_timestamp), (1_000, 61_000));
+        assert_eq!(segments[0].frame_count, 60);
+        assert!(segments[0].path.starts_with(recording_storage.get_session_path(&imported_uuid)));
+        assert_eq!(std::fs::read(&segments[0].path).unwrap(), b"video bytes");
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_unknown_version() {
+        let db = test_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_bundle_version");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let recording_storage = Arc::new(RecordingStorage::new(temp_dir.join("recordings"), db.clone()).await.unwrap());
+        let session_manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string())
+            .await
+            .unwrap();
+
+        let session_id = Uuid::new_v4().to_string();
+        db.create_session(&session_id, 1_000, "test-device").await.unwrap();
+        db.end_session(&session_id, 61_000).await.unwrap();
+
+        let bundler = SessionBundler::new(db.clone(), recording_storage).await.unwrap();
+        let mut manifest = bundler
+            .export(&session_manager, &session_id, &temp_dir.join("bundle.zip"))
+            .await
+            .unwrap();
+
+        // Same layout, but claiming a schema from a later version
+        manifest.version = BUNDLE_VERSION + 1;
+        let future_path = temp_dir.join("future.zip");
+        let mut zip = ZipWriter::new(File::create(&future_path).unwrap());
+        zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap()).unwrap();
+        zip.finish().unwrap();
+
+        let error = bundler.import(&future_path).await.unwrap_err();
+        assert!(error.to_string().contains("Unsupported bundle version"));
+
+        let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(sessions, 1);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_import_checks_segments_before_copying() {
+        let db = test_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_bundle_segments");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let recording_storage = Arc::new(RecordingStorage::new(temp_dir.join("recordings"), db.clone()).await.unwrap());
+        let session_manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string())
+            .await
+            .unwrap();
+
+        let session_id = Uuid::new_v4().to_string();
+        db.create_session(&session_id, 1_000, "test-device").await.unwrap();
+        db.end_session(&session_id, 61_000).await.unwrap();
+
+        let bundler = SessionBundler::new(db.clone(), recording_storage.clone()).await.unwrap();
+        let manifest = bundler
+            .export(&session_manager, &session_id, &temp_dir.join("bundle.zip"))
+            .await
+            .unwrap();
+
+        let write_bundle = |name: &str, entries: &[&str]| {
+            let segment = |entry: &str| VideoSegment {
+                path: PathBuf::from(entry),
+                start_timestamp: 1_000,
+                end_timestamp: 2_000,
+                frame_count: 10,
+                duration_ms: 1_000,
+                file_size_bytes: 1_000,
+            };
+            let manifest = BundleManifest {
+                segments: entries.iter().map(|entry| segment(entry)).collect(),
+                files: entries.iter().map(|entry| entry.to_string()).collect(),
+                ..manifest.clone()
+            };
+
+            let path = temp_dir.join(name);
+            let mut zip = ZipWriter::new(File::create(&path).unwrap());
+            for entry in entries {
+                zip.start_file(*entry, SimpleFileOptions::default()).unwrap();
+                zip.write_all(&[0u8; 1_000]).unwrap();
+            }
+            zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&serde_json::to_vec(&manifest).unwrap()).unwrap();
+            zip.finish().unwrap();
+            path
+        };
+
+        // Both would be copied to segments/segment_0000.mp4
+        let duplicated = write_bundle("duplicated.zip", &["segments/segment_0000.mp4", "other/segment_0000.mp4"]);
+        let error = bundler.import(&duplicated).await.unwrap_err();
+        assert!(error.to_string().contains("Duplicate segment file name"));
+
+        // Too big for the quota even with every finished session evicted
+        recording_storage.set_max_storage_bytes(Some(500));
+        let oversized = write_bundle("oversized.zip", &["segments/segment_0000.mp4"]);
+        assert!(bundler.import(&oversized).await.is_err());
+
+        let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(sessions, 1);
+        assert_eq!(std::fs::read_dir(temp_dir.join("recordings")).unwrap().count(), 0);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_failed_import_does_not_count_towards_quota() {
+        let db = test_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_bundle_failed_import");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let recording_storage = Arc::new(RecordingStorage::new(temp_dir.join("recordings"), db.clone()).await.unwrap());
+        let session_manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string())
+            .await
+            .unwrap();
+
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 1_000, "test-device").await.unwrap();
+        db.end_session(&session_id.to_string(), 61_000).await.unwrap();
+
+        recording_storage.attach_session(session_id).await.unwrap();
+        let segment_path = recording_storage.get_segment_path(&session_id, 0);
+        std::fs::write(&segment_path, b"video bytes").unwrap();
+        recording_storage
+            .save_segment(&session_id, &VideoSegment {
+                path: segment_path.clone(),
+                start_timestamp: 1_000,
+                end_timestamp: 61_000,
+                frame_count: 60,
+                duration_ms: 60_000,
+                file_size_bytes: 11,
+            })
+            .await
+            .unwrap();
+
+        let bundler = SessionBundler::new(db.clone(), recording_storage.clone()).await.unwrap();
+        let bundle_path = temp_dir.join("bundle.zip");
+        bundler
+            .export(&session_manager, &session_id.to_string(), &bundle_path)
+            .await
+            .unwrap();
+        assert_eq!(recording_storage.get_disk_usage().unwrap(), 11);
+
+        // The segments are copied, then recording them fails
+        sqlx::query("DROP TABLE video_segments").execute(db.pool()).await.unwrap();
+        assert!(bundler.import(&bundle_path).await.is_err());
+
+        // Exactly at the quota, so any bytes left over from the import would evict the session
+        recording_storage.set_max_storage_bytes(Some(11));
+        assert!(recording_storage.enforce_quota().await.unwrap().is_empty());
+        assert!(segment_path.exists());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::core::consent::{ConsentManager, Feature};
+use crate::core::database::Database;
+use crate::platform::clipboard::{self, ClipboardMetadata};
+
+/// How often the clipboard change token is checked
+const POLL_INTERVAL_MS: u64 = 500;
+
+// ==============================================================================
+// Clipboard Storage
+// ==============================================================================
+
+/// A clipboard change. Only the type and size are stored, never the contents.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ClipboardEvent {
+    pub id: String,
+    pub session_id: String,
+    pub timestamp: i64,
+    pub content_type: String,
+    pub size_bytes: i64,
+}
+
+#[derive(Clone)]
+pub struct ClipboardStorage {
+    db: Arc<Database>,
+}
+
+impl ClipboardStorage {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn record_change(&self, session_id: &str, timestamp: i64, metadata: &ClipboardMetadata) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            "INSERT INTO clipboard_events (id, session_id, timestamp, content_type, size_bytes)
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(timestamp)
+        .bind(metadata.content_type.as_str())
+        .bind(metadata.size_bytes as i64)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_events_for_session(&self, session_id: &str) -> Result<Vec<ClipboardEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        let events = sqlx::query_as::<_, ClipboardEvent>(
+            "SELECT id, session_id, timestamp, content_type, size_bytes
+             FROM clipboard_events
+             WHERE session_id = ?
+             ORDER BY timestamp ASC"
+        )
+        .bind(session_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(events)
+    }
+}
+
+// ==============================================================================
+// Clipboard Monitor
+// ==============================================================================
+
+pub struct ClipboardMonitor {
+    consent_manager: Arc<ConsentManager>,
+    storage: ClipboardStorage,
+    current_session_id: Arc<RwLock<Option<String>>>,
+    is_recording: Arc<RwLock<bool>>,
+    poll_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+impl ClipboardMonitor {
+    pub async fn new(consent_manager: Arc<ConsentManager>, db: Arc<Database>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let storage = ClipboardStorage::new(db);
+
+        Ok(Self {
+            consent_manager,
+            storage,
+            current_session_id: Arc::new(RwLock::new(None)),
+            is_recording: Arc::new(RwLock::new(false)),
+            poll_task: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    pub async fn start_recording(&self, session_id: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let has_consent = self.consent_manager
+            .is_consent_granted(Feature::ClipboardMonitoring)
+            .await
+            .map_err(|e| format!("Consent check failed: {}", e))?;
+
+        if !has_consent {
+            return Err("ClipboardMonitoring consent not granted".into());
+        }
+
+        let mut is_recording = self.is_recording.write().await;
+        if *is_recording {
+            return Err("Already recording".into());
+        }
+
+        *self.current_session_id.write().await = Some(session_id);
+        *is_recording = true;
+
+        let storage = self.storage.clone();
+        let current_session_id = self.current_session_id.clone();
+        let is_recording_clone = self.is_recording.clone();
+
+        let handle = tokio::spawn(async move {
+            Self::poll_loop(storage, current_session_id, is_recording_clone).await;
+        });
+        *self.poll_task.write().await = Some(handle);
+
+        Ok(())
+    }
+
+    pub async fn stop_recording(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut is_recording = self.is_recording.write().await;
+        if !*is_recording {
+            return Ok(());
+        }
+
+        *is_recording = false;
+        *self.current_session_id.write().await = None;
+        drop(is_recording);
+
+        // The loop may be parked on its interval; stop it now so a quick
+        // restart doesn't leave two loops recording the same changes
+        if let Some(handle) = self.poll_task.write().await.take() {
+            handle.abort();
+            let _ = handle.await;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_clipboard_events(&self, session_id: &str) -> Result<Vec<ClipboardEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.storage.get_events_for_session(session_id).await
+    }
+
+    async fn poll_loop(
+        storage: ClipboardStorage,
+        current_session_id: Arc<RwLock<Option<String>>>,
+        is_recording: Arc<RwLock<bool>>,
+    ) {
+        let mut interval = tokio::time::interval(Duration::from_millis(POLL_INTERVAL_MS));
+
+        // Whatever is on the clipboard at start was copied before this session
+        let mut last_token = tokio::task::spawn_blocking(clipboard::change_token)
+            .await
+            .ok()
+            .flatten();
+
+        loop {
+            interval.tick().await;
+
+            if !*is_recording.read().await {
+                break;
+            }
+
+            let token = match tokio::task::spawn_blocking(clipboard::change_token).await {
+                Ok(Some(token)) => token,
+                _ => continue,
+            };
+
+            if last_token == Some(token) {
+                continue;
+            }
+            last_token = Some(token);
+
+            let timestamp = chrono::Utc::now().timestamp_millis();
+
+            let Ok(Some(metadata)) = tokio::task::spawn_blocking(clipboard::current_metadata).await else {
+                continue;
+            };
+
+            let session_id = match &*current_session_id.read().await {
+                Some(id) => id.clone(),
+                None => continue,
+            };
+
+            if let Err(e) = storage.record_change(&session_id, timestamp, &metadata).await {
+                eprintln!("Error recording clipboard change: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stop_recording_ends_the_poll_task() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        let consent_manager = Arc::new(ConsentManager::new(db.clone()).await.unwrap());
+        consent_manager.grant_consent(Feature::ClipboardMonitoring).await.unwrap();
+        let monitor = ClipboardMonitor::new(consent_manager, db).await.unwrap();
+
+        monitor.start_recording("first".to_string()).await.unwrap();
+        let first_task = monitor.poll_task.read().await.as_ref().unwrap().abort_handle();
+        monitor.stop_recording().await.unwrap();
+        assert!(first_task.is_finished());
+
+        // Restarting right away runs only the new loop
+        monitor.start_recording("second".to_string()).await.unwrap();
+        let second_task = monitor.poll_task.read().await.as_ref().unwrap().abort_handle();
+        assert!(!second_task.is_finished());
+        monitor.stop_recording().await.unwrap();
+        assert!(second_task.is_finished());
+    }
+}
@@ -22,7 +22,7 @@ pub struct Command {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum CommandType {
     System,              // OS-level shortcuts
@@ -46,18 +46,59 @@ impl CommandType {
 // Command Database
 // ==============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CommandDefinition {
     pub shortcut: KeyboardShortcut,
     pub name: String,
     pub description: String,
+    #[serde(default = "default_command_type")]
     pub command_type: CommandType,
+    #[serde(default)]
     pub platforms: Vec<String>, // ["macos", "windows", "linux"]
+    #[serde(default)]
     pub applications: Option<Vec<String>>, // Specific apps or None for global
 }
 
+fn default_command_type() -> CommandType {
+    CommandType::Custom
+}
+
+impl CommandDefinition {
+    fn applies_to(&self, app_name: &str) -> bool {
+        self.applications
+            .as_ref()
+            .is_some_and(|apps| apps.iter().any(|app| app.eq_ignore_ascii_case(app_name)))
+    }
+}
+
+/// A command entered as an ordered series of shortcuts, e.g. Ctrl+K Ctrl+C
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChordDefinition {
+    pub sequence: Vec<KeyboardShortcut>,
+    pub name: String,
+    pub description: String,
+    pub command_type: CommandType,
+    pub platforms: Vec<String>,
+    pub applications: Option<Vec<String>>,
+}
+
+impl ChordDefinition {
+    fn applies_to(&self, app_name: &str) -> bool {
+        match &self.applications {
+            Some(apps) => apps.iter().any(|app| app.eq_ignore_ascii_case(app_name)),
+            None => true,
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.sequence.iter().map(CommandDatabase::shortcut_to_key).collect()
+    }
+}
+
 pub struct CommandDatabase {
-    shortcuts: HashMap<String, CommandDefinition>,
+    // Global and app-specific definitions that share a key combination
+    shortcuts: HashMap<String, Vec<CommandDefinition>>,
+    chords: Vec<ChordDefinition>,
 }
 
 impl CommandDatabase {
@@ -609,15 +650,240 @@ impl CommandDatabase {
             );
         }
 
-        Self { shortcuts }
+        let mut database = Self {
+            shortcuts: shortcuts
+                .into_iter()
+                .map(|(key, definition)| (key, vec![definition]))
+                .collect(),
+            chords: Self::default_chords(),
+        };
+
+        for definition in Self::default_app_shortcuts() {
+            let key = Self::shortcut_to_key(&definition.shortcut);
+            database.shortcuts.entry(key).or_default().push(definition);
+        }
+
+        database
     }
 
-    pub fn lookup(&self, shortcut: &KeyboardShortcut) -> Option<&CommandDefinition> {
-        let key = self.shortcut_to_key(shortcut);
-        self.shortcuts.get(&key)
+    /// Shortcuts whose meaning depends on the focused application
+    fn default_app_shortcuts() -> Vec<CommandDefinition> {
+        #[cfg(target_os = "macos")]
+        let (modifiers, prefix, platforms) = (
+            ModifierState {
+                meta: true,
+                shift: false,
+                ctrl: false,
+                alt: false,
+            },
+            "⌘",
+            vec!["macos".to_string()],
+        );
+        #[cfg(not(target_os = "macos"))]
+        let (modifiers, prefix, platforms) = (
+            ModifierState {
+                meta: false,
+                shift: false,
+                ctrl: true,
+                alt: false,
+            },
+            "Ctrl+",
+            vec!["windows".to_string(), "linux".to_string()],
+        );
+
+        let shifted = ModifierState {
+            shift: true,
+            ..modifiers.clone()
+        };
+        #[cfg(target_os = "macos")]
+        let shifted_prefix = "⇧⌘";
+        #[cfg(not(target_os = "macos"))]
+        let shifted_prefix = "Ctrl+Shift+";
+
+        #[cfg(target_os = "macos")]
+        let file_manager = vec!["Finder".to_string()];
+        #[cfg(not(target_os = "macos"))]
+        let file_manager = vec!["Explorer".to_string(), "Files".to_string(), "Nautilus".to_string()];
+
+        // Cmd+D duplicates in Finder; Ctrl+D deletes in Windows Explorer
+        #[cfg(target_os = "macos")]
+        let (file_manager_d, file_manager_d_name, file_manager_d_description) =
+            (file_manager.clone(), "Duplicate", "Duplicate the selected files");
+        #[cfg(not(target_os = "macos"))]
+        let (file_manager_d, file_manager_d_name, file_manager_d_description) =
+            (vec!["Explorer".to_string()], "Delete", "Move the selected files to the Recycle Bin");
+
+        let browser = vec!["Google Chrome".to_string(), "Chrome".to_string()];
+
+        let definition = |modifiers: &ModifierState, display: String, key: &str, name: &str, description: &str, apps: &[String]| {
+            CommandDefinition {
+                shortcut: KeyboardShortcut {
+                    modifiers: modifiers.clone(),
+                    key: key.to_string(),
+                    display,
+                },
+                name: name.to_string(),
+                description: description.to_string(),
+                command_type: CommandType::ApplicationSpecific,
+                platforms: platforms.clone(),
+                applications: Some(apps.to_vec()),
+            }
+        };
+
+        vec![
+            definition(&modifiers, format!("{}D", prefix), "D", "Bookmark Page", "Bookmark the current page", &browser),
+            definition(&modifiers, format!("{}D", prefix), "D", file_manager_d_name, file_manager_d_description, &file_manager_d),
+            definition(&shifted, format!("{}N", shifted_prefix), "N", "New Incognito Window", "Open a private browsing window", &browser),
+            definition(&shifted, format!("{}N", shifted_prefix), "N", "New Folder", "Create a new folder", &file_manager),
+        ]
     }
 
-    fn shortcut_to_key(&self, shortcut: &KeyboardShortcut) -> String {
+    fn default_chords() -> Vec<ChordDefinition> {
+        let ctrl = ModifierState {
+            meta: false,
+            shift: false,
+            ctrl: true,
+            alt: false,
+        };
+
+        let mut chords = Vec::new();
+
+        // VS Code uses Cmd on macOS and Ctrl elsewhere
+        #[cfg(target_os = "macos")]
+        let (vscode_modifiers, vscode_prefix) = (
+            ModifierState {
+                meta: true,
+                shift: false,
+                ctrl: false,
+                alt: false,
+            },
+            "⌘",
+        );
+        #[cfg(not(target_os = "macos"))]
+        let (vscode_modifiers, vscode_prefix) = (ctrl.clone(), "Ctrl+");
+
+        let vscode_apps = Some(vec!["Code".to_string(), "Visual Studio Code".to_string()]);
+
+        for (key, name, description) in [
+            ("C", "Add Line Comment", "Comment out the selected lines"),
+            ("U", "Remove Line Comment", "Uncomment the selected lines"),
+            ("F", "Format Selection", "Format the selected code"),
+        ] {
+            chords.push(ChordDefinition {
+                sequence: vec![
+                    KeyboardShortcut {
+                        modifiers: vscode_modifiers.clone(),
+                        key: "K".to_string(),
+                        display: format!("{}K", vscode_prefix),
+                    },
+                    KeyboardShortcut {
+                        modifiers: vscode_modifiers.clone(),
+                        key: key.to_string(),
+                        display: format!("{}{}", vscode_prefix, key),
+                    },
+                ],
+                name: name.to_string(),
+                description: description.to_string(),
+                command_type: CommandType::ApplicationSpecific,
+                platforms: vec!["macos".to_string(), "windows".to_string(), "linux".to_string()],
+                applications: vscode_apps.clone(),
+            });
+        }
+
+        // Emacs uses Ctrl on every platform
+        for (key, name, description) in [
+            ("S", "Save Buffer", "Save the current buffer"),
+            ("F", "Find File", "Open a file"),
+            ("C", "Quit Emacs", "Exit Emacs"),
+        ] {
+            chords.push(ChordDefinition {
+                sequence: vec![
+                    KeyboardShortcut {
+                        modifiers: ctrl.clone(),
+                        key: "X".to_string(),
+                        display: "Ctrl+X".to_string(),
+                    },
+                    KeyboardShortcut {
+                        modifiers: ctrl.clone(),
+                        key: key.to_string(),
+                        display: format!("Ctrl+{}", key),
+                    },
+                ],
+                name: name.to_string(),
+                description: description.to_string(),
+                command_type: CommandType::ApplicationSpecific,
+                platforms: vec!["macos".to_string(), "windows".to_string(), "linux".to_string()],
+                applications: Some(vec!["Emacs".to_string()]),
+            });
+        }
+
+        chords
+    }
+
+    /// Merge user-defined shortcuts into the database
+    ///
+    /// Entries are stored as `CommandType::Custom`. A custom entry replaces an
+    /// existing one for the same keys and application scope.
+    pub fn load_custom(&mut self, definitions: Vec<CommandDefinition>) {
+        for mut definition in definitions {
+            definition.command_type = CommandType::Custom;
+
+            let key = Self::shortcut_to_key(&definition.shortcut);
+            let entries = self.shortcuts.entry(key).or_default();
+            entries.retain(|existing| existing.applications != definition.applications);
+            entries.push(definition);
+        }
+    }
+
+    /// Find the definition for a shortcut, preferring one scoped to `app_name`
+    pub fn lookup(&self, shortcut: &KeyboardShortcut, app_name: Option<&str>) -> Option<&CommandDefinition> {
+        let key = Self::shortcut_to_key(shortcut);
+        let entries = self.shortcuts.get(&key)?;
+
+        app_name
+            .and_then(|app| entries.iter().find(|d| d.applies_to(app)))
+            .or_else(|| entries.iter().find(|d| d.applications.is_none()))
+    }
+
+    /// Find a chord whose full sequence ends the given keys, preferring app-specific ones
+    ///
+    /// `recent_keys` holds the most recent key combinations, oldest first; `None`
+    /// marks a key press that can't be part of a chord.
+    pub fn lookup_chord(&self, recent_keys: &[Option<String>], app_name: &str) -> Option<&ChordDefinition> {
+        let matches = |chord: &&ChordDefinition| {
+            let keys = chord.keys();
+            recent_keys.len() >= keys.len()
+                && recent_keys[recent_keys.len() - keys.len()..]
+                    .iter()
+                    .zip(&keys)
+                    .all(|(recent, key)| recent.as_ref() == Some(key))
+        };
+
+        let applicable = || self.chords.iter().filter(|c| c.applies_to(app_name));
+        applicable()
+            .filter(|c| c.applications.is_some())
+            .find(matches)
+            .or_else(|| applicable().filter(|c| c.applications.is_none()).find(matches))
+    }
+
+    /// Whether the given keys end with an incomplete chord for this app
+    pub fn is_chord_prefix(&self, recent_keys: &[Option<String>], app_name: &str) -> bool {
+        self.chords
+            .iter()
+            .filter(|chord| chord.applies_to(app_name))
+            .any(|chord| {
+                let keys = chord.keys();
+                (1..keys.len()).any(|len| {
+                    recent_keys.len() >= len
+                        && recent_keys[recent_keys.len() - len..]
+                            .iter()
+                            .zip(&keys[..len])
+                            .all(|(recent, key)| recent.as_ref() == Some(key))
+                })
+            })
+    }
+
+    fn shortcut_to_key(shortcut: &KeyboardShortcut) -> String {
         let mut parts = Vec::new();
 
         if shortcut.modifiers.ctrl {
@@ -648,6 +914,8 @@ pub struct CommandAnalyzer {
     command_database: CommandDatabase,
     keyboard_buffer: VecDeque<KeyboardEvent>,
     buffer_duration: Duration,
+    // Key presses held back because they may start a chord
+    pending_chord: Vec<KeyboardEvent>,
 }
 
 impl CommandAnalyzer {
@@ -656,9 +924,15 @@ impl CommandAnalyzer {
             command_database: CommandDatabase::new(),
             keyboard_buffer: VecDeque::new(),
             buffer_duration: Duration::from_millis(500),
+            pending_chord: Vec::new(),
         }
     }
 
+    /// Recognize user-defined shortcuts in addition to the built-in ones
+    pub fn load_custom(&mut self, definitions: Vec<CommandDefinition>) {
+        self.command_database.load_custom(definitions);
+    }
+
     pub fn analyze_events(&mut self, events: Vec<KeyboardEvent>) -> Vec<Command> {
         let mut commands = Vec::new();
 
@@ -674,8 +948,27 @@ impl CommandAnalyzer {
             // Remove old events from buffer
             self.clean_buffer();
 
-            // Check if this forms a command
-            if let Some(command) = self.detect_command(&event) {
+            // Chords take precedence over their individual steps
+            if let Some(command) = self.detect_chord(&event) {
+                self.pending_chord.clear();
+                self.keyboard_buffer.clear();
+                commands.push(command);
+                continue;
+            }
+
+            // The held-back steps didn't complete a chord; report them on their own
+            let still_pending = self.is_chord_prefix(&event);
+            if !still_pending {
+                for pending in std::mem::take(&mut self.pending_chord) {
+                    if let Some(command) = self.detect_command(&pending) {
+                        commands.push(command);
+                    }
+                }
+            }
+
+            if still_pending {
+                self.pending_chord.push(event);
+            } else if let Some(command) = self.detect_command(&event) {
                 commands.push(command);
             }
         }
@@ -683,8 +976,24 @@ impl CommandAnalyzer {
         commands
     }
 
+    /// Report held-back chord steps on their own
+    ///
+    /// For when no more keys are coming, such as when recording stops or the
+    /// session changes, so a trailing chord prefix isn't dropped.
+    pub fn flush_pending(&mut self) -> Vec<Command> {
+        self.keyboard_buffer.clear();
+        std::mem::take(&mut self.pending_chord)
+            .iter()
+            .filter_map(|pending| self.detect_command(pending))
+            .collect()
+    }
+
+    // The window is measured from the newest event so batches of older
+    // events are analyzed the same way as live ones
     fn clean_buffer(&mut self) {
-        let now = chrono::Utc::now().timestamp_millis();
+        let Some(now) = self.keyboard_buffer.back().map(|e| e.timestamp) else {
+            return;
+        };
 
         while let Some(front) = self.keyboard_buffer.front() {
             if now - front.timestamp > self.buffer_duration.as_millis() as i64 {
@@ -715,7 +1024,10 @@ impl CommandAnalyzer {
         };
 
         // Lookup in command database
-        if let Some(definition) = self.command_database.lookup(&shortcut) {
+        if let Some(definition) = self
+            .command_database
+            .lookup(&shortcut, Some(&event.app_context.app_name))
+        {
             return Some(Command {
                 id: Uuid::new_v4(),
                 timestamp: event.timestamp,
@@ -737,6 +1049,66 @@ impl CommandAnalyzer {
         })
     }
 
+    /// Key combination for a buffered event, or None if it can't be a chord step
+    fn chord_key(&self, event: &KeyboardEvent) -> Option<String> {
+        if !self.has_any_modifier(&event.modifiers) || event.is_sensitive {
+            return None;
+        }
+
+        let key = event.key_char?.to_string().to_uppercase();
+        Some(CommandDatabase::shortcut_to_key(&KeyboardShortcut {
+            modifiers: event.modifiers.clone(),
+            key,
+            display: String::new(),
+        }))
+    }
+
+    fn recent_chord_keys(&self) -> Vec<Option<String>> {
+        self.keyboard_buffer.iter().map(|e| self.chord_key(e)).collect()
+    }
+
+    fn is_chord_prefix(&self, event: &KeyboardEvent) -> bool {
+        self.chord_key(event).is_some()
+            && self
+                .command_database
+                .is_chord_prefix(&self.recent_chord_keys(), &event.app_context.app_name)
+    }
+
+    fn detect_chord(&self, event: &KeyboardEvent) -> Option<Command> {
+        let recent_keys = self.recent_chord_keys();
+        let chord = self
+            .command_database
+            .lookup_chord(&recent_keys, &event.app_context.app_name)?;
+
+        let steps: Vec<&KeyboardEvent> = self
+            .keyboard_buffer
+            .iter()
+            .skip(self.keyboard_buffer.len() - chord.sequence.len())
+            .collect();
+
+        let display = steps
+            .iter()
+            .map(|step| {
+                let key = step.key_char.map(|c| c.to_uppercase().to_string()).unwrap_or_default();
+                self.format_shortcut(&step.modifiers, &key)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Some(Command {
+            id: Uuid::new_v4(),
+            timestamp: steps[0].timestamp,
+            shortcut: KeyboardShortcut {
+                modifiers: event.modifiers.clone(),
+                key: event.key_char?.to_string().to_uppercase(),
+                display,
+            },
+            command_type: chord.command_type.clone(),
+            app_name: event.app_context.app_name.clone(),
+            description: chord.description.clone(),
+        })
+    }
+
     fn has_any_modifier(&self, modifiers: &ModifierState) -> bool {
         modifiers.ctrl || modifiers.alt || modifiers.shift || modifiers.meta
     }
@@ -849,8 +1221,55 @@ impl CommandAnalyzer {
         session_id: Uuid,
         command: Command,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let pool = db.pool();
+        Self::insert_command(db.pool(), session_id, &command).await?;
+        Ok(())
+    }
+
+    /// Analyze keyboard events and store the detected commands in one transaction
+    pub async fn analyze_and_store(
+        &mut self,
+        db: &Arc<Database>,
+        session_id: Uuid,
+        events: Vec<KeyboardEvent>,
+    ) -> Result<Vec<Command>, Box<dyn std::error::Error + Send + Sync>> {
+        let commands = self.analyze_events(events);
+        Self::store_commands(db, session_id, &commands).await?;
+        Ok(commands)
+    }
 
+    /// Flush any held-back chord steps and store them as commands
+    pub async fn flush_and_store(
+        &mut self,
+        db: &Arc<Database>,
+        session_id: Uuid,
+    ) -> Result<Vec<Command>, Box<dyn std::error::Error + Send + Sync>> {
+        let commands = self.flush_pending();
+        Self::store_commands(db, session_id, &commands).await?;
+        Ok(commands)
+    }
+
+    async fn store_commands(
+        db: &Arc<Database>,
+        session_id: Uuid,
+        commands: &[Command],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = db.pool().begin().await?;
+        for command in commands {
+            Self::insert_command(&mut *tx, session_id, command).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn insert_command<'e, E>(executor: E, session_id: Uuid, command: &Command) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    {
         sqlx::query(
             r#"
             INSERT INTO commands (id, session_id, timestamp, shortcut, command_type, app_name, description)
@@ -860,11 +1279,11 @@ impl CommandAnalyzer {
         .bind(command.id.to_string())
         .bind(session_id.to_string())
         .bind(command.timestamp)
-        .bind(command.shortcut.display)
+        .bind(&command.shortcut.display)
         .bind(command.command_type.to_string())
-        .bind(command.app_name)
-        .bind(command.description)
-        .execute(pool)
+        .bind(&command.app_name)
+        .bind(&command.description)
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -912,7 +1331,7 @@ mod tests {
                 display: "⌘C".to_string(),
             };
 
-            let definition = db.lookup(&shortcut);
+            let definition = db.lookup(&shortcut, None);
             assert!(definition.is_some());
             assert_eq!(definition.unwrap().name, "Copy");
         }
@@ -930,12 +1349,186 @@ mod tests {
                 display: "Ctrl+C".to_string(),
             };
 
-            let definition = db.lookup(&shortcut);
+            let definition = db.lookup(&shortcut, None);
             assert!(definition.is_some());
             assert_eq!(definition.unwrap().name, "Copy");
         }
     }
 
+    #[test]
+    fn test_app_specific_lookup() {
+        let db = CommandDatabase::new();
+
+        #[cfg(target_os = "macos")]
+        let (file_manager, modifiers) = (
+            "Finder",
+            ModifierState {
+                meta: true,
+                shift: true,
+                ctrl: false,
+                alt: false,
+            },
+        );
+        #[cfg(not(target_os = "macos"))]
+        let (file_manager, modifiers) = (
+            "Explorer",
+            ModifierState {
+                meta: false,
+                shift: true,
+                ctrl: true,
+                alt: false,
+            },
+        );
+
+        let shortcut = KeyboardShortcut {
+            modifiers,
+            key: "N".to_string(),
+            display: String::new(),
+        };
+
+        assert_eq!(db.lookup(&shortcut, Some(file_manager)).unwrap().name, "New Folder");
+        assert_eq!(db.lookup(&shortcut, Some("Google Chrome")).unwrap().name, "New Incognito Window");
+        assert!(db.lookup(&shortcut, Some("TextEdit")).is_none());
+    }
+
+    #[test]
+    fn test_custom_app_shortcut() {
+        let mut db = CommandDatabase::new();
+
+        let shortcut = KeyboardShortcut {
+            modifiers: ModifierState {
+                meta: true,
+                shift: true,
+                ctrl: false,
+                alt: false,
+            },
+            key: "P".to_string(),
+            display: "⌘⇧P".to_string(),
+        };
+
+        db.load_custom(vec![CommandDefinition {
+            shortcut: shortcut.clone(),
+            name: "Command Palette".to_string(),
+            description: "Open the command palette".to_string(),
+            command_type: CommandType::ApplicationSpecific,
+            platforms: vec!["macos".to_string()],
+            applications: Some(vec!["Code".to_string()]),
+        }]);
+
+        let definition = db.lookup(&shortcut, Some("Code")).unwrap();
+        assert_eq!(definition.command_type, CommandType::Custom);
+        assert_eq!(definition.description, "Open the command palette");
+
+        // Scoped to Code only
+        assert!(db.lookup(&shortcut, Some("Safari")).is_none());
+        assert!(db.lookup(&shortcut, None).is_none());
+    }
+
+    fn ctrl_key_event(key: char, app_name: &str, timestamp: i64) -> KeyboardEvent {
+        KeyboardEvent {
+            timestamp,
+            event_type: KeyEventType::KeyDown,
+            key_code: 0,
+            key_char: Some(key),
+            modifiers: ModifierState {
+                meta: false,
+                shift: false,
+                ctrl: true,
+                alt: false,
+            },
+            app_context: AppContext::new(app_name.to_string(), "Test".to_string(), 1234),
+            ui_element: None,
+            is_sensitive: false,
+        }
+    }
+
+    #[test]
+    fn test_chord_within_window() {
+        let mut analyzer = CommandAnalyzer::new();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let commands = analyzer.analyze_events(vec![
+            ctrl_key_event('x', "Emacs", now),
+            ctrl_key_event('s', "Emacs", now + 200),
+        ]);
+
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].description, "Save the current buffer");
+        assert_eq!(commands[0].timestamp, now);
+        assert!(matches!(commands[0].command_type, CommandType::ApplicationSpecific));
+    }
+
+    #[test]
+    fn test_chord_second_key_too_late() {
+        let mut analyzer = CommandAnalyzer::new();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let commands = analyzer.analyze_events(vec![
+            ctrl_key_event('x', "Emacs", now),
+            ctrl_key_event('s', "Emacs", now + 2000),
+        ]);
+
+        // Both keys are reported individually
+        assert_eq!(commands.len(), 2);
+        assert!(commands.iter().all(|c| c.description != "Save the current buffer"));
+    }
+
+    #[test]
+    fn test_trailing_chord_prefix_is_flushed() {
+        let mut analyzer = CommandAnalyzer::new();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        // The batch ends while Ctrl+X may still start a chord
+        let commands = analyzer.analyze_events(vec![ctrl_key_event('x', "Emacs", now)]);
+        assert!(commands.is_empty());
+
+        let flushed = analyzer.flush_pending();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].timestamp, now);
+        assert_eq!(flushed[0].shortcut.key, "X");
+
+        // Nothing is held any more, so a later key doesn't complete the old chord
+        assert!(analyzer.flush_pending().is_empty());
+        let commands = analyzer.analyze_events(vec![ctrl_key_event('s', "Emacs", now + 100)]);
+        assert!(commands.iter().all(|c| c.description != "Save the current buffer"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_and_store() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.expect("Failed to run migrations");
+
+        let session_id = Uuid::new_v4();
+        let now = chrono::Utc::now().timestamp_millis();
+        db.create_session(&session_id.to_string(), now, "test-device")
+            .await
+            .expect("Failed to create session");
+
+        let mut analyzer = CommandAnalyzer::new();
+        let commands = analyzer
+            .analyze_and_store(
+                &db,
+                session_id,
+                vec![
+                    ctrl_key_event('x', "Emacs", now),
+                    ctrl_key_event('s', "Emacs", now + 100),
+                ],
+            )
+            .await
+            .expect("Failed to analyze and store");
+        assert_eq!(commands.len(), 1);
+
+        let stats = CommandAnalyzer::get_command_stats(&db, Some(session_id))
+            .await
+            .expect("Failed to get command stats");
+        assert_eq!(stats.total_shortcuts, 1);
+    }
+
     #[test]
     fn test_format_shortcut() {
         let analyzer = CommandAnalyzer::new();
@@ -1,12 +1,44 @@
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Weekday};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::core::auto_record::AutoRecordConfig;
+use crate::core::command_analyzer::CommandDefinition;
+use crate::core::model_manager::ModelKind;
+
+/// Longest retention period accepted for any data type (about ten years)
+const MAX_RETENTION_DAYS: u32 = 3650;
+
+/// Retention keys covering keyboard and mouse recording
+const INPUT_RETENTION_KEYS: [&str; 2] = ["keyboard", "mouse"];
+
+/// Visually distinct colors cycled through for apps without an override
+const APP_PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f",
+    "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac",
+];
+
+/// Intended working hours for one weekday, as local hours [start_hour, end_hour)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WorkHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+/// Where to download a model from and the checksum it must match
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelSource {
+    pub kind: ModelKind,
+    pub url: String,
+    /// Hex-encoded SHA-256 of the complete file
+    pub sha256: String,
+    pub size_bytes: u64,
+}
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Config {
-    /// Where recordings are saved
-    pub storage_path: PathBuf,
     /// How long to keep different data types (in days)
     pub retention_days: HashMap<String, u32>,
     /// Recording quality: "High", "Medium", or "Low"
@@ -33,18 +65,74 @@ pub struct Config {
     pub hardware_acceleration: bool,
     /// Target FPS for video encoding
     pub target_fps: u32,
+    /// Work hours per weekday ("mon".."sun"); days not listed are off
+    #[serde(default = "default_work_hours")]
+    pub work_hours: HashMap<String, WorkHours>,
+    /// Model download manifest entries, by name; these override built-in entries
+    #[serde(default)]
+    pub model_manifest: HashMap<String, ModelSource>,
+    /// User-defined keyboard shortcuts recognized by the command analyzer
+    #[serde(default)]
+    pub custom_shortcuts: Vec<CommandDefinition>,
+    /// Apps whose focus starts and stops screen recording automatically
+    #[serde(default)]
+    pub auto_record: AutoRecordConfig,
+    /// Drop typed characters in password and other sensitive fields
+    #[serde(default = "default_redact_sensitive_input")]
+    pub redact_sensitive_input: bool,
+    /// Mouse moves closer together than this keep only the latest (0 = keep all)
+    #[serde(default = "default_mouse_sample_interval_ms")]
+    pub mouse_sample_interval_ms: u64,
+    /// How often buffered input events are written out while recording
+    #[serde(default = "default_input_flush_interval_secs")]
+    pub input_flush_interval_secs: u64,
+    /// Identifier stamped on sessions, taken from the platform on first run
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Timeline colors chosen by the user, by app name (any CSS color)
+    #[serde(default)]
+    pub color_overrides: HashMap<String, String>,
+    /// Where recordings and the database live, instead of the platform default
+    #[serde(default)]
+    pub data_directory: Option<PathBuf>,
+    /// Cap on recording files; the oldest sessions are deleted past it (None = unlimited)
+    #[serde(default)]
+    pub max_storage_bytes: Option<u64>,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        let home = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE"))
-            .unwrap_or_else(|_| ".".to_string());
+fn default_redact_sensitive_input() -> bool {
+    true
+}
+
+fn default_mouse_sample_interval_ms() -> u64 {
+    50
+}
 
-        let mut storage_path = PathBuf::from(home.clone());
-        storage_path.push(".observer_data");
-        storage_path.push("recordings");
+fn default_input_flush_interval_secs() -> u64 {
+    10
+}
 
+fn default_work_hours() -> HashMap<String, WorkHours> {
+    ["mon", "tue", "wed", "thu", "fri"]
+        .iter()
+        .map(|day| (day.to_string(), WorkHours { start_hour: 9, end_hour: 17 }))
+        .collect()
+}
+
+fn weekday_key(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "mon",
+        Weekday::Tue => "tue",
+        Weekday::Wed => "wed",
+        Weekday::Thu => "thu",
+        Weekday::Fri => "fri",
+        Weekday::Sat => "sat",
+        Weekday::Sun => "sun",
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
         let mut retention_days = HashMap::new();
         retention_days.insert("screen".to_string(), 30);
         retention_days.insert("ocr".to_string(), 90);
@@ -52,7 +140,6 @@ impl Default for Config {
         retention_days.insert("mouse".to_string(), 7);
 
         Self {
-            storage_path,
             retention_days,
             recording_quality: "Medium".to_string(),
             auto_start: false,
@@ -66,6 +153,17 @@ impl Default for Config {
             video_quality: "Medium".to_string(),
             hardware_acceleration: true,
             target_fps: 15,
+            work_hours: default_work_hours(),
+            model_manifest: HashMap::new(),
+            custom_shortcuts: Vec::new(),
+            auto_record: AutoRecordConfig::default(),
+            redact_sensitive_input: default_redact_sensitive_input(),
+            mouse_sample_interval_ms: default_mouse_sample_interval_ms(),
+            input_flush_interval_secs: default_input_flush_interval_secs(),
+            device_id: None,
+            color_overrides: HashMap::new(),
+            data_directory: None,
+            max_storage_bytes: None,
         }
     }
 }
@@ -73,26 +171,48 @@ impl Default for Config {
 impl Config {
     /// Load configuration from file, creating with defaults if it doesn't exist
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let config_path = Self::get_config_path()?;
+        Self::load_from(&Self::get_config_path()?)
+    }
 
+    /// Load configuration from a specific file, creating it with defaults if missing
+    pub fn load_from(config_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         if config_path.exists() {
-            let contents = std::fs::read_to_string(&config_path)?;
+            let contents = std::fs::read_to_string(config_path)?;
             let config: Config = serde_json::from_str(&contents)?;
             config.validate()?;
             Ok(config)
         } else {
             // Create default config and save it
             let config = Self::default();
-            config.save()?;
+            config.save_to(config_path)?;
             Ok(config)
         }
     }
 
+    /// The stored device id, adopting `platform_id` (or a random id) if none is stored yet
+    ///
+    /// Once stored the id never changes, even if the platform later reports a
+    /// different one. Returns the id and whether the config needs saving.
+    pub fn ensure_device_id(&mut self, platform_id: impl FnOnce() -> Option<String>) -> (String, bool) {
+        if let Some(id) = self.device_id.as_ref().filter(|id| !id.is_empty()) {
+            return (id.clone(), false);
+        }
+
+        let id = platform_id()
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.device_id = Some(id.clone());
+        (id, true)
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        self.validate()?;
+        self.save_to(&Self::get_config_path()?)
+    }
 
-        let config_path = Self::get_config_path()?;
+    /// Save configuration to a specific file; invalid configurations are never written
+    pub fn save_to(&self, config_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        self.validate()?;
 
         // Create parent directories if they don't exist
         if let Some(parent) = config_path.parent() {
@@ -101,7 +221,7 @@ impl Config {
 
         // Serialize and write to file with pretty formatting
         let contents = serde_json::to_string_pretty(self)?;
-        std::fs::write(&config_path, contents)?;
+        std::fs::write(config_path, contents)?;
 
         Ok(())
     }
@@ -166,10 +286,17 @@ impl Config {
 
         // Validate retention days
         for (data_type, days) in &self.retention_days {
-            if *days == 0 || *days > 3650 {
+            if *days == 0 && INPUT_RETENTION_KEYS.contains(&data_type.as_str()) {
                 return Err(format!(
-                    "Invalid retention days for {}: {}. Must be between 1 and 3650",
-                    data_type, days
+                    "Invalid retention days for {}: 0 would delete input events as soon as they are recorded",
+                    data_type
+                )
+                .into());
+            }
+            if *days == 0 || *days > MAX_RETENTION_DAYS {
+                return Err(format!(
+                    "Invalid retention days for {}: {}. Must be between 1 and {}",
+                    data_type, days, MAX_RETENTION_DAYS
                 )
                 .into());
             }
@@ -198,6 +325,159 @@ impl Config {
             return Err("OCR languages cannot be empty".into());
         }
 
+        // Validate work hours
+        let valid_days = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+        for (day, hours) in &self.work_hours {
+            if !valid_days.contains(&day.as_str()) {
+                return Err(format!(
+                    "Invalid work hours day: {}. Must be one of: mon, tue, wed, thu, fri, sat, sun",
+                    day
+                )
+                .into());
+            }
+            if hours.start_hour >= hours.end_hour || hours.end_hour > 24 {
+                return Err(format!(
+                    "Invalid work hours for {}: {}-{}. Start must be before end, within 0-24",
+                    day, hours.start_hour, hours.end_hour
+                )
+                .into());
+            }
+        }
+
+        // Validate model manifest
+        for (name, source) in &self.model_manifest {
+            if source.url.is_empty() {
+                return Err(format!("Invalid model manifest entry for {}: URL cannot be empty", name).into());
+            }
+            if source.sha256.len() != 64 || !source.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!(
+                    "Invalid model manifest entry for {}: sha256 must be 64 hex characters",
+                    name
+                )
+                .into());
+            }
+        }
+
+        // Validate custom shortcuts
+        for definition in &self.custom_shortcuts {
+            if definition.shortcut.key.is_empty() || definition.shortcut.modifiers.is_empty() {
+                return Err(format!(
+                    "Invalid custom shortcut {}: needs a key and at least one modifier",
+                    definition.name
+                )
+                .into());
+            }
+        }
+
+        // Validate color overrides
+        if self.color_overrides.values().any(|color| color.trim().is_empty()) {
+            return Err("App color overrides cannot be empty".into());
+        }
+
+        // Validate data directory override
+        if let Some(dir) = &self.data_directory {
+            if !dir.is_absolute() {
+                return Err(format!("Invalid data directory: {}. Must be an absolute path", dir.display()).into());
+            }
+        }
+
+        // Validate storage quota
+        if self.max_storage_bytes == Some(0) {
+            return Err("Invalid storage quota: 0. Leave it unset for no limit".into());
+        }
+
+        // Validate auto-record settings
+        if self.auto_record.trigger_apps.iter().any(|app| app.trim().is_empty()) {
+            return Err("Auto-record trigger apps cannot be empty names".into());
+        }
+        if self.auto_record.stop_after_secs == 0 {
+            return Err("Invalid auto-record stop delay: 0. Must be at least 1 second".into());
+        }
+
+        self.validate_combinations()
+    }
+
+    /// Reject settings that are each valid but contradict one another
+    fn validate_combinations(&self) -> Result<(), Box<dyn std::error::Error>> {
+        // A flush empties the buffer moves are coalesced in, so sampling can't span one
+        if self.mouse_sample_interval_ms > 0
+            && self.mouse_sample_interval_ms >= self.input_flush_interval_secs.saturating_mul(1000)
+        {
+            return Err(format!(
+                "Invalid mouse sample interval: {}ms. Must be shorter than the input flush interval of {}s",
+                self.mouse_sample_interval_ms, self.input_flush_interval_secs
+            )
+            .into());
+        }
+
+        // Otherwise a glance away ends the recording, and returning waits out the debounce
+        if !self.auto_record.trigger_apps.is_empty()
+            && self.auto_record.stop_after_secs < self.auto_record.focus_debounce_secs
+        {
+            return Err(format!(
+                "Invalid auto-record stop delay: {}s. Must be at least the {}s focus debounce",
+                self.auto_record.stop_after_secs, self.auto_record.focus_debounce_secs
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a moment falls inside the configured work hours
+    pub fn is_work_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        self.work_hours
+            .get(weekday_key(time.weekday()))
+            .map(|hours| time.hour() >= hours.start_hour && time.hour() < hours.end_hour)
+            .unwrap_or(false)
+    }
+
+    /// Timeline color for an app
+    ///
+    /// An override wins. Otherwise `order` - the app's position among a
+    /// session's apps by first use - picks a palette entry, so apps in the same
+    /// session get distinct colors until the palette wraps.
+    pub fn app_color(&self, app_name: &str, order: usize) -> String {
+        self.color_overrides
+            .get(app_name)
+            .cloned()
+            .unwrap_or_else(|| APP_PALETTE[order % APP_PALETTE.len()].to_string())
+    }
+
+    /// Data directory to use, given the platform's default
+    pub fn data_dir(&self, platform_default: PathBuf) -> PathBuf {
+        self.data_directory.clone().unwrap_or(platform_default)
+    }
+
+    pub fn recordings_path(&self, platform_default: PathBuf) -> PathBuf {
+        self.data_dir(platform_default).join("recordings")
+    }
+
+    /// Database file to use, given where it lives without an override
+    ///
+    /// The default is passed separately because the database predates the
+    /// platform data directory and, on Windows, sits outside it.
+    pub fn database_path(&self, default_path: PathBuf) -> PathBuf {
+        match &self.data_directory {
+            Some(dir) => dir.join("database").join("observer.db"),
+            None => default_path,
+        }
+    }
+
+    /// Create the data directory override if missing and check it can be written
+    pub fn prepare_data_directory(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(dir) = &self.data_directory else {
+            return Ok(());
+        };
+
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create data directory {}: {}", dir.display(), e))?;
+
+        let probe = dir.join(".write_test");
+        std::fs::write(&probe, b"")
+            .map_err(|e| format!("Data directory {} is not writable: {}", dir.display(), e))?;
+        let _ = std::fs::remove_file(&probe);
+
         Ok(())
     }
 
@@ -209,7 +489,7 @@ impl Config {
     }
 
     /// Get the configuration file path
-    fn get_config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    pub(crate) fn get_config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
         let home = std::env::var("HOME")
             .or_else(|_| std::env::var("USERPROFILE"))
             .map_err(|_| "Could not determine home directory")?;
@@ -297,6 +577,94 @@ mod tests {
         assert_eq!(config, deserialized);
     }
 
+    #[test]
+    fn test_work_hours() {
+        let mut config = Config::default();
+
+        // Monday 2024-01-01 10:00 and 18:00, Saturday 2024-01-06 10:00
+        let monday_morning = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let monday_evening = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 18, 0, 0).unwrap();
+        let saturday = chrono::Utc.with_ymd_and_hms(2024, 1, 6, 10, 0, 0).unwrap();
+
+        assert!(config.is_work_time(&monday_morning));
+        assert!(!config.is_work_time(&monday_evening));
+        assert!(!config.is_work_time(&saturday));
+
+        // Invalid ranges are rejected
+        config.work_hours.insert("sat".to_string(), WorkHours { start_hour: 12, end_hour: 10 });
+        assert!(config.validate().is_err());
+        config.work_hours.insert("sat".to_string(), WorkHours { start_hour: 10, end_hour: 12 });
+        assert!(config.validate().is_ok());
+        assert!(config.is_work_time(&saturday));
+
+        config.work_hours.insert("someday".to_string(), WorkHours { start_hour: 9, end_hour: 17 });
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_input_retention_validation() {
+        let mut config = Config::default();
+
+        config.retention_days.insert("keyboard".to_string(), 0);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("keyboard"));
+        assert!(err.contains("as soon as they are recorded"));
+
+        config.retention_days.insert("keyboard".to_string(), 30);
+        config.retention_days.insert("mouse".to_string(), MAX_RETENTION_DAYS + 1);
+        assert!(config.validate().is_err());
+
+        config.retention_days.insert("mouse".to_string(), MAX_RETENTION_DAYS);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_contradictory_settings_rejected() {
+        let mut config = Config::default();
+
+        config.input_flush_interval_secs = 1;
+        config.mouse_sample_interval_ms = 1_000;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("mouse sample interval"));
+        // Keeping every move needs no flush ordering
+        config.mouse_sample_interval_ms = 0;
+        assert!(config.validate().is_ok());
+
+        config.auto_record.focus_debounce_secs = 30;
+        config.auto_record.stop_after_secs = 10;
+        // Harmless while auto-record has nothing to react to
+        assert!(config.validate().is_ok());
+        config.auto_record.trigger_apps = vec!["Figma".to_string()];
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("focus debounce"));
+        config.auto_record.stop_after_secs = 30;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_retention_not_saved() {
+        // Separate directory so test_reset_config's cleanup cannot race with this test
+        let dir = std::env::temp_dir().join("observer_test_config_retention");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("settings.json");
+
+        let saved = Config::load_from(&path).unwrap();
+        assert_eq!(saved, Config::default());
+
+        let mut invalid = saved.clone();
+        invalid.retention_days.insert("keyboard".to_string(), 0);
+        assert!(invalid.save_to(&path).is_err());
+
+        let mut too_long = saved.clone();
+        too_long.retention_days.insert("screen".to_string(), 5000);
+        assert!(too_long.save_to(&path).is_err());
+
+        // The file on disk still holds the last valid configuration
+        assert_eq!(Config::load_from(&path).unwrap(), saved);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_reset_config() {
         cleanup_test_config();
@@ -306,4 +674,47 @@ mod tests {
 
         cleanup_test_config();
     }
+
+    #[test]
+    fn test_app_color_overrides_and_palette() {
+        let mut config = Config::default();
+        config.color_overrides.insert("Slack".to_string(), "#123456".to_string());
+
+        assert_eq!(config.app_color("Slack", 0), "#123456");
+
+        // Apps without overrides take distinct entries by their order in the session
+        let first = config.app_color("Terminal", 0);
+        let second = config.app_color("Safari", 1);
+        assert_ne!(first, second);
+        assert_eq!(config.app_color("Terminal", 0), first);
+
+        config.color_overrides.insert("Safari".to_string(), " ".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_data_directory_override() {
+        let platform_default = PathBuf::from("/home/user/.observer_data");
+        let default_database = PathBuf::from("/home/user/.legacy/observer.db");
+        let mut config = Config::default();
+        assert_eq!(config.recordings_path(platform_default.clone()), platform_default.join("recordings"));
+        // Without an override the database stays where earlier versions put it
+        assert_eq!(config.database_path(default_database.clone()), default_database);
+
+        let dir = std::env::temp_dir().join("observer_test_data_dir").join("external");
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+        config.data_directory = Some(dir.clone());
+
+        assert_eq!(config.recordings_path(platform_default.clone()), dir.join("recordings"));
+        assert_eq!(config.database_path(default_database), dir.join("database").join("observer.db"));
+
+        // Missing directories are created
+        config.prepare_data_directory().unwrap();
+        assert!(dir.is_dir());
+
+        config.data_directory = Some(PathBuf::from("relative/data"));
+        assert!(config.validate().is_err());
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
 }
@@ -14,6 +14,8 @@ pub enum Feature {
     MouseRecording,
     CameraRecording,
     MicrophoneRecording,
+    ClipboardMonitoring,
+    PoseTracking,
 }
 
 impl Feature {
@@ -26,6 +28,8 @@ impl Feature {
             Feature::MouseRecording,
             Feature::CameraRecording,
             Feature::MicrophoneRecording,
+            Feature::ClipboardMonitoring,
+            Feature::PoseTracking,
         ]
     }
 
@@ -38,6 +42,8 @@ impl Feature {
             Feature::MouseRecording => "mouse_recording",
             Feature::CameraRecording => "camera_recording",
             Feature::MicrophoneRecording => "microphone_recording",
+            Feature::ClipboardMonitoring => "clipboard_monitoring",
+            Feature::PoseTracking => "pose_tracking",
         }
     }
 
@@ -50,6 +56,8 @@ impl Feature {
             "mouse_recording" => Ok(Feature::MouseRecording),
             "camera_recording" => Ok(Feature::CameraRecording),
             "microphone_recording" => Ok(Feature::MicrophoneRecording),
+            "clipboard_monitoring" => Ok(Feature::ClipboardMonitoring),
+            "pose_tracking" => Ok(Feature::PoseTracking),
             _ => Err(format!("Unknown feature: {}", s)),
         }
     }
@@ -61,6 +69,14 @@ impl fmt::Display for Feature {
     }
 }
 
+/// A recorded change to a feature's consent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentAuditEntry {
+    pub feature: Feature,
+    pub granted: bool,
+    pub changed_at: i64,
+}
+
 /// Manages user consent for various features
 #[derive(Clone)]
 pub struct ConsentManager {
@@ -125,36 +141,67 @@ impl ConsentManager {
 
     /// Grant consent for a feature
     pub async fn grant_consent(&self, feature: Feature) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_consent(feature, true).await
+    }
+
+    /// Revoke consent for a feature
+    pub async fn revoke_consent(&self, feature: Feature) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_consent(feature, false).await
+    }
+
+    /// Update consent and record the change in the audit log
+    async fn set_consent(&self, feature: Feature, granted: bool) -> Result<(), Box<dyn std::error::Error>> {
         let feature_name = feature.to_db_string();
         let timestamp = chrono::Utc::now().timestamp();
 
+        let mut tx = self.db.pool().begin().await?;
+
         sqlx::query(
-            "UPDATE consent_records SET consent_given = 1, last_updated = ? WHERE feature_name = ?"
+            "UPDATE consent_records SET consent_given = ?, last_updated = ? WHERE feature_name = ?"
         )
+        .bind(granted as i64)
         .bind(timestamp)
         .bind(feature_name)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
-        Ok(())
-    }
-
-    /// Revoke consent for a feature
-    pub async fn revoke_consent(&self, feature: Feature) -> Result<(), Box<dyn std::error::Error>> {
-        let feature_name = feature.to_db_string();
-        let timestamp = chrono::Utc::now().timestamp();
-
         sqlx::query(
-            "UPDATE consent_records SET consent_given = 0, last_updated = ? WHERE feature_name = ?"
+            "INSERT INTO consent_audit (id, feature_name, granted, changed_at)
+             VALUES (?, ?, ?, ?)"
         )
-        .bind(timestamp)
+        .bind(uuid::Uuid::new_v4().to_string())
         .bind(feature_name)
-        .execute(self.db.pool())
+        .bind(granted as i64)
+        .bind(timestamp)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         Ok(())
     }
 
+    /// Get every consent change for a feature, oldest first
+    pub async fn get_consent_history(&self, feature: Feature) -> Result<Vec<ConsentAuditEntry>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT granted, changed_at FROM consent_audit
+             WHERE feature_name = ?
+             ORDER BY changed_at ASC, rowid ASC"
+        )
+        .bind(feature.to_db_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(granted, changed_at)| ConsentAuditEntry {
+                feature,
+                granted: granted != 0,
+                changed_at,
+            })
+            .collect())
+    }
+
     /// Get all consents as a HashMap
     pub async fn get_all_consents(&self) -> Result<HashMap<Feature, bool>, Box<dyn std::error::Error>> {
         let mut consents = HashMap::new();
@@ -199,6 +246,10 @@ mod tests {
             Feature::from_string("os_activity").unwrap(),
             Feature::OsActivity
         );
+        assert_eq!(
+            Feature::from_string("pose_tracking").unwrap(),
+            Feature::PoseTracking
+        );
         assert!(Feature::from_string("invalid_feature").is_err());
     }
 
@@ -258,6 +309,29 @@ mod tests {
         assert!(!granted, "Consent should be revoked");
     }
 
+    #[tokio::test]
+    async fn test_consent_history() {
+        let db = setup_test_db().await;
+        let manager = ConsentManager::new(db).await.expect("Failed to create manager");
+
+        manager.grant_consent(Feature::MicrophoneRecording).await.unwrap();
+        manager.revoke_consent(Feature::MicrophoneRecording).await.unwrap();
+
+        let history = manager.get_consent_history(Feature::MicrophoneRecording)
+            .await
+            .expect("Failed to get consent history");
+
+        assert_eq!(history.len(), 2);
+        assert!(history[0].granted, "First entry should be the grant");
+        assert!(!history[1].granted, "Second entry should be the revoke");
+        assert!(history[0].changed_at <= history[1].changed_at);
+        assert!(history.iter().all(|entry| entry.feature == Feature::MicrophoneRecording));
+
+        // Other features have no history
+        let other = manager.get_consent_history(Feature::ScreenRecording).await.unwrap();
+        assert!(other.is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_all_consents() {
         let db = setup_test_db().await;
@@ -270,13 +344,15 @@ mod tests {
         // Get all consents
         let consents = manager.get_all_consents().await.expect("Failed to get consents");
 
-        assert_eq!(consents.len(), 6, "Should have 6 features");
+        assert_eq!(consents.len(), 8, "Should have 8 features");
         assert_eq!(consents.get(&Feature::ScreenRecording), Some(&true));
         assert_eq!(consents.get(&Feature::MouseRecording), Some(&true));
         assert_eq!(consents.get(&Feature::OsActivity), Some(&false));
         assert_eq!(consents.get(&Feature::KeyboardRecording), Some(&false));
         assert_eq!(consents.get(&Feature::CameraRecording), Some(&false));
         assert_eq!(consents.get(&Feature::MicrophoneRecording), Some(&false));
+        assert_eq!(consents.get(&Feature::ClipboardMonitoring), Some(&false));
+        assert_eq!(consents.get(&Feature::PoseTracking), Some(&false));
     }
 
     #[tokio::test]
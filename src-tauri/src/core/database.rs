@@ -1,6 +1,17 @@
+use serde::{Deserialize, Serialize};
 use sqlx::sqlite::{SqliteConnection, SqlitePool, SqlitePoolOptions};
 use sqlx::{migrate::MigrateDatabase, Sqlite};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Columns the old runtime-created recorder tables may lack: table, column, definition
+///
+/// keyboard_events was created either by the input recorder (no is_sensitive)
+/// or by the keyboard recorder (no ui_element), whichever started first.
+const LEGACY_RECORDER_COLUMNS: [(&str, &str, &str); 3] = [
+    ("keyboard_events", "ui_element", "TEXT"),
+    ("keyboard_events", "is_sensitive", "INTEGER NOT NULL DEFAULT 0"),
+    ("mouse_events", "ui_element", "TEXT"),
+];
 
 #[derive(Debug, Clone)]
 pub struct Database {
@@ -10,7 +21,11 @@ pub struct Database {
 impl Database {
     /// Initialize the database with migrations
     pub async fn init() -> Result<Self, Box<dyn std::error::Error>> {
-        let db_path = Self::get_db_path()?;
+        Self::init_at(&Self::get_db_path()?).await
+    }
+
+    /// Initialize the database file at `db_path` with migrations
+    pub async fn init_at(db_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         let db_url = format!("sqlite://{}", db_path.display());
 
         // Create database directory if it doesn't exist
@@ -49,14 +64,40 @@ impl Database {
 
     /// Run database migrations
     pub async fn run_migrations(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.add_missing_recorder_columns().await?;
+
         sqlx::migrate!("./migrations")
             .run(&self.pool)
             .await?;
         Ok(())
     }
 
-    /// Get the database file path
-    fn get_db_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    /// Bring recorder tables created by the old runtime schema up to the migrated layout
+    ///
+    /// Those tables predate the recorder migration, whose CREATE TABLE IF NOT
+    /// EXISTS leaves them as they are. Runs before the migrations so later ones
+    /// can rely on the columns; tables that don't exist yet are skipped.
+    async fn add_missing_recorder_columns(&self) -> Result<(), sqlx::Error> {
+        for (table, column, definition) in LEGACY_RECORDER_COLUMNS {
+            let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+                .bind(table)
+                .fetch_all(&self.pool)
+                .await?;
+
+            if columns.is_empty() || columns.iter().any(|name| name == column) {
+                continue;
+            }
+
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the database file path used when no data directory is configured
+    pub fn get_db_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
         let home = std::env::var("HOME")
             .or_else(|_| std::env::var("USERPROFILE"))
             .map_err(|_| "Could not determine home directory")?;
@@ -78,6 +119,7 @@ pub struct Session {
     pub end_timestamp: Option<i64>,
     pub device_id: String,
     pub created_at: i64,
+    pub session_type: Option<String>,
 }
 
 impl Database {
@@ -123,6 +165,17 @@ impl Database {
         Ok(())
     }
 
+    /// Update the stored classification of a session
+    pub async fn set_session_type(&self, id: &str, session_type: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET session_type = ? WHERE id = ?")
+            .bind(session_type)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Delete a session
     pub async fn delete_session(&self, id: &str) -> Result<(), sqlx::Error> {
         sqlx::query("DELETE FROM sessions WHERE id = ?")
@@ -152,6 +205,59 @@ impl Database {
         .fetch_all(&self.pool)
         .await
     }
+
+    /// Get one page of sessions within a time range, newest first
+    pub async fn get_sessions_in_range_page(&self, start: i64, end: i64, limit: u32, offset: u32) -> Result<Vec<Session>, sqlx::Error> {
+        sqlx::query_as::<_, Session>(
+            "SELECT * FROM sessions
+             WHERE start_timestamp >= ? AND start_timestamp <= ?
+             ORDER BY start_timestamp DESC, id
+             LIMIT ? OFFSET ?"
+        )
+        .bind(start)
+        .bind(end)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Count the rows each modality recorded for a session
+    pub async fn get_session_coverage(&self, session_id: &str) -> Result<SessionCoverage, sqlx::Error> {
+        Ok(SessionCoverage {
+            screen_segments: self.count_session_rows("video_segments", session_id).await?,
+            keyboard_events: self.count_session_rows("keyboard_events", session_id).await?,
+            mouse_events: self.count_session_rows("mouse_events", session_id).await?,
+            app_usage: self.count_session_rows("app_usage", session_id).await?,
+            clipboard_events: self.count_session_rows("clipboard_events", session_id).await?,
+            ocr_results: self.count_session_rows("ocr_results", session_id).await?,
+            commands: self.count_session_rows("commands", session_id).await?,
+        })
+    }
+
+    async fn count_session_rows(&self, table: &str, session_id: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE session_id = ?", table))
+            .bind(session_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+}
+
+/// Rows recorded per modality for one session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionCoverage {
+    pub screen_segments: i64,
+    pub keyboard_events: i64,
+    pub mouse_events: i64,
+    pub app_usage: i64,
+    pub clipboard_events: i64,
+    pub ocr_results: i64,
+    pub commands: i64,
+}
+
+/// `count` comma-separated `?` placeholders, for an `IN (...)` list
+pub(crate) fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
 }
 
 #[cfg(test)]
@@ -174,6 +280,121 @@ mod tests {
         db
     }
 
+    #[tokio::test]
+    async fn test_migrations_idempotent() {
+        let db = setup_test_db().await;
+
+        // A second run on an up-to-date database applies nothing
+        db.run_migrations().await.expect("Failed to re-run migrations");
+
+        let latest = sqlx::migrate!("./migrations")
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .expect("No migrations defined");
+        let (applied, version): (i64, i64) =
+            sqlx::query_as("SELECT COUNT(*), MAX(version) FROM _sqlx_migrations")
+                .fetch_one(db.pool())
+                .await
+                .expect("Failed to read migration history");
+
+        assert_eq!(applied as usize, sqlx::migrate!("./migrations").iter().count());
+        assert_eq!(version, latest);
+
+        // Recorder tables come from migrations rather than runtime setup
+        for table in ["app_usage", "clipboard_events", "keyboard_events", "mouse_events", "commands"] {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+            )
+            .bind(table)
+            .fetch_one(db.pool())
+            .await
+            .expect("Failed to query sqlite_master");
+            assert!(exists, "missing table {}", table);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrations_upgrade_legacy_recorder_tables() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        // An install from before migrations tracked the recorder tables: the first
+        // sessions schema, and keyboard_events as the keyboard recorder created it
+        sqlx::query(include_str!("../../migrations/20251001000001_create_sessions_table.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to create sessions table");
+        sqlx::query("INSERT INTO sessions (id, start_timestamp, device_id, created_at) VALUES ('session', 0, 'test-device', 0)")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert session");
+        sqlx::query(
+            "CREATE TABLE keyboard_events (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                event_type TEXT NOT NULL,
+                key_code INTEGER NOT NULL,
+                key_char TEXT,
+                modifiers TEXT NOT NULL,
+                app_name TEXT NOT NULL,
+                window_title TEXT NOT NULL,
+                process_id INTEGER NOT NULL,
+                is_sensitive INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (session_id) REFERENCES sessions(id)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create legacy table");
+        sqlx::query(
+            "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id, is_sensitive)
+             VALUES ('old', 'session', 1, 'key_down', 65, '{}', 'Editor', '', 1, 1)",
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to insert legacy row");
+
+        let db = Database { pool };
+        db.run_migrations().await.expect("Failed to run migrations");
+
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info('keyboard_events')")
+            .fetch_all(db.pool())
+            .await
+            .expect("Failed to read columns");
+        assert!(columns.contains(&"ui_element".to_string()));
+        assert!(columns.contains(&"is_sensitive".to_string()));
+
+        // Existing rows survive and the full insert used by the recorders now works
+        sqlx::query(
+            "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id, ui_element, is_sensitive)
+             VALUES ('new', 'session', 2, 'key_down', 66, '{}', 'Editor', '', 1, NULL, 0)",
+        )
+        .execute(db.pool())
+        .await
+        .expect("Failed to insert into upgraded table");
+
+        let sensitive: Vec<(String, i64)> = sqlx::query_as("SELECT id, is_sensitive FROM keyboard_events ORDER BY timestamp")
+            .fetch_all(db.pool())
+            .await
+            .expect("Failed to read rows");
+        assert_eq!(sensitive, vec![("old".to_string(), 1), ("new".to_string(), 0)]);
+
+        // The old redacted row no longer gives away which key it was
+        let old_key_code: i64 = sqlx::query_scalar("SELECT key_code FROM keyboard_events WHERE id = 'old'")
+            .fetch_one(db.pool())
+            .await
+            .expect("Failed to read key code");
+        assert_eq!(old_key_code, u32::MAX as i64);
+
+        // A second run finds nothing left to add
+        db.run_migrations().await.expect("Failed to re-run migrations");
+    }
+
     #[tokio::test]
     async fn test_create_and_get_session() {
         let db = setup_test_db().await;
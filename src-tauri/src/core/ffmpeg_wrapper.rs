@@ -4,8 +4,8 @@
 /// for video encoding operations.
 
 use crate::models::capture::{RawFrame, PixelFormat};
-use std::ffi::CString;
-use std::path::Path;
+use std::ffi::{c_void, CStr, CString};
+use std::path::{Path, PathBuf};
 use std::ptr;
 use thiserror::Error;
 
@@ -36,6 +36,12 @@ pub enum FFmpegError {
     SwscaleInitFailed,
     #[error("Color conversion failed")]
     ColorConversionFailed,
+    #[error("Failed to open input: {0}")]
+    InputOpenFailed(String),
+    #[error("No video stream found")]
+    VideoStreamNotFound,
+    #[error("Decoding error: {0}")]
+    DecodingError(String),
 }
 
 pub type Result<T> = std::result::Result<T, FFmpegError>;
@@ -63,6 +69,7 @@ impl FFmpegEncoder {
     /// * `fps` - Frames per second
     /// * `codec_name` - FFmpeg codec name (e.g., "h264_videotoolbox", "libx264")
     /// * `crf` - Constant Rate Factor for quality (lower = better quality)
+    /// * `keyframe_interval` - Frames between keyframes (GOP size)
     pub fn new(
         output_path: &Path,
         width: u32,
@@ -70,6 +77,7 @@ impl FFmpegEncoder {
         fps: u32,
         codec_name: &str,
         crf: u32,
+        keyframe_interval: u32,
     ) -> Result<Self> {
         unsafe {
             // Convert output path to C string
@@ -103,7 +111,7 @@ impl FFmpegEncoder {
                 den: 1,
             };
             (*codec_context).pix_fmt = AVPixelFormat::AV_PIX_FMT_YUV420P;
-            (*codec_context).gop_size = fps as i32 * 2; // Keyframe every 2 seconds
+            (*codec_context).gop_size = keyframe_interval as i32;
             (*codec_context).max_b_frames = 2;
 
             // Set CRF for quality control (H.264 specific)
@@ -402,6 +410,670 @@ impl Drop for FFmpegEncoder {
     }
 }
 
+/// Safe wrapper around FFmpeg decoder for reading frames back out of segments
+pub struct FFmpegDecoder {
+    format_context: *mut AVFormatContext,
+    codec_context: *mut AVCodecContext,
+    stream_index: i32,
+    time_base: AVRational,
+    frame: *mut AVFrame,
+    packet: *mut AVPacket,
+}
+
+unsafe impl Send for FFmpegDecoder {}
+
+impl FFmpegDecoder {
+    /// Open a video file for decoding
+    pub fn open(input_path: &Path) -> Result<Self> {
+        unsafe {
+            let input_path_c = CString::new(input_path.to_str().unwrap_or_default())
+                .map_err(|_| FFmpegError::InputOpenFailed(input_path.display().to_string()))?;
+
+            let mut format_context: *mut AVFormatContext = ptr::null_mut();
+            let ret = avformat_open_input(
+                &mut format_context,
+                input_path_c.as_ptr(),
+                ptr::null(),
+                ptr::null_mut(),
+            );
+            if ret < 0 || format_context.is_null() {
+                return Err(FFmpegError::InputOpenFailed(format!("Error code: {}", ret)));
+            }
+
+            if avformat_find_stream_info(format_context, ptr::null_mut()) < 0 {
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::VideoStreamNotFound);
+            }
+
+            let mut codec: *const AVCodec = ptr::null();
+            let stream_index = av_find_best_stream(
+                format_context,
+                AVMediaType::AVMEDIA_TYPE_VIDEO,
+                -1,
+                -1,
+                &mut codec,
+                0,
+            );
+            if stream_index < 0 || codec.is_null() {
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::VideoStreamNotFound);
+            }
+
+            let stream = *(*format_context).streams.add(stream_index as usize);
+
+            let codec_context = avcodec_alloc_context3(codec);
+            if codec_context.is_null() {
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::CodecContextAllocation);
+            }
+
+            if avcodec_parameters_to_context(codec_context, (*stream).codecpar) < 0 {
+                avcodec_free_context(&mut (codec_context as *mut _));
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::CodecContextAllocation);
+            }
+
+            let ret = avcodec_open2(codec_context, codec, ptr::null_mut());
+            if ret < 0 {
+                avcodec_free_context(&mut (codec_context as *mut _));
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::CodecOpenFailed(format!("Error code: {}", ret)));
+            }
+
+            let frame = av_frame_alloc();
+            if frame.is_null() {
+                avcodec_free_context(&mut (codec_context as *mut _));
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::FrameAllocation);
+            }
+
+            let packet = av_packet_alloc();
+            if packet.is_null() {
+                av_frame_free(&mut (frame as *mut _));
+                avcodec_free_context(&mut (codec_context as *mut _));
+                avformat_close_input(&mut format_context);
+                return Err(FFmpegError::PacketAllocation);
+            }
+
+            Ok(Self {
+                format_context,
+                codec_context,
+                stream_index,
+                time_base: (*stream).time_base,
+                frame,
+                packet,
+            })
+        }
+    }
+
+    /// Decode the frame shown at `offset_ms` from the start of the video
+    ///
+    /// Returns the last decoded frame if the offset is past the end.
+    pub fn decode_frame_at(&mut self, offset_ms: i64) -> Result<RawFrame> {
+        unsafe {
+            let target_pts = av_rescale_q(
+                offset_ms.max(0),
+                AVRational { num: 1, den: 1000 },
+                self.time_base,
+            );
+
+            // Seek to the keyframe at or before the target, then decode forward
+            let ret = av_seek_frame(
+                self.format_context,
+                self.stream_index,
+                target_pts,
+                AVSEEK_FLAG_BACKWARD as i32,
+            );
+            if ret < 0 {
+                return Err(FFmpegError::DecodingError(format!("Seek failed: {}", ret)));
+            }
+            avcodec_flush_buffers(self.codec_context);
+
+            let mut last_frame: Option<RawFrame> = None;
+
+            while av_read_frame(self.format_context, self.packet) >= 0 {
+                if (*self.packet).stream_index != self.stream_index {
+                    av_packet_unref(self.packet);
+                    continue;
+                }
+
+                let ret = avcodec_send_packet(self.codec_context, self.packet);
+                av_packet_unref(self.packet);
+                if ret < 0 {
+                    return Err(FFmpegError::DecodingError(format!("Send packet failed: {}", ret)));
+                }
+
+                if let Some(frame) = self.receive_until(target_pts, &mut last_frame)? {
+                    return Ok(frame);
+                }
+            }
+
+            // End of stream, drain the decoder
+            avcodec_send_packet(self.codec_context, ptr::null());
+            if let Some(frame) = self.receive_until(target_pts, &mut last_frame)? {
+                return Ok(frame);
+            }
+
+            last_frame.ok_or_else(|| FFmpegError::DecodingError("No frames decoded".to_string()))
+        }
+    }
+
+    /// Decode the next frame in stream order, or None at the end of the video
+    pub fn next_frame(&mut self) -> Result<Option<RawFrame>> {
+        unsafe {
+            loop {
+                let ret = avcodec_receive_frame(self.codec_context, self.frame);
+
+                if ret >= 0 {
+                    let pts = (*self.frame).best_effort_timestamp;
+                    let converted = self.convert_frame(pts)?;
+                    av_frame_unref(self.frame);
+                    return Ok(Some(converted));
+                }
+
+                if ret == AVERROR_EOF {
+                    return Ok(None);
+                }
+
+                if ret != AVERROR(EAGAIN) {
+                    return Err(FFmpegError::DecodingError(format!("Receive frame failed: {}", ret)));
+                }
+
+                if av_read_frame(self.format_context, self.packet) < 0 {
+                    // End of file; drain the decoder so the remaining frames come out, then EOF
+                    avcodec_send_packet(self.codec_context, ptr::null());
+                    continue;
+                }
+
+                if (*self.packet).stream_index != self.stream_index {
+                    av_packet_unref(self.packet);
+                    continue;
+                }
+
+                let ret = avcodec_send_packet(self.codec_context, self.packet);
+                av_packet_unref(self.packet);
+                if ret < 0 {
+                    return Err(FFmpegError::DecodingError(format!("Send packet failed: {}", ret)));
+                }
+            }
+        }
+    }
+
+    /// Length of the video in milliseconds, when the container records it
+    pub fn duration_ms(&self) -> Option<i64> {
+        // AVFormatContext::duration is in AV_TIME_BASE units (microseconds)
+        let duration = unsafe { (*self.format_context).duration };
+        (duration > 0).then(|| duration / 1000)
+    }
+
+    /// Receive decoded frames, returning the first one at or after `target_pts`
+    fn receive_until(
+        &mut self,
+        target_pts: i64,
+        last_frame: &mut Option<RawFrame>,
+    ) -> Result<Option<RawFrame>> {
+        unsafe {
+            loop {
+                let ret = avcodec_receive_frame(self.codec_context, self.frame);
+
+                if ret == AVERROR(EAGAIN) || ret == AVERROR_EOF {
+                    return Ok(None);
+                }
+
+                if ret < 0 {
+                    return Err(FFmpegError::DecodingError(format!("Receive frame failed: {}", ret)));
+                }
+
+                let pts = (*self.frame).best_effort_timestamp;
+                let converted = self.convert_frame(pts)?;
+                av_frame_unref(self.frame);
+
+                if pts >= target_pts {
+                    return Ok(Some(converted));
+                }
+
+                *last_frame = Some(converted);
+            }
+        }
+    }
+
+    /// Convert the current decoded frame to RGBA
+    fn convert_frame(&self, pts: i64) -> Result<RawFrame> {
+        unsafe {
+            let width = (*self.frame).width;
+            let height = (*self.frame).height;
+
+            let sws_context = sws_getContext(
+                width,
+                height,
+                (*self.codec_context).pix_fmt,
+                width,
+                height,
+                AVPixelFormat::AV_PIX_FMT_RGBA,
+                1, // SWS_BILINEAR flag
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null(),
+            );
+            if sws_context.is_null() {
+                return Err(FFmpegError::SwscaleInitFailed);
+            }
+
+            let mut data = vec![0u8; (width * height * 4) as usize];
+            let dst_data = [data.as_mut_ptr(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut()];
+            let dst_linesize = [width * 4, 0, 0, 0];
+
+            let ret = sws_scale(
+                sws_context,
+                (*self.frame).data.as_ptr() as *const *const u8,
+                (*self.frame).linesize.as_ptr(),
+                0,
+                height,
+                dst_data.as_ptr(),
+                dst_linesize.as_ptr(),
+            );
+            sws_freeContext(sws_context);
+
+            if ret < 0 {
+                return Err(FFmpegError::ColorConversionFailed);
+            }
+
+            let timestamp_ms = av_rescale_q(pts, self.time_base, AVRational { num: 1, den: 1000 });
+
+            Ok(RawFrame {
+                timestamp: timestamp_ms,
+                width: width as u32,
+                height: height as u32,
+                data,
+                format: PixelFormat::RGBA8,
+            })
+        }
+    }
+}
+
+impl Drop for FFmpegDecoder {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.packet.is_null() {
+                av_packet_free(&mut (self.packet as *mut _));
+            }
+
+            if !self.frame.is_null() {
+                av_frame_free(&mut (self.frame as *mut _));
+            }
+
+            if !self.codec_context.is_null() {
+                avcodec_free_context(&mut (self.codec_context as *mut _));
+            }
+
+            if !self.format_context.is_null() {
+                avformat_close_input(&mut self.format_context);
+            }
+        }
+    }
+}
+
+/// Names of every encoder compiled into the linked FFmpeg
+pub fn available_encoders() -> Vec<String> {
+    let mut names = Vec::new();
+
+    unsafe {
+        let mut opaque: *mut c_void = ptr::null_mut();
+        loop {
+            let codec = av_codec_iterate(&mut opaque);
+            if codec.is_null() {
+                break;
+            }
+            if av_codec_is_encoder(codec) != 0 && !(*codec).name.is_null() {
+                names.push(CStr::from_ptr((*codec).name).to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    names
+}
+
+/// Frame rate of the output when inputs have to be re-encoded to be joined
+const CONCAT_REENCODE_FPS: u32 = 15;
+
+/// Join video files end to end, using the concat demuxer when possible
+///
+/// Inputs with identical codec parameters are stream-copied, with packet
+/// timestamps rescaled into the output stream's timebase so the joined file
+/// plays continuously. Inputs from different encoders or settings can't be
+/// copied into one stream, so those are decoded and re-encoded instead.
+/// `on_progress` receives how many milliseconds have been written so far.
+/// Returns the duration of the output in milliseconds.
+pub fn concat_videos(
+    inputs: &[PathBuf],
+    output_path: &Path,
+    mut on_progress: impl FnMut(i64),
+) -> Result<i64> {
+    if inputs.is_empty() {
+        return Err(FFmpegError::InputOpenFailed("No input files".to_string()));
+    }
+
+    let first = stream_params(&inputs[0])?;
+    for input in &inputs[1..] {
+        if stream_params(input)? != first {
+            return reencode_concat(inputs, output_path, &mut on_progress);
+        }
+    }
+
+    // The concat demuxer reads its inputs from a list file
+    let list_path = output_path.with_extension("ffconcat");
+    let mut list = String::from("ffconcat version 1.0\n");
+    for input in inputs {
+        let escaped = input.to_string_lossy().replace('\'', r"'\''");
+        list.push_str(&format!("file '{}'\n", escaped));
+    }
+    std::fs::write(&list_path, list)
+        .map_err(|e| FFmpegError::InputOpenFailed(e.to_string()))?;
+
+    let result = unsafe { remux_concat_list(&list_path, output_path, &mut on_progress) };
+    let _ = std::fs::remove_file(&list_path);
+    result
+}
+
+/// What has to agree between inputs for their packets to share one stream
+#[derive(Debug, PartialEq)]
+struct StreamParams {
+    codec_id: AVCodecID,
+    width: i32,
+    height: i32,
+    format: i32,
+    /// Codec setup such as H.264's SPS and PPS, which differ between encoders and settings
+    extradata: Vec<u8>,
+}
+
+fn stream_params(path: &Path) -> Result<StreamParams> {
+    let path_c = CString::new(path.to_str().unwrap_or_default())
+        .map_err(|_| FFmpegError::InputOpenFailed(path.display().to_string()))?;
+
+    unsafe {
+        let mut context: *mut AVFormatContext = ptr::null_mut();
+        let ret = avformat_open_input(&mut context, path_c.as_ptr(), ptr::null(), ptr::null_mut());
+        if ret < 0 || context.is_null() {
+            return Err(FFmpegError::InputOpenFailed(format!("{}: error code {}", path.display(), ret)));
+        }
+
+        let result = if avformat_find_stream_info(context, ptr::null_mut()) < 0 {
+            Err(FFmpegError::VideoStreamNotFound)
+        } else {
+            let index = av_find_best_stream(context, AVMediaType::AVMEDIA_TYPE_VIDEO, -1, -1, ptr::null_mut(), 0);
+            if index < 0 {
+                Err(FFmpegError::VideoStreamNotFound)
+            } else {
+                let params = (**(*context).streams.add(index as usize)).codecpar;
+                let extradata = if (*params).extradata.is_null() || (*params).extradata_size <= 0 {
+                    Vec::new()
+                } else {
+                    std::slice::from_raw_parts((*params).extradata, (*params).extradata_size as usize).to_vec()
+                };
+
+                Ok(StreamParams {
+                    codec_id: (*params).codec_id,
+                    width: (*params).width,
+                    height: (*params).height,
+                    format: (*params).format,
+                    extradata,
+                })
+            }
+        };
+
+        avformat_close_input(&mut context);
+        result
+    }
+}
+
+/// Join inputs by decoding and re-encoding them as one H.264 stream
+///
+/// The output runs at `CONCAT_REENCODE_FPS`, each frame showing the latest
+/// input frame due by then, so every input keeps its duration whatever its
+/// own frame rate. Inputs of another size are scaled to the first one's.
+fn reencode_concat(
+    inputs: &[PathBuf],
+    output_path: &Path,
+    on_progress: &mut dyn FnMut(i64),
+) -> Result<i64> {
+    let frame_ms = 1000.0 / CONCAT_REENCODE_FPS as f64;
+    let mut encoder: Option<(FFmpegEncoder, u32, u32)> = None;
+    let mut frames_written = 0u64;
+    // Where the current input starts in the output
+    let mut input_start_ms = 0i64;
+
+    for input in inputs {
+        let mut decoder = FFmpegDecoder::open(input)?;
+        let mut first_timestamp = None;
+        let mut shown: Option<RawFrame> = None;
+
+        while let Some(frame) = decoder.next_frame()? {
+            if encoder.is_none() {
+                let output = FFmpegEncoder::new(
+                    output_path,
+                    frame.width,
+                    frame.height,
+                    CONCAT_REENCODE_FPS,
+                    "libx264",
+                    23,
+                    CONCAT_REENCODE_FPS * 2,
+                )?;
+                encoder = Some((output, frame.width, frame.height));
+            }
+            let Some((encoder, width, height)) = encoder.as_mut() else {
+                unreachable!("encoder created above");
+            };
+
+            let offset = frame.timestamp - *first_timestamp.get_or_insert(frame.timestamp);
+            let frame = fit_frame(frame, *width, *height);
+
+            // Hold the previous frame until this one is due
+            if let Some(previous) = &shown {
+                while (frames_written as f64) * frame_ms < (input_start_ms + offset) as f64 {
+                    encoder.encode_frame(previous)?;
+                    frames_written += 1;
+                    on_progress(((frames_written as f64) * frame_ms) as i64);
+                }
+            }
+            shown = Some(frame);
+        }
+
+        let Some(last) = shown else {
+            continue;
+        };
+        let last_offset = last.timestamp - first_timestamp.unwrap_or(last.timestamp);
+        let input_end_ms = input_start_ms + decoder.duration_ms().unwrap_or(0).max(last_offset + frame_ms as i64);
+
+        if let Some((encoder, _, _)) = encoder.as_mut() {
+            // At least one frame per input, however short
+            loop {
+                encoder.encode_frame(&last)?;
+                frames_written += 1;
+                on_progress(((frames_written as f64) * frame_ms) as i64);
+
+                if (frames_written as f64) * frame_ms >= input_end_ms as f64 {
+                    break;
+                }
+            }
+        }
+        input_start_ms = input_end_ms;
+    }
+
+    let Some((mut encoder, _, _)) = encoder else {
+        return Err(FFmpegError::DecodingError("No frames decoded".to_string()));
+    };
+    encoder.finish()?;
+
+    Ok(((frames_written as f64) * frame_ms).round() as i64)
+}
+
+/// `frame` as RGBA at `width` x `height`, scaling it if it is another size
+fn fit_frame(frame: RawFrame, width: u32, height: u32) -> RawFrame {
+    if frame.width == width && frame.height == height {
+        return frame;
+    }
+
+    let data = match image::RgbaImage::from_raw(frame.width, frame.height, frame.data) {
+        Some(image) => image::imageops::resize(&image, width, height, image::imageops::FilterType::Triangle).into_raw(),
+        None => vec![0; (width * height * 4) as usize],
+    };
+
+    RawFrame {
+        timestamp: frame.timestamp,
+        width,
+        height,
+        data,
+        format: PixelFormat::RGBA8,
+    }
+}
+
+unsafe fn remux_concat_list(
+    list_path: &Path,
+    output_path: &Path,
+    on_progress: &mut dyn FnMut(i64),
+) -> Result<i64> {
+    let list_path_c = CString::new(list_path.to_str().unwrap_or_default())
+        .map_err(|_| FFmpegError::InputOpenFailed(list_path.display().to_string()))?;
+    let output_path_c = CString::new(output_path.to_str().unwrap_or_default())
+        .map_err(|_| FFmpegError::FormatContextCreation)?;
+    let concat_name = CString::new("concat").unwrap();
+    let safe_key = CString::new("safe").unwrap();
+    let safe_value = CString::new("0").unwrap();
+
+    // Allow absolute paths in the list
+    let mut options: *mut AVDictionary = ptr::null_mut();
+    av_dict_set(&mut options, safe_key.as_ptr(), safe_value.as_ptr(), 0);
+
+    let mut input_context: *mut AVFormatContext = ptr::null_mut();
+    let ret = avformat_open_input(
+        &mut input_context,
+        list_path_c.as_ptr(),
+        av_find_input_format(concat_name.as_ptr()),
+        &mut options,
+    );
+    av_dict_free(&mut options);
+    if ret < 0 || input_context.is_null() {
+        return Err(FFmpegError::InputOpenFailed(format!("Error code: {}", ret)));
+    }
+
+    let mut output_context: *mut AVFormatContext = ptr::null_mut();
+    let result = remux_packets(input_context, &mut output_context, &output_path_c, on_progress);
+
+    if !output_context.is_null() {
+        if (*output_context).pb as usize != 0 {
+            avio_closep(&mut (*output_context).pb);
+        }
+        avformat_free_context(output_context);
+    }
+    avformat_close_input(&mut input_context);
+
+    result
+}
+
+/// Copy every video packet from `input_context` into a new MP4 at `output_path_c`
+unsafe fn remux_packets(
+    input_context: *mut AVFormatContext,
+    output_context: &mut *mut AVFormatContext,
+    output_path_c: &CString,
+    on_progress: &mut dyn FnMut(i64),
+) -> Result<i64> {
+    if avformat_find_stream_info(input_context, ptr::null_mut()) < 0 {
+        return Err(FFmpegError::VideoStreamNotFound);
+    }
+
+    let stream_index = av_find_best_stream(
+        input_context,
+        AVMediaType::AVMEDIA_TYPE_VIDEO,
+        -1,
+        -1,
+        ptr::null_mut(),
+        0,
+    );
+    if stream_index < 0 {
+        return Err(FFmpegError::VideoStreamNotFound);
+    }
+    let in_stream = *(*input_context).streams.add(stream_index as usize);
+
+    let ret = avformat_alloc_output_context2(
+        output_context,
+        ptr::null_mut(),
+        ptr::null(),
+        output_path_c.as_ptr(),
+    );
+    if ret < 0 || output_context.is_null() {
+        return Err(FFmpegError::FormatContextCreation);
+    }
+    let output = *output_context;
+
+    let out_stream = avformat_new_stream(output, ptr::null());
+    if out_stream.is_null() {
+        return Err(FFmpegError::StreamCreation);
+    }
+    if avcodec_parameters_copy((*out_stream).codecpar, (*in_stream).codecpar) < 0 {
+        return Err(FFmpegError::StreamCreation);
+    }
+    (*(*out_stream).codecpar).codec_tag = 0;
+    (*out_stream).time_base = (*in_stream).time_base;
+
+    if (*(*output).oformat).flags & AVFMT_NOFILE == 0 {
+        let ret = avio_open(&mut (*output).pb, output_path_c.as_ptr(), AVIO_FLAG_WRITE);
+        if ret < 0 {
+            return Err(FFmpegError::FormatContextCreation);
+        }
+    }
+
+    if avformat_write_header(output, ptr::null_mut()) < 0 {
+        return Err(FFmpegError::WriteHeaderFailed);
+    }
+
+    // The muxer may have picked its own timebase while writing the header
+    let in_time_base = (*in_stream).time_base;
+    let out_time_base = (*out_stream).time_base;
+    let millis = AVRational { num: 1, den: 1000 };
+
+    let mut packet = av_packet_alloc();
+    if packet.is_null() {
+        return Err(FFmpegError::PacketAllocation);
+    }
+
+    let mut end_pts = 0i64;
+    let mut result = Ok(());
+
+    while av_read_frame(input_context, packet) >= 0 {
+        if (*packet).stream_index != stream_index {
+            av_packet_unref(packet);
+            continue;
+        }
+
+        av_packet_rescale_ts(packet, in_time_base, out_time_base);
+        (*packet).stream_index = (*out_stream).index;
+        (*packet).pos = -1;
+
+        if (*packet).pts != AV_NOPTS_VALUE {
+            end_pts = end_pts.max((*packet).pts + (*packet).duration);
+        }
+
+        let ret = av_interleaved_write_frame(output, packet);
+        av_packet_unref(packet);
+
+        if ret < 0 {
+            result = Err(FFmpegError::EncodingError(format!("Write frame failed: {}", ret)));
+            break;
+        }
+
+        on_progress(av_rescale_q(end_pts, out_time_base, millis));
+    }
+
+    av_packet_free(&mut packet);
+    result?;
+
+    if av_write_trailer(output) < 0 {
+        return Err(FFmpegError::EncodingError("Failed to write trailer".to_string()));
+    }
+
+    Ok(av_rescale_q(end_pts, out_time_base, millis))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -429,8 +1101,77 @@ mod tests {
             30,
             "libx264",
             23,
+            60,
         );
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_concat_videos_sums_duration() {
+        let temp_dir = std::env::temp_dir().join(format!("concat_test_{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        // Two one-second clips at 10fps
+        let clips: Vec<PathBuf> = (0..2)
+            .map(|i| {
+                let path = temp_dir.join(format!("clip_{}.mp4", i));
+                let mut encoder = FFmpegEncoder::new(&path, 64, 48, 10, "libx264", 23, 20).unwrap();
+                for _ in 0..10 {
+                    encoder.encode_frame(&create_test_frame(64, 48)).unwrap();
+                }
+                encoder.finish().unwrap();
+                path
+            })
+            .collect();
+
+        let output_path = temp_dir.join("joined.mp4");
+        let mut last_progress = 0;
+        let duration_ms = concat_videos(&clips, &output_path, |ms| last_progress = ms).unwrap();
+
+        assert!((duration_ms - 2000).abs() <= 100, "duration was {}ms", duration_ms);
+        assert_eq!(last_progress, duration_ms);
+
+        // The joined file decodes past the point where the first clip ended
+        let mut decoder = FFmpegDecoder::open(&output_path).unwrap();
+        let frame = decoder.decode_frame_at(1_500).unwrap();
+        assert_eq!((frame.width, frame.height), (64, 48));
+        assert!(frame.timestamp >= 1_000);
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_concat_reencodes_mismatched_inputs() {
+        let temp_dir = std::env::temp_dir().join(format!("concat_mismatch_test_{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        // One second each, but different sizes, frame rates and GOPs, so the streams can't be copied
+        let clips: Vec<PathBuf> = [(64, 48, 10, 20), (32, 24, 5, 5)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(width, height, fps, gop))| {
+                let path = temp_dir.join(format!("clip_{}.mp4", i));
+                let mut encoder = FFmpegEncoder::new(&path, width, height, fps, "libx264", 30, gop).unwrap();
+                for _ in 0..fps {
+                    encoder.encode_frame(&create_test_frame(width, height)).unwrap();
+                }
+                encoder.finish().unwrap();
+                path
+            })
+            .collect();
+        assert_ne!(stream_params(&clips[0]).unwrap(), stream_params(&clips[1]).unwrap());
+
+        let output_path = temp_dir.join("joined.mp4");
+        let duration_ms = concat_videos(&clips, &output_path, |_| {}).unwrap();
+        assert!((duration_ms - 2000).abs() <= 150, "duration was {}ms", duration_ms);
+
+        // The second clip is scaled to the first one's size and plays after it
+        let mut decoder = FFmpegDecoder::open(&output_path).unwrap();
+        let frame = decoder.decode_frame_at(1_500).unwrap();
+        assert_eq!((frame.width, frame.height), (64, 48));
+        assert!(frame.timestamp >= 1_000);
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
 }
@@ -0,0 +1,101 @@
+// Health reporting - which subsystems came up, for the diagnostics panel
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Snapshot of the app's subsystems
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ObserverStatus {
+    pub uptime_secs: u64,
+    /// Subsystem name to whether it initialized successfully
+    pub subsystems: HashMap<String, bool>,
+    /// Most recent initialization failure, if any
+    pub last_error: Option<String>,
+}
+
+impl ObserverStatus {
+    pub fn is_healthy(&self) -> bool {
+        self.subsystems.values().all(|up| *up)
+    }
+}
+
+/// Something that knows which subsystems it holds, such as the app state
+pub trait HealthSource {
+    fn started_at(&self) -> Instant;
+    fn subsystems(&self) -> Vec<(&'static str, bool)>;
+    fn last_error(&self) -> Option<String>;
+}
+
+pub fn collect_status(source: &impl HealthSource) -> ObserverStatus {
+    ObserverStatus {
+        uptime_secs: source.started_at().elapsed().as_secs(),
+        subsystems: source
+            .subsystems()
+            .into_iter()
+            .map(|(name, up)| (name.to_string(), up))
+            .collect(),
+        last_error: source.last_error(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct FakeSource {
+        started_at: Instant,
+        screen_recorder: bool,
+    }
+
+    impl HealthSource for FakeSource {
+        fn started_at(&self) -> Instant {
+            self.started_at
+        }
+
+        fn subsystems(&self) -> Vec<(&'static str, bool)> {
+            vec![("database", true), ("screen_recorder", self.screen_recorder)]
+        }
+
+        fn last_error(&self) -> Option<String> {
+            (!self.screen_recorder).then(|| "screen recorder: no displays".to_string())
+        }
+    }
+
+    #[test]
+    fn test_status_reports_down_subsystem() {
+        let source = FakeSource {
+            started_at: Instant::now() - Duration::from_secs(90),
+            screen_recorder: false,
+        };
+
+        let status = collect_status(&source);
+
+        assert!(status.uptime_secs >= 90);
+        assert_eq!(status.subsystems.get("database"), Some(&true));
+        assert_eq!(status.subsystems.get("screen_recorder"), Some(&false));
+        assert_eq!(status.last_error.as_deref(), Some("screen recorder: no displays"));
+        assert!(!status.is_healthy());
+    }
+
+    #[test]
+    fn test_status_serialization() {
+        let status = ObserverStatus {
+            uptime_secs: 42,
+            subsystems: HashMap::from([("database".to_string(), true)]),
+            last_error: None,
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "uptime_secs": 42,
+                "subsystems": { "database": true },
+                "last_error": null,
+            })
+        );
+        assert_eq!(serde_json::from_value::<ObserverStatus>(json).unwrap(), status);
+    }
+}
@@ -1,14 +1,19 @@
-use crate::core::command_analyzer::CommandAnalyzer;
+use crate::core::command_analyzer::{CommandAnalyzer, CommandDefinition};
 use crate::core::consent::{ConsentManager, Feature};
 use crate::core::database::Database;
 use crate::core::input_storage::InputStorage;
+use crate::core::session_manager::ActivitySignal;
 use crate::models::input::{KeyboardEvent, MouseEvent};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+/// Minimum spacing between input activity reports to the session manager
+const ACTIVITY_REPORT_INTERVAL_MS: i64 = 1000;
+
 // Platform-specific keyboard listener
 #[cfg(target_os = "macos")]
 use crate::platform::input::MacOSKeyboardListener as PlatformKeyboardListener;
@@ -37,6 +42,10 @@ pub struct InputRecorder {
     mouse_listener: Arc<RwLock<Option<PlatformMouseListener>>>,
     current_session_id: Arc<RwLock<Option<String>>>,
     is_recording: Arc<RwLock<bool>>,
+    activity_sink: Arc<RwLock<Option<mpsc::UnboundedSender<ActivitySignal>>>>,
+    custom_shortcuts: Arc<RwLock<Vec<CommandDefinition>>>,
+    /// Shutdown signal and handle of the periodic buffer flush
+    flush_task: Arc<RwLock<Option<(Arc<Notify>, JoinHandle<()>)>>>,
 }
 
 impl InputRecorder {
@@ -54,9 +63,37 @@ impl InputRecorder {
             mouse_listener: Arc::new(RwLock::new(None)),
             current_session_id: Arc::new(RwLock::new(None)),
             is_recording: Arc::new(RwLock::new(false)),
+            activity_sink: Arc::new(RwLock::new(None)),
+            custom_shortcuts: Arc::new(RwLock::new(Vec::new())),
+            flush_task: Arc::new(RwLock::new(None)),
         })
     }
 
+    /// Report input activity to the session manager's live metrics
+    pub async fn set_activity_sink(&self, sink: mpsc::UnboundedSender<ActivitySignal>) {
+        *self.activity_sink.write().await = Some(sink);
+    }
+
+    /// Whether keystrokes in sensitive fields are stored without their character
+    pub fn set_redact_sensitive_input(&self, redact: bool) {
+        self.storage.set_redact_sensitive(redact);
+    }
+
+    /// Minimum spacing between stored mouse moves; bursts keep the latest position
+    pub fn set_mouse_sample_interval(&self, interval_ms: u64) {
+        self.storage.set_mouse_sample_interval(interval_ms);
+    }
+
+    /// How often buffered events are written out; applies from the next recording start
+    pub fn set_flush_interval(&self, interval: Duration) {
+        self.storage.set_flush_interval(interval);
+    }
+
+    /// User-defined shortcuts to recognize; applies from the next recording start
+    pub async fn set_custom_shortcuts(&self, definitions: Vec<CommandDefinition>) {
+        *self.custom_shortcuts.write().await = definitions;
+    }
+
     pub async fn start_recording(
         &self,
         session_id: String,
@@ -103,6 +140,8 @@ impl InputRecorder {
             let db = self.db.clone();
             let session_id_clone = session_id.clone();
             let is_recording_clone = self.is_recording.clone();
+            let activity_sink = self.activity_sink.read().await.clone();
+            let custom_shortcuts = self.custom_shortcuts.read().await.clone();
 
             tokio::spawn(async move {
                 Self::process_keyboard_events(
@@ -111,6 +150,8 @@ impl InputRecorder {
                     db,
                     session_id_clone,
                     is_recording_clone,
+                    activity_sink,
+                    custom_shortcuts,
                 )
                 .await;
             });
@@ -129,29 +170,24 @@ impl InputRecorder {
             let storage = self.storage.clone();
             let session_id_clone = session_id.clone();
             let is_recording_clone = self.is_recording.clone();
+            let activity_sink = self.activity_sink.read().await.clone();
 
             tokio::spawn(async move {
-                Self::process_mouse_events(mouse_rx, storage, session_id_clone, is_recording_clone)
-                    .await;
+                Self::process_mouse_events(
+                    mouse_rx,
+                    storage,
+                    session_id_clone,
+                    is_recording_clone,
+                    activity_sink,
+                )
+                .await;
             });
         }
 
         // Start periodic buffer flush
-        let storage_clone = self.storage.clone();
-        let is_recording_clone = self.is_recording.clone();
-
-        tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(Duration::from_secs(5)).await;
-
-                // Check if still recording
-                if !*is_recording_clone.read().await {
-                    break;
-                }
-
-                let _ = storage_clone.flush_buffers().await;
-            }
-        });
+        let shutdown = Arc::new(Notify::new());
+        let handle = tokio::spawn(self.storage.clone().run_periodic_flush(shutdown.clone()));
+        *self.flush_task.write().await = Some((shutdown, handle));
 
         *is_recording = true;
 
@@ -172,6 +208,12 @@ impl InputRecorder {
             listener.stop_listening().await?;
         }
 
+        // Stop the periodic flush, which flushes one last time on the way out
+        if let Some((shutdown, handle)) = self.flush_task.write().await.take() {
+            shutdown.notify_one();
+            let _ = handle.await;
+        }
+
         // Final buffer flush
         self.storage.flush_buffers().await?;
 
@@ -185,15 +227,24 @@ impl InputRecorder {
         *self.is_recording.read().await
     }
 
+    #[cfg(test)]
+    pub(crate) fn storage(&self) -> &Arc<InputStorage> {
+        &self.storage
+    }
+
     async fn process_keyboard_events(
         mut rx: mpsc::UnboundedReceiver<KeyboardEvent>,
         storage: Arc<InputStorage>,
         db: Arc<Database>,
         session_id: String,
         is_recording: Arc<RwLock<bool>>,
+        activity_sink: Option<mpsc::UnboundedSender<ActivitySignal>>,
+        custom_shortcuts: Vec<CommandDefinition>,
     ) {
         let mut command_analyzer = CommandAnalyzer::new();
+        command_analyzer.load_custom(custom_shortcuts);
         let mut keyboard_events_buffer: Vec<KeyboardEvent> = Vec::new();
+        let mut last_activity_report = 0i64;
 
         while let Some(event) = rx.recv().await {
             // Check if still recording
@@ -201,6 +252,8 @@ impl InputRecorder {
                 break;
             }
 
+            Self::report_activity(&activity_sink, event.timestamp, &mut last_activity_report);
+
             // Store event (ignore errors to prevent blocking)
             let _ = storage
                 .store_keyboard_event(session_id.clone(), event.clone())
@@ -212,22 +265,28 @@ impl InputRecorder {
             // Analyze for commands every 50 events
             if keyboard_events_buffer.len() >= 50 {
                 let events: Vec<KeyboardEvent> = keyboard_events_buffer.drain(..).collect();
-                let commands = command_analyzer.analyze_events(events);
-
-                // Store detected commands
-                let session_uuid = Uuid::parse_str(&session_id).unwrap();
-                for command in commands {
-                    let _ = CommandAnalyzer::store_command(&db, session_uuid, command).await;
+                if let Ok(session_uuid) = Uuid::parse_str(&session_id) {
+                    if let Err(e) = command_analyzer.analyze_and_store(&db, session_uuid, events).await {
+                        eprintln!("Error storing commands: {}", e);
+                    }
                 }
             }
         }
 
         // Flush remaining events for command analysis
-        if !keyboard_events_buffer.is_empty() {
-            let commands = command_analyzer.analyze_events(keyboard_events_buffer);
-            let session_uuid = Uuid::parse_str(&session_id).unwrap();
-            for command in commands {
-                let _ = CommandAnalyzer::store_command(&db, session_uuid, command).await;
+        if let Ok(session_uuid) = Uuid::parse_str(&session_id) {
+            if !keyboard_events_buffer.is_empty() {
+                if let Err(e) = command_analyzer
+                    .analyze_and_store(&db, session_uuid, keyboard_events_buffer)
+                    .await
+                {
+                    eprintln!("Error storing commands: {}", e);
+                }
+            }
+
+            // No second key is coming for a chord prefix still held
+            if let Err(e) = command_analyzer.flush_and_store(&db, session_uuid).await {
+                eprintln!("Error storing commands: {}", e);
             }
         }
     }
@@ -237,18 +296,37 @@ impl InputRecorder {
         storage: Arc<InputStorage>,
         session_id: String,
         is_recording: Arc<RwLock<bool>>,
+        activity_sink: Option<mpsc::UnboundedSender<ActivitySignal>>,
     ) {
+        let mut last_activity_report = 0i64;
+
         while let Some(event) = rx.recv().await {
             // Check if still recording
             if !*is_recording.read().await {
                 break;
             }
 
+            Self::report_activity(&activity_sink, event.timestamp, &mut last_activity_report);
+
             // Store event (ignore errors to prevent blocking)
             let _ = storage.store_mouse_event(session_id.clone(), event).await;
         }
     }
 
+    /// Forward input activity to the session manager, at most once per interval
+    fn report_activity(
+        sink: &Option<mpsc::UnboundedSender<ActivitySignal>>,
+        timestamp: i64,
+        last_report: &mut i64,
+    ) {
+        if let Some(sink) = sink {
+            if timestamp - *last_report >= ACTIVITY_REPORT_INTERVAL_MS {
+                let _ = sink.send(ActivitySignal::Input { timestamp });
+                *last_report = timestamp;
+            }
+        }
+    }
+
     // ==============================================================================
     // Cleanup
     // ==============================================================================
@@ -259,4 +337,16 @@ impl InputRecorder {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         self.storage.cleanup_old_events(retention_days).await
     }
+
+    pub async fn get_mouse_heatmap(
+        &self,
+        session_id: String,
+        display_size: Option<(u32, u32)>,
+        grid_width: u32,
+        grid_height: u32,
+    ) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error + Send + Sync>> {
+        self.storage
+            .get_mouse_heatmap(session_id, display_size, grid_width, grid_height)
+            .await
+    }
 }
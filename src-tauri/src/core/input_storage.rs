@@ -1,10 +1,22 @@
 use crate::core::database::Database;
-use crate::models::input::{KeyboardEvent, MouseEvent};
+use crate::models::input::{KeyboardEvent, MouseEvent, MouseEventType, Point};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use sqlx::SqliteConnection;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
 use uuid::Uuid;
 
+/// Stored in place of the key code of a redacted keystroke
+///
+/// Some platforms derive the character from the code, so the code has to go
+/// along with the character. No platform uses this value for a real key.
+pub const REDACTED_KEY_CODE: u32 = u32::MAX;
+
+/// Largest heatmap side, in cells, which bounds the grid allocated per request
+pub const MAX_HEATMAP_GRID_SIZE: u32 = 1024;
+
 // ==============================================================================
 // Time Range for Queries
 // ==============================================================================
@@ -32,6 +44,7 @@ struct KeyboardEventRow {
     window_title: String,
     process_id: i64,
     ui_element: Option<String>,
+    is_sensitive: i64, // SQLite boolean (0 or 1)
 }
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -67,129 +80,45 @@ pub struct InputStorage {
     keyboard_buffer: Arc<RwLock<Vec<(String, KeyboardEvent)>>>, // (session_id, event)
     mouse_buffer: Arc<RwLock<Vec<(String, MouseEvent)>>>,       // (session_id, event)
     buffer_size: usize,
+    /// Drop the character and key code of keystrokes flagged as sensitive
+    redact_sensitive: AtomicBool,
+    /// Moves closer together than this are collapsed into the latest (0 = keep all)
+    mouse_sample_interval_ms: AtomicU64,
+    /// Timestamp of the first move in the run currently being collapsed
+    mouse_window_start: Mutex<Option<i64>>,
+    /// How often `run_periodic_flush` writes out partially filled buffers
+    flush_interval_ms: AtomicU64,
 }
 
 impl InputStorage {
     pub async fn new(db: Arc<Database>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // Initialize database schema
-        Self::init_schema(&db).await?;
-
         Ok(Self {
             db,
             keyboard_buffer: Arc::new(RwLock::new(Vec::new())),
             mouse_buffer: Arc::new(RwLock::new(Vec::new())),
             buffer_size: 100, // Flush every 100 events
+            redact_sensitive: AtomicBool::new(true),
+            mouse_sample_interval_ms: AtomicU64::new(50),
+            mouse_window_start: Mutex::new(None),
+            flush_interval_ms: AtomicU64::new(10_000),
         })
     }
 
-    async fn init_schema(db: &Arc<Database>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let pool = db.pool();
-
-        // Create keyboard_events table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS keyboard_events (
-                id TEXT PRIMARY KEY,
-                session_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                event_type TEXT NOT NULL,
-                key_code INTEGER NOT NULL,
-                key_char TEXT,
-                modifiers TEXT NOT NULL,
-                app_name TEXT NOT NULL,
-                window_title TEXT NOT NULL,
-                process_id INTEGER NOT NULL,
-                ui_element TEXT,
-                FOREIGN KEY (session_id) REFERENCES sessions(id)
-            )
-            "#,
-        )
-        .execute(pool)
-        .await?;
-
-        // Create indexes for keyboard_events
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_keyboard_session ON keyboard_events(session_id)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_keyboard_timestamp ON keyboard_events(timestamp)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_keyboard_app ON keyboard_events(app_name)")
-            .execute(pool)
-            .await?;
-
-        // Create mouse_events table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS mouse_events (
-                id TEXT PRIMARY KEY,
-                session_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                event_type TEXT NOT NULL,
-                position_x INTEGER NOT NULL,
-                position_y INTEGER NOT NULL,
-                app_name TEXT NOT NULL,
-                window_title TEXT NOT NULL,
-                process_id INTEGER NOT NULL,
-                ui_element TEXT,
-                FOREIGN KEY (session_id) REFERENCES sessions(id)
-            )
-            "#,
-        )
-        .execute(pool)
-        .await?;
-
-        // Create indexes for mouse_events
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_mouse_session ON mouse_events(session_id)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_mouse_timestamp ON mouse_events(timestamp)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_mouse_position ON mouse_events(position_x, position_y)")
-            .execute(pool)
-            .await?;
-
-        // Create commands table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS commands (
-                id TEXT PRIMARY KEY,
-                session_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                shortcut TEXT NOT NULL,
-                command_type TEXT NOT NULL,
-                app_name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                FOREIGN KEY (session_id) REFERENCES sessions(id)
-            )
-            "#,
-        )
-        .execute(pool)
-        .await?;
-
-        // Create indexes for commands
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_commands_session ON commands(session_id)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_commands_timestamp ON commands(timestamp)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_commands_shortcut ON commands(shortcut)")
-            .execute(pool)
-            .await?;
+    /// Interval for the periodic flush; applies from the next `run_periodic_flush`
+    pub fn set_flush_interval(&self, interval: Duration) {
+        // A zero interval would make the flush loop spin
+        let interval_ms = (interval.as_millis() as u64).max(1);
+        self.flush_interval_ms.store(interval_ms, Ordering::Relaxed);
+    }
 
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_commands_app ON commands(app_name)")
-            .execute(pool)
-            .await?;
+    /// Minimum spacing between stored mouse moves
+    pub fn set_mouse_sample_interval(&self, interval_ms: u64) {
+        self.mouse_sample_interval_ms.store(interval_ms, Ordering::Relaxed);
+    }
 
-        Ok(())
+    /// Whether sensitive keystrokes are stored without their character
+    pub fn set_redact_sensitive(&self, redact: bool) {
+        self.redact_sensitive.store(redact, Ordering::Relaxed);
     }
 
     // ==============================================================================
@@ -220,39 +149,13 @@ impl InputStorage {
         }
 
         let pool = self.db.pool();
+        let redact_sensitive = self.redact_sensitive.load(Ordering::Relaxed);
 
         // Begin transaction for batch insert
         let mut tx = pool.begin().await?;
 
         for (session_id, event) in buffer.drain(..) {
-            let modifiers_json = serde_json::to_string(&event.modifiers)?;
-            let ui_element_json = event
-                .ui_element
-                .as_ref()
-                .map(|e| serde_json::to_string(e))
-                .transpose()?;
-
-            sqlx::query(
-                r#"
-                INSERT INTO keyboard_events (
-                    id, session_id, timestamp, event_type, key_code, key_char,
-                    modifiers, app_name, window_title, process_id, ui_element
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-            )
-            .bind(Uuid::new_v4().to_string())
-            .bind(session_id)
-            .bind(event.timestamp)
-            .bind(event.event_type.to_string())
-            .bind(event.key_code as i64)
-            .bind(event.key_char.map(|c| c.to_string()))
-            .bind(modifiers_json)
-            .bind(event.app_context.app_name)
-            .bind(event.app_context.window_title)
-            .bind(event.app_context.process_id as i64)
-            .bind(ui_element_json)
-            .execute(&mut *tx)
-            .await?;
+            Self::insert_keyboard_event(&mut tx, &session_id, &event, redact_sensitive).await?;
         }
 
         tx.commit().await?;
@@ -260,6 +163,53 @@ impl InputStorage {
         Ok(())
     }
 
+    /// Write one keyboard event row, dropping which key was pressed for sensitive keys when redacting
+    pub(crate) async fn insert_keyboard_event(
+        conn: &mut SqliteConnection,
+        session_id: &str,
+        event: &KeyboardEvent,
+        redact_sensitive: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // The event itself is kept so typing activity still shows up
+        let (key_code, key_char) = if event.is_sensitive && redact_sensitive {
+            (REDACTED_KEY_CODE, None)
+        } else {
+            (event.key_code, event.key_char.map(|c| c.to_string()))
+        };
+
+        let modifiers_json = serde_json::to_string(&event.modifiers)?;
+        let ui_element_json = event
+            .ui_element
+            .as_ref()
+            .map(|e| serde_json::to_string(e))
+            .transpose()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO keyboard_events (
+                id, session_id, timestamp, event_type, key_code, key_char,
+                modifiers, app_name, window_title, process_id, ui_element, is_sensitive
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(event.timestamp)
+        .bind(event.event_type.to_string())
+        .bind(key_code as i64)
+        .bind(key_char)
+        .bind(modifiers_json)
+        .bind(&event.app_context.app_name)
+        .bind(&event.app_context.window_title)
+        .bind(event.app_context.process_id as i64)
+        .bind(ui_element_json)
+        .bind(if event.is_sensitive { 1 } else { 0 })
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
     // ==============================================================================
     // Mouse Event Storage
     // ==============================================================================
@@ -270,7 +220,11 @@ impl InputStorage {
         event: MouseEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let mut buffer = self.mouse_buffer.write().await;
-        buffer.push((session_id, event));
+        let interval_ms = self.mouse_sample_interval_ms.load(Ordering::Relaxed) as i64;
+        {
+            let mut window_start = self.mouse_window_start.lock().unwrap();
+            push_coalesced(&mut buffer, &mut window_start, session_id, event, interval_ms);
+        }
 
         if buffer.len() >= self.buffer_size {
             drop(buffer); // Release lock before flushing
@@ -293,32 +247,7 @@ impl InputStorage {
         let mut tx = pool.begin().await?;
 
         for (session_id, event) in buffer.drain(..) {
-            let ui_element_json = event
-                .ui_element
-                .as_ref()
-                .map(|e| serde_json::to_string(e))
-                .transpose()?;
-
-            sqlx::query(
-                r#"
-                INSERT INTO mouse_events (
-                    id, session_id, timestamp, event_type,
-                    position_x, position_y, app_name, window_title, process_id, ui_element
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-            )
-            .bind(Uuid::new_v4().to_string())
-            .bind(session_id)
-            .bind(event.timestamp)
-            .bind(event.event_type.to_string())
-            .bind(event.position.x as i64)
-            .bind(event.position.y as i64)
-            .bind(event.app_context.app_name)
-            .bind(event.app_context.window_title)
-            .bind(event.app_context.process_id as i64)
-            .bind(ui_element_json)
-            .execute(&mut *tx)
-            .await?;
+            Self::insert_mouse_event(&mut tx, &session_id, &event).await?;
         }
 
         tx.commit().await?;
@@ -326,6 +255,42 @@ impl InputStorage {
         Ok(())
     }
 
+    /// Write one mouse event row
+    pub(crate) async fn insert_mouse_event(
+        conn: &mut SqliteConnection,
+        session_id: &str,
+        event: &MouseEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let ui_element_json = event
+            .ui_element
+            .as_ref()
+            .map(|e| serde_json::to_string(e))
+            .transpose()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO mouse_events (
+                id, session_id, timestamp, event_type,
+                position_x, position_y, app_name, window_title, process_id, ui_element
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(event.timestamp)
+        .bind(event.event_type.to_string())
+        .bind(event.position.x as i64)
+        .bind(event.position.y as i64)
+        .bind(&event.app_context.app_name)
+        .bind(&event.app_context.window_title)
+        .bind(event.app_context.process_id as i64)
+        .bind(ui_element_json)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
     // ==============================================================================
     // Flush All Buffers
     // ==============================================================================
@@ -336,6 +301,31 @@ impl InputStorage {
         Ok(())
     }
 
+    /// Flush both buffers on every interval until `shutdown` is notified
+    ///
+    /// Events below the batch threshold would otherwise sit in memory until the
+    /// buffer fills, and be lost if the app dies first. A final flush runs on
+    /// shutdown so nothing buffered is left behind.
+    pub async fn run_periodic_flush(self: Arc<Self>, shutdown: Arc<Notify>) {
+        let interval = Duration::from_millis(self.flush_interval_ms.load(Ordering::Relaxed));
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.flush_buffers().await {
+                        eprintln!("Error flushing input buffers: {}", e);
+                    }
+                }
+                _ = shutdown.notified() => break,
+            }
+        }
+
+        if let Err(e) = self.flush_buffers().await {
+            eprintln!("Error flushing input buffers: {}", e);
+        }
+    }
+
     // ==============================================================================
     // Querying
     // ==============================================================================
@@ -433,6 +423,58 @@ impl InputStorage {
         })
     }
 
+    /// Count mouse events per cell of a `grid_width` x `grid_height` grid
+    ///
+    /// Each side must be between 1 and `MAX_HEATMAP_GRID_SIZE` cells.
+    ///
+    /// Positions are scaled against `display_size`, the resolution the session
+    /// was captured at, or the furthest recorded position when it isn't known.
+    /// Anything outside those bounds is clamped into the nearest edge cell.
+    /// Rows run top to bottom, so the result is indexed `[row][column]`.
+    pub async fn get_mouse_heatmap(
+        &self,
+        session_id: String,
+        display_size: Option<(u32, u32)>,
+        grid_width: u32,
+        grid_height: u32,
+    ) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error + Send + Sync>> {
+        if grid_width == 0 || grid_height == 0 {
+            return Err("Heatmap grid must be at least 1x1".into());
+        }
+        if grid_width > MAX_HEATMAP_GRID_SIZE || grid_height > MAX_HEATMAP_GRID_SIZE {
+            return Err(format!(
+                "Heatmap grid can be at most {0}x{0}, got {1}x{2}",
+                MAX_HEATMAP_GRID_SIZE, grid_width, grid_height
+            )
+            .into());
+        }
+
+        let pool = self.db.pool();
+
+        // Grouping on (position_x, position_y) is served by idx_mouse_position
+        let positions: Vec<(i64, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT position_x, position_y, COUNT(*)
+            FROM mouse_events
+            WHERE session_id = ?
+            GROUP BY position_x, position_y
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(pool)
+        .await?;
+
+        let bounds = match display_size {
+            Some((width, height)) if width > 0 && height > 0 => (width as i64, height as i64),
+            _ => (
+                positions.iter().map(|p| p.0 + 1).max().unwrap_or(1).max(1),
+                positions.iter().map(|p| p.1 + 1).max().unwrap_or(1).max(1),
+            ),
+        };
+
+        Ok(bucket_positions(&positions, bounds, grid_width, grid_height))
+    }
+
     // ==============================================================================
     // Row Conversion
     // ==============================================================================
@@ -469,7 +511,7 @@ impl InputStorage {
                 process_id: row.process_id as u32,
             },
             ui_element,
-            is_sensitive: false, // Set based on UI element if needed
+            is_sensitive: row.is_sensitive != 0,
         })
     }
 
@@ -477,10 +519,13 @@ impl InputStorage {
         &self,
         row: MouseEventRow,
     ) -> Result<MouseEvent, Box<dyn std::error::Error + Send + Sync>> {
-        use crate::models::input::{AppContext, MouseEventType, Point, UiElement};
+        use crate::models::input::{AppContext, UiElement};
 
-        // Parse event_type JSON
-        let event_type: MouseEventType = serde_json::from_str(&row.event_type)?;
+        let position = Point {
+            x: row.position_x as i32,
+            y: row.position_y as i32,
+        };
+        let event_type = parse_mouse_event_type(&row.event_type, position)?;
 
         let ui_element: Option<UiElement> = row
             .ui_element
@@ -491,10 +536,7 @@ impl InputStorage {
         Ok(MouseEvent {
             timestamp: row.timestamp,
             event_type,
-            position: Point {
-                x: row.position_x as i32,
-                y: row.position_y as i32,
-            },
+            position,
             app_context: AppContext {
                 app_name: row.app_name,
                 window_title: row.window_title,
@@ -537,3 +579,333 @@ impl InputStorage {
         Ok(())
     }
 }
+
+/// Rebuild a stored mouse event type
+///
+/// Rows hold the type's name, so point-carrying types take the row's position
+/// and scroll deltas are not recoverable. Full JSON is accepted as well.
+fn parse_mouse_event_type(
+    stored: &str,
+    position: Point,
+) -> Result<MouseEventType, Box<dyn std::error::Error + Send + Sync>> {
+    if let Ok(event_type) = serde_json::from_str(stored) {
+        return Ok(event_type);
+    }
+
+    Ok(match stored {
+        "move" => MouseEventType::Move { target: position },
+        "left_click" => MouseEventType::LeftClick,
+        "right_click" => MouseEventType::RightClick,
+        "middle_click" => MouseEventType::MiddleClick,
+        "double_click" => MouseEventType::DoubleClick,
+        "drag_start" => MouseEventType::DragStart { start_pos: position },
+        "drag_move" => MouseEventType::DragMove { current_pos: position },
+        "drag_end" => MouseEventType::DragEnd { end_pos: position },
+        "scroll_wheel" => MouseEventType::ScrollWheel { delta_x: 0, delta_y: 0 },
+        other => return Err(format!("Unknown mouse event type: {}", other).into()),
+    })
+}
+
+/// Bucket `(x, y, count)` positions into a grid covering `bounds`
+fn bucket_positions(
+    positions: &[(i64, i64, i64)],
+    bounds: (i64, i64),
+    grid_width: u32,
+    grid_height: u32,
+) -> Vec<Vec<u32>> {
+    let mut grid = vec![vec![0u32; grid_width as usize]; grid_height as usize];
+    let cell = |value: i64, extent: i64, cells: u32| {
+        (value * cells as i64 / extent).clamp(0, cells as i64 - 1) as usize
+    };
+
+    for &(x, y, count) in positions {
+        let column = cell(x, bounds.0, grid_width);
+        let row = cell(y, bounds.1, grid_height);
+        grid[row][column] += count as u32;
+    }
+
+    grid
+}
+
+/// Buffer a mouse event, collapsing bursts of moves
+///
+/// A move replaces the buffered move before it when both fall within
+/// `interval_ms` of the first move in the run, so each window keeps only its
+/// latest position. Any other event type is always kept and ends the run.
+fn push_coalesced(
+    buffer: &mut Vec<(String, MouseEvent)>,
+    window_start: &mut Option<i64>,
+    session_id: String,
+    event: MouseEvent,
+    interval_ms: i64,
+) {
+    if !matches!(event.event_type, MouseEventType::Move { .. }) {
+        *window_start = None;
+        buffer.push((session_id, event));
+        return;
+    }
+
+    if let (Some(start), Some((last_session, last))) = (*window_start, buffer.last_mut()) {
+        let last_is_move = matches!(last.event_type, MouseEventType::Move { .. });
+        if last_is_move && *last_session == session_id && event.timestamp - start < interval_ms {
+            *last = event;
+            return;
+        }
+    }
+
+    *window_start = Some(event.timestamp);
+    buffer.push((session_id, event));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::input::{AppContext, KeyEventType, ModifierState, Point};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn key_event(timestamp: i64, key_char: char, is_sensitive: bool) -> KeyboardEvent {
+        KeyboardEvent {
+            timestamp,
+            event_type: KeyEventType::KeyDown,
+            key_code: key_char as u32,
+            key_char: Some(key_char),
+            modifiers: ModifierState::new(),
+            app_context: AppContext {
+                app_name: "Browser".to_string(),
+                window_title: "Sign in".to_string(),
+                process_id: 42,
+            },
+            ui_element: None,
+            is_sensitive,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_key_stored_without_character() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = InputStorage::new(db.clone()).await.unwrap();
+        storage.store_keyboard_event("session-1".to_string(), key_event(1, 'u', false)).await.unwrap();
+        storage.store_keyboard_event("session-1".to_string(), key_event(2, 'p', true)).await.unwrap();
+        storage.flush_keyboard_buffer().await.unwrap();
+
+        // Neither the character nor the code it could be derived from is kept
+        let stored: Vec<(i64, Option<String>, i64)> = sqlx::query_as(
+            "SELECT key_code, key_char, is_sensitive FROM keyboard_events ORDER BY timestamp"
+        )
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(
+            stored,
+            vec![
+                ('u' as i64, Some("u".to_string()), 0),
+                (REDACTED_KEY_CODE as i64, None, 1),
+            ]
+        );
+
+        let events = storage.get_keyboard_events("session-1".to_string(), None).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(!events[0].is_sensitive);
+        assert!(events[1].is_sensitive);
+        assert_eq!(events[1].key_char, None);
+
+        // With redaction off the character is kept, still flagged sensitive
+        storage.set_redact_sensitive(false);
+        storage.store_keyboard_event("session-1".to_string(), key_event(3, 'w', true)).await.unwrap();
+        storage.flush_keyboard_buffer().await.unwrap();
+
+        let events = storage.get_keyboard_events("session-1".to_string(), None).await.unwrap();
+        assert_eq!(events[2].key_char, Some('w'));
+        assert_eq!(events[2].key_code, 'w' as u32);
+        assert!(events[2].is_sensitive);
+    }
+
+    fn mouse_event(timestamp: i64, event_type: MouseEventType) -> MouseEvent {
+        MouseEvent {
+            timestamp,
+            event_type,
+            position: Point { x: timestamp as i32, y: 0 },
+            app_context: AppContext {
+                app_name: "Editor".to_string(),
+                window_title: "main.rs".to_string(),
+                process_id: 7,
+            },
+            ui_element: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mouse_moves_coalesced_clicks_kept() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = InputStorage::new(db.clone()).await.unwrap();
+        let mv = |t: i64| mouse_event(t, MouseEventType::Move { target: Point { x: t as i32, y: 0 } });
+
+        for event in [
+            mv(0), mv(10), mv(20), mv(30), mv(40),
+            mouse_event(45, MouseEventType::LeftClick),
+            mv(50), mv(60), mv(120),
+        ] {
+            storage.store_mouse_event("session-1".to_string(), event).await.unwrap();
+        }
+        storage.flush_mouse_buffer().await.unwrap();
+
+        let stored: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT event_type, timestamp FROM mouse_events ORDER BY timestamp"
+        )
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            stored,
+            vec![
+                ("move".to_string(), 40),
+                ("left_click".to_string(), 45),
+                ("move".to_string(), 60),
+                ("move".to_string(), 120),
+            ]
+        );
+
+        // Stored names read back as events
+        let events = storage.get_mouse_events("session-1".to_string(), None).await.unwrap();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[0].event_type, MouseEventType::Move { target: Point { x: 40, y: 0 } }));
+        assert!(matches!(events[1].event_type, MouseEventType::LeftClick));
+    }
+
+    #[tokio::test]
+    async fn test_mouse_heatmap_buckets_and_clamps() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = InputStorage::new(db.clone()).await.unwrap();
+        let click = |t: i64, x: i32, y: i32| MouseEvent {
+            position: Point { x, y },
+            ..mouse_event(t, MouseEventType::LeftClick)
+        };
+        for event in [
+            click(1, 0, 0),
+            click(2, 10, 10),
+            click(3, 260, 0),
+            click(4, 999, 499),
+            click(5, -50, 600),  // off the bottom-left corner
+            click(6, 5000, -10), // off the top-right corner
+        ] {
+            storage.store_mouse_event("session-1".to_string(), event).await.unwrap();
+        }
+        storage.flush_mouse_buffer().await.unwrap();
+
+        // Captured display is 1000x500
+        let display = Some((1000, 500));
+        let heatmap = storage.get_mouse_heatmap("session-1".to_string(), display, 4, 2).await.unwrap();
+        assert_eq!(heatmap, vec![vec![2, 1, 0, 1], vec![1, 0, 0, 1]]);
+
+        assert!(storage.get_mouse_heatmap("session-1".to_string(), display, 0, 2).await.is_err());
+        assert!(storage
+            .get_mouse_heatmap("session-1".to_string(), display, MAX_HEATMAP_GRID_SIZE + 1, 2)
+            .await
+            .is_err());
+        assert!(storage
+            .get_mouse_heatmap("session-1".to_string(), display, 2, u32::MAX)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mouse_heatmap_scales_to_recorded_source_size() {
+        use crate::core::storage::RecordingStorage;
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        // Recorded as video segments only, so the session has no frames rows
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 0, "test-device").await.unwrap();
+        let recordings = std::env::temp_dir().join(format!("observer_test_heatmap_{}", session_id));
+        let recording_storage = RecordingStorage::new(recordings.clone(), db.clone()).await.unwrap();
+        recording_storage.save_source_dimensions(&session_id, 1000, 500).await.unwrap();
+
+        let storage = InputStorage::new(db.clone()).await.unwrap();
+        for (t, x, y) in [(1, 0, 0), (2, 100, 50)] {
+            let event = MouseEvent {
+                position: Point { x, y },
+                ..mouse_event(t, MouseEventType::LeftClick)
+            };
+            storage.store_mouse_event(session_id.to_string(), event).await.unwrap();
+        }
+        storage.flush_mouse_buffer().await.unwrap();
+
+        // Both clicks sit in the left half of the display, not spread over the pointer's reach
+        let display = recording_storage.get_source_dimensions(&session_id).await.unwrap();
+        assert_eq!(display, Some((1000, 500)));
+        let heatmap = storage.get_mouse_heatmap(session_id.to_string(), display, 2, 1).await.unwrap();
+        assert_eq!(heatmap, vec![vec![2, 0]]);
+
+        let _ = std::fs::remove_dir_all(recordings);
+    }
+
+    #[tokio::test]
+    async fn test_periodic_flush_persists_partial_buffers() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = Arc::new(InputStorage::new(db.clone()).await.unwrap());
+        storage.set_flush_interval(Duration::from_millis(50));
+
+        let shutdown = Arc::new(Notify::new());
+        let flush_task = tokio::spawn(storage.clone().run_periodic_flush(shutdown.clone()));
+
+        let count = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM keyboard_events")
+                .fetch_one(db.pool())
+                .await
+                .unwrap()
+        };
+
+        // Well below the batch size, so only the timer can write these out
+        for t in 1..=3 {
+            storage.store_keyboard_event("session-1".to_string(), key_event(t, 'a', false)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(count().await, 3);
+
+        // Anything buffered at shutdown is flushed before the task ends
+        storage.store_keyboard_event("session-1".to_string(), key_event(4, 'b', false)).await.unwrap();
+        shutdown.notify_one();
+        flush_task.await.unwrap();
+        assert_eq!(count().await, 4);
+    }
+}
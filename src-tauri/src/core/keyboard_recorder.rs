@@ -1,8 +1,11 @@
+use crate::core::command_analyzer::CommandAnalyzer;
 use crate::core::consent::ConsentManager;
 use crate::core::database::Database;
+use crate::core::input_storage::InputStorage;
 use crate::models::input::{KeyboardEvent, KeyEventType, KeyboardStats};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 
@@ -14,6 +17,21 @@ use crate::platform::input::WindowsKeyboardListener as PlatformKeyboardListener;
 #[cfg(target_os = "linux")]
 use crate::platform::input::LinuxKeyboardListener as PlatformKeyboardListener;
 
+/// Keyboard events collected before running command analysis
+const COMMAND_ANALYSIS_BATCH: usize = 50;
+
+/// Characters that make up one "word" for WPM
+const CHARS_PER_WORD: f32 = 5.0;
+
+/// Pauses longer than this don't count as typing time
+const TYPING_GAP_MS: i64 = 5_000;
+
+/// Window used for peak WPM
+const PEAK_WPM_WINDOW_MS: i64 = 60_000;
+
+/// Fewer typed characters than this report 0 WPM instead of extrapolating
+const MIN_WPM_KEYSTROKES: usize = 10;
+
 // ==============================================================================
 // Database Models
 // ==============================================================================
@@ -43,6 +61,8 @@ pub struct KeyboardRecorder {
     listener: Arc<RwLock<Option<PlatformKeyboardListener>>>,
     current_session_id: Arc<RwLock<Option<String>>>,
     is_recording: Arc<RwLock<bool>>,
+    /// Drop which key was pressed for keystrokes flagged as sensitive
+    redact_sensitive: Arc<AtomicBool>,
 }
 
 impl KeyboardRecorder {
@@ -50,55 +70,18 @@ impl KeyboardRecorder {
         consent_manager: Arc<ConsentManager>,
         db: Arc<Database>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // Initialize database schema
-        Self::init_schema(&db).await?;
-
         Ok(Self {
             db,
             consent_manager,
             listener: Arc::new(RwLock::new(None)),
             current_session_id: Arc::new(RwLock::new(None)),
             is_recording: Arc::new(RwLock::new(false)),
+            redact_sensitive: Arc::new(AtomicBool::new(true)),
         })
     }
 
-    async fn init_schema(db: &Arc<Database>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let pool = db.pool();
-
-        // Create keyboard_events table
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS keyboard_events (
-                id TEXT PRIMARY KEY,
-                session_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                event_type TEXT NOT NULL,
-                key_code INTEGER NOT NULL,
-                key_char TEXT,
-                modifiers TEXT NOT NULL,
-                app_name TEXT NOT NULL,
-                window_title TEXT NOT NULL,
-                process_id INTEGER NOT NULL,
-                is_sensitive INTEGER NOT NULL DEFAULT 0,
-                FOREIGN KEY (session_id) REFERENCES sessions(id)
-            )"
-        )
-        .execute(pool)
-        .await?;
-
-        // Create indexes for efficient queries
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_keyboard_events_session ON keyboard_events(session_id)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_keyboard_events_timestamp ON keyboard_events(timestamp)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_keyboard_events_app ON keyboard_events(app_name)")
-            .execute(pool)
-            .await?;
-
-        Ok(())
+    pub fn set_redact_sensitive_input(&self, redact: bool) {
+        self.redact_sensitive.store(redact, Ordering::Relaxed);
     }
 
     pub async fn start_recording(&self, session_id: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -123,9 +106,10 @@ impl KeyboardRecorder {
         let db = self.db.clone();
         let current_session_id = self.current_session_id.clone();
         let is_recording_clone = self.is_recording.clone();
+        let redact_sensitive = self.redact_sensitive.clone();
 
         tokio::spawn(async move {
-            Self::process_events(event_rx, db, current_session_id, is_recording_clone).await;
+            Self::process_events(event_rx, db, current_session_id, is_recording_clone, redact_sensitive).await;
         });
 
         println!("Started keyboard recording for session {}", session_id);
@@ -155,7 +139,12 @@ impl KeyboardRecorder {
         db: Arc<Database>,
         current_session_id: Arc<RwLock<Option<String>>>,
         is_recording: Arc<RwLock<bool>>,
+        redact_sensitive: Arc<AtomicBool>,
     ) {
+        let mut command_analyzer = CommandAnalyzer::new();
+        let mut pending: Vec<KeyboardEvent> = Vec::new();
+        let mut pending_session_id: Option<String> = None;
+
         while let Some(event) = event_rx.recv().await {
             // Check if still recording
             if !*is_recording.read().await {
@@ -167,44 +156,72 @@ impl KeyboardRecorder {
                 None => continue,
             };
 
-            // Store event in database (respecting privacy - sensitive events already filtered)
-            if let Err(e) = Self::store_event(&db, &session_id, &event).await {
+            let redact = redact_sensitive.load(Ordering::Relaxed);
+            if let Err(e) = Self::store_event(&db, &session_id, &event, redact).await {
                 eprintln!("Error storing keyboard event: {}", e);
             }
+
+            // Commands belong to the session their keys were typed in
+            if pending_session_id.as_deref() != Some(session_id.as_str()) {
+                if let Some(previous) = pending_session_id.replace(session_id) {
+                    Self::store_commands(&mut command_analyzer, &db, &previous, std::mem::take(&mut pending)).await;
+                    Self::flush_commands(&mut command_analyzer, &db, &previous).await;
+                }
+            }
+
+            pending.push(event);
+            if pending.len() >= COMMAND_ANALYSIS_BATCH {
+                if let Some(session_id) = &pending_session_id {
+                    Self::store_commands(&mut command_analyzer, &db, session_id, std::mem::take(&mut pending)).await;
+                }
+            }
+        }
+
+        if let Some(session_id) = &pending_session_id {
+            Self::store_commands(&mut command_analyzer, &db, session_id, pending).await;
+            Self::flush_commands(&mut command_analyzer, &db, session_id).await;
         }
     }
 
-    async fn store_event(
+    /// Store a chord prefix still held when its session's keys stop coming
+    async fn flush_commands(command_analyzer: &mut CommandAnalyzer, db: &Arc<Database>, session_id: &str) {
+        let Ok(session_uuid) = uuid::Uuid::parse_str(session_id) else {
+            return;
+        };
+
+        if let Err(e) = command_analyzer.flush_and_store(db, session_uuid).await {
+            eprintln!("Error storing commands: {}", e);
+        }
+    }
+
+    async fn store_commands(
+        command_analyzer: &mut CommandAnalyzer,
         db: &Arc<Database>,
         session_id: &str,
-        event: &KeyboardEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let id = uuid::Uuid::new_v4().to_string();
+        events: Vec<KeyboardEvent>,
+    ) {
+        if events.is_empty() {
+            return;
+        }
 
-        // Serialize modifiers to JSON
-        let modifiers_json = serde_json::to_string(&event.modifiers)?;
+        let Ok(session_uuid) = uuid::Uuid::parse_str(session_id) else {
+            return;
+        };
 
-        sqlx::query(
-            "INSERT INTO keyboard_events
-             (id, session_id, timestamp, event_type, key_code, key_char, modifiers,
-              app_name, window_title, process_id, is_sensitive)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-        )
-        .bind(&id)
-        .bind(session_id)
-        .bind(event.timestamp)
-        .bind(event.event_type.to_string())
-        .bind(event.key_code as i64)
-        .bind(event.key_char.map(|c| c.to_string()))
-        .bind(modifiers_json)
-        .bind(&event.app_context.app_name)
-        .bind(&event.app_context.window_title)
-        .bind(event.app_context.process_id as i64)
-        .bind(if event.is_sensitive { 1 } else { 0 })
-        .execute(db.pool())
-        .await?;
+        if let Err(e) = command_analyzer.analyze_and_store(db, session_uuid, events).await {
+            eprintln!("Error storing commands: {}", e);
+        }
+    }
 
-        Ok(())
+    /// Store one event through the same redaction as the input recorder
+    async fn store_event(
+        db: &Arc<Database>,
+        session_id: &str,
+        event: &KeyboardEvent,
+        redact_sensitive: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = db.pool().acquire().await?;
+        InputStorage::insert_keyboard_event(&mut conn, session_id, event, redact_sensitive).await
     }
 
     pub async fn get_keyboard_stats(&self, session_id: String) -> Result<KeyboardStats, Box<dyn std::error::Error + Send + Sync>> {
@@ -224,6 +241,8 @@ impl KeyboardRecorder {
                 most_used_keys: Vec::new(),
                 shortcut_usage: Vec::new(),
                 typing_speed_wpm: None,
+                words_per_minute: 0.0,
+                peak_wpm: 0.0,
             });
         }
 
@@ -275,13 +294,29 @@ impl KeyboardRecorder {
         shortcut_usage.sort_by(|a, b| b.1.cmp(&a.1));
         shortcut_usage.truncate(10); // Top 10
 
+        // Characters typed, including ones redacted from sensitive fields
+        let typed_timestamps: Vec<i64> = events
+            .iter()
+            .filter(|e| e.event_type == "key_down")
+            .filter(|e| e.key_char.is_some() || e.is_sensitive != 0)
+            .filter(|e| {
+                serde_json::from_str::<crate::models::input::ModifierState>(&e.modifiers)
+                    .map(|m| !m.ctrl && !m.meta && !m.alt)
+                    .unwrap_or(true)
+            })
+            .map(|e| e.timestamp)
+            .collect();
+        let (words_per_minute, peak_wpm) = typing_speed(&typed_timestamps);
+
         Ok(KeyboardStats {
             session_id,
             total_keystrokes,
             keys_per_minute,
             most_used_keys,
             shortcut_usage,
-            typing_speed_wpm: None, // Would require word boundary detection
+            typing_speed_wpm: (words_per_minute > 0.0).then_some(words_per_minute),
+            words_per_minute,
+            peak_wpm,
         })
     }
 
@@ -289,3 +324,170 @@ impl KeyboardRecorder {
         *self.is_recording.read().await
     }
 }
+
+/// Average and peak words per minute from sorted typed-character timestamps
+///
+/// Only time between keystrokes at most `TYPING_GAP_MS` apart counts, so
+/// breaks don't drag the average down. The peak is the best average over any
+/// window of `PEAK_WPM_WINDOW_MS` that holds at least `MIN_WPM_KEYSTROKES`.
+fn typing_speed(timestamps: &[i64]) -> (f32, f32) {
+    if timestamps.len() < MIN_WPM_KEYSTROKES {
+        return (0.0, 0.0);
+    }
+
+    // active_ms[i] = typing time from the first keystroke up to keystroke i
+    let mut active_ms = Vec::with_capacity(timestamps.len());
+    let mut total = 0i64;
+    active_ms.push(0);
+    for pair in timestamps.windows(2) {
+        let gap = pair[1] - pair[0];
+        if gap <= TYPING_GAP_MS {
+            total += gap;
+        }
+        active_ms.push(total);
+    }
+
+    let wpm = |keystrokes: usize, ms: i64| {
+        if ms <= 0 {
+            0.0
+        } else {
+            (keystrokes as f32 / CHARS_PER_WORD) / (ms as f32 / 60_000.0)
+        }
+    };
+
+    let average = wpm(timestamps.len(), total);
+
+    let mut peak = 0.0f32;
+    let mut end = 0;
+    for start in 0..timestamps.len() {
+        while end + 1 < timestamps.len() && timestamps[end + 1] - timestamps[start] < PEAK_WPM_WINDOW_MS {
+            end += 1;
+        }
+        let keystrokes = end - start + 1;
+        if keystrokes >= MIN_WPM_KEYSTROKES {
+            peak = peak.max(wpm(keystrokes, active_ms[end] - active_ms[start]));
+        }
+    }
+
+    (average, peak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::input_storage::REDACTED_KEY_CODE;
+    use crate::models::input::{AppContext, ModifierState};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// `count` keystrokes starting at `start`, one every `interval_ms`
+    fn burst(start: i64, count: usize, interval_ms: i64) -> Vec<i64> {
+        (0..count as i64).map(|i| start + i * interval_ms).collect()
+    }
+
+    #[test]
+    fn test_steady_typing_wpm() {
+        // One character every 200ms is 300 characters, or 60 words, a minute
+        let timestamps = burst(0, 301, 200);
+        let (wpm, peak) = typing_speed(&timestamps);
+
+        assert!((wpm - 60.2).abs() < 0.5, "wpm was {}", wpm);
+        assert!((peak - 60.0).abs() < 1.0, "peak was {}", peak);
+    }
+
+    #[test]
+    fn test_pauses_excluded_and_peak_window() {
+        // A slow minute, a long break, then a fast burst
+        let mut timestamps = burst(0, 61, 1_000);
+        timestamps.extend(burst(600_000, 201, 100));
+        let (wpm, peak) = typing_speed(&timestamps);
+
+        // 262 characters over 60s + 20s of active typing
+        let expected = (262.0 / 5.0) / (80.0 / 60.0);
+        assert!((wpm - expected).abs() < 0.5, "wpm was {}", wpm);
+        // The burst alone runs at 600 characters a minute
+        assert!((peak - 120.6).abs() < 1.0, "peak was {}", peak);
+        assert!(peak > wpm);
+    }
+
+    #[test]
+    fn test_too_few_keystrokes_report_zero() {
+        assert_eq!(typing_speed(&[]), (0.0, 0.0));
+        assert_eq!(typing_speed(&burst(0, MIN_WPM_KEYSTROKES - 1, 10)), (0.0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_stored_events_redacted_like_input_recorder() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let event = KeyboardEvent {
+            timestamp: 1,
+            event_type: KeyEventType::KeyDown,
+            key_code: 'p' as u32,
+            key_char: Some('p'),
+            modifiers: ModifierState::new(),
+            app_context: AppContext::new("Browser".to_string(), "Sign in".to_string(), 42),
+            ui_element: None,
+            is_sensitive: true,
+        };
+        KeyboardRecorder::store_event(&db, "session-1", &event, true).await.unwrap();
+
+        let stored: (i64, Option<String>, i64) = sqlx::query_as(
+            "SELECT key_code, key_char, is_sensitive FROM keyboard_events"
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(stored, (REDACTED_KEY_CODE as i64, None, 1));
+    }
+
+    #[tokio::test]
+    async fn test_trailing_chord_prefix_stored_when_recording_stops() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        let session_id = uuid::Uuid::new_v4().to_string();
+        db.create_session(&session_id, 0, "test-device").await.unwrap();
+
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        // The last key of the session, which could still have started a chord
+        event_tx
+            .send(KeyboardEvent {
+                timestamp: 1,
+                event_type: KeyEventType::KeyDown,
+                key_code: 'x' as u32,
+                key_char: Some('x'),
+                modifiers: ModifierState { ctrl: true, ..ModifierState::new() },
+                app_context: AppContext::new("Emacs".to_string(), "notes".to_string(), 42),
+                ui_element: None,
+                is_sensitive: false,
+            })
+            .unwrap();
+        drop(event_tx);
+
+        KeyboardRecorder::process_events(
+            event_rx,
+            db.clone(),
+            Arc::new(RwLock::new(Some(session_id.clone()))),
+            Arc::new(RwLock::new(true)),
+            Arc::new(AtomicBool::new(false)),
+        )
+        .await;
+
+        let commands: Vec<String> = sqlx::query_scalar("SELECT session_id FROM commands")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(commands, vec![session_id]);
+    }
+}
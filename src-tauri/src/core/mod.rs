@@ -7,6 +7,7 @@ pub mod motion_detector;
 pub mod ffmpeg_wrapper;
 pub mod video_encoder;
 pub mod os_activity;
+pub mod clipboard_monitor;
 pub mod session_manager;
 pub mod keyboard_recorder;
 pub mod input_storage;
@@ -17,3 +18,10 @@ pub mod ocr_storage;
 pub mod ocr_processor;
 pub mod search_engine;
 pub mod playback_engine;
+pub mod model_manager;
+pub mod self_test;
+pub mod auto_record;
+pub mod timeline;
+pub mod health;
+pub mod retention;
+pub mod bundle;
@@ -0,0 +1,428 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+use crate::core::config::ModelSource;
+
+#[derive(Debug, Error)]
+pub enum ModelError {
+    #[error("Unknown model: {0}")]
+    UnknownModel(String),
+
+    #[error("Model {0} is already downloading")]
+    AlreadyDownloading(String),
+
+    #[error("Download failed: {0}")]
+    Download(#[from] reqwest::Error),
+
+    #[error("Size mismatch: expected {expected} bytes, got {actual}")]
+    SizeMismatch { expected: u64, actual: u64 },
+
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type ModelResult<T> = Result<T, ModelError>;
+
+/// What a model file is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelKind {
+    Pose,
+    Face,
+    Hand,
+    Whisper,
+}
+
+/// Where to fetch a model from and how to verify it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSpec {
+    pub name: String,
+    pub kind: ModelKind,
+    pub file_name: String,
+    pub url: String,
+    /// Hex-encoded SHA-256 of the file, when known
+    pub sha256: Option<String>,
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelStatus {
+    Missing,
+    /// An interrupted download that will be resumed
+    Partial,
+    Downloading,
+    /// On disk but not verified against a manifest checksum
+    Unverified,
+    Ready,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub kind: ModelKind,
+    pub path: String,
+    pub status: ModelStatus,
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDownloadProgress {
+    pub name: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Models the app knows how to fetch
+fn default_manifest() -> Vec<ModelSpec> {
+    let mediapipe = |name: &str, kind: ModelKind, path: &str| ModelSpec {
+        name: name.to_string(),
+        kind,
+        file_name: format!("{}.task", name),
+        url: format!("https://storage.googleapis.com/mediapipe-models/{}", path),
+        sha256: None,
+        size_bytes: None,
+    };
+    let whisper = |size: &str| ModelSpec {
+        name: format!("whisper_{}", size),
+        kind: ModelKind::Whisper,
+        file_name: format!("ggml-{}.bin", size),
+        url: format!("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{}.bin", size),
+        sha256: None,
+        size_bytes: None,
+    };
+
+    vec![
+        mediapipe("pose_landmarker", ModelKind::Pose, "pose_landmarker/pose_landmarker_full/float16/latest/pose_landmarker_full.task"),
+        mediapipe("face_landmarker", ModelKind::Face, "face_landmarker/face_landmarker/float16/latest/face_landmarker.task"),
+        mediapipe("hand_landmarker", ModelKind::Hand, "hand_landmarker/hand_landmarker/float16/latest/hand_landmarker.task"),
+        whisper("tiny"),
+        whisper("base"),
+        whisper("small"),
+        whisper("medium"),
+        whisper("large-v3"),
+    ]
+}
+
+/// Tracks model files on disk and downloads missing ones
+pub struct ModelManager {
+    models_dir: PathBuf,
+    manifest: Vec<ModelSpec>,
+    downloading: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ModelManager {
+    /// Create a manager for `models_dir`, applying configured manifest entries
+    pub fn new(models_dir: PathBuf, overrides: &HashMap<String, ModelSource>) -> Self {
+        let mut manifest = default_manifest();
+
+        for (name, source) in overrides {
+            let file_name = match manifest.iter().find(|spec| &spec.name == name) {
+                Some(spec) => spec.file_name.clone(),
+                None => source
+                    .url
+                    .rsplit('/')
+                    .next()
+                    .filter(|segment| !segment.is_empty())
+                    .unwrap_or(name)
+                    .to_string(),
+            };
+
+            manifest.retain(|spec| &spec.name != name);
+            manifest.push(ModelSpec {
+                name: name.clone(),
+                kind: source.kind,
+                file_name,
+                url: source.url.clone(),
+                sha256: Some(source.sha256.to_lowercase()),
+                size_bytes: Some(source.size_bytes),
+            });
+        }
+
+        Self {
+            models_dir,
+            manifest,
+            downloading: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    fn part_path(&self, spec: &ModelSpec) -> PathBuf {
+        self.models_dir.join(format!("{}.part", spec.file_name))
+    }
+
+    /// Sidecar recording the checksum a model was verified against
+    fn verified_path(&self, spec: &ModelSpec) -> PathBuf {
+        self.models_dir.join(format!("{}.sha256", spec.file_name))
+    }
+
+    fn is_verified(&self, spec: &ModelSpec) -> bool {
+        let Some(expected) = &spec.sha256 else {
+            return false;
+        };
+        std::fs::read_to_string(self.verified_path(spec))
+            .map(|recorded| recorded.trim().eq_ignore_ascii_case(expected))
+            .unwrap_or(false)
+    }
+
+    fn spec(&self, name: &str) -> ModelResult<&ModelSpec> {
+        self.manifest
+            .iter()
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| ModelError::UnknownModel(name.to_string()))
+    }
+
+    /// Path a model is stored at, whether or not it exists yet
+    pub fn model_path(&self, name: &str) -> ModelResult<PathBuf> {
+        Ok(self.models_dir.join(&self.spec(name)?.file_name))
+    }
+
+    /// Report every known model and whether it is on disk
+    pub fn list_models(&self) -> Vec<ModelInfo> {
+        let downloading = self.downloading.lock().unwrap();
+
+        self.manifest
+            .iter()
+            .map(|spec| {
+                let path = self.models_dir.join(&spec.file_name);
+                let mut size_bytes = std::fs::metadata(&path).ok().map(|m| m.len());
+
+                let status = if downloading.contains(&spec.name) {
+                    ModelStatus::Downloading
+                } else if size_bytes.is_some() {
+                    if self.is_verified(spec) {
+                        ModelStatus::Ready
+                    } else {
+                        ModelStatus::Unverified
+                    }
+                } else if let Ok(part) = std::fs::metadata(self.part_path(spec)) {
+                    size_bytes = Some(part.len());
+                    ModelStatus::Partial
+                } else {
+                    ModelStatus::Missing
+                };
+
+                ModelInfo {
+                    name: spec.name.clone(),
+                    kind: spec.kind,
+                    path: path.to_string_lossy().to_string(),
+                    status,
+                    size_bytes,
+                }
+            })
+            .collect()
+    }
+
+    /// Download a model, verifying its checksum before it is put in place
+    ///
+    /// An interrupted download is resumed from its `.part` file when the
+    /// server supports range requests.
+    pub async fn download_model<F>(&self, name: &str, on_progress: F) -> ModelResult<PathBuf>
+    where
+        F: Fn(ModelDownloadProgress),
+    {
+        let spec = self.spec(name)?.clone();
+
+        if !self.downloading.lock().unwrap().insert(spec.name.clone()) {
+            return Err(ModelError::AlreadyDownloading(spec.name));
+        }
+
+        let result = self.fetch(&spec, on_progress).await;
+        self.downloading.lock().unwrap().remove(&spec.name);
+        result
+    }
+
+    async fn fetch<F>(&self, spec: &ModelSpec, on_progress: F) -> ModelResult<PathBuf>
+    where
+        F: Fn(ModelDownloadProgress),
+    {
+        tokio::fs::create_dir_all(&self.models_dir).await?;
+
+        let final_path = self.models_dir.join(&spec.file_name);
+        let part_path = self.part_path(spec);
+        let verified_path = self.verified_path(spec);
+        let _ = tokio::fs::remove_file(&verified_path).await;
+
+        let existing = tokio::fs::metadata(&part_path).await.map(|m| m.len()).unwrap_or(0);
+
+        let client = reqwest::Client::new();
+        let mut request = client.get(&spec.url);
+        if existing > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        }
+        let response = request.send().await?;
+
+        let (mut response, resumed) = match response.status() {
+            reqwest::StatusCode::PARTIAL_CONTENT => (Some(response), true),
+            // The part file already holds the whole model
+            reqwest::StatusCode::RANGE_NOT_SATISFIABLE if existing > 0 => (None, true),
+            _ => (Some(response.error_for_status()?), false),
+        };
+
+        // Hash what is already on disk so the final digest covers the whole file
+        let mut hasher = Sha256::new();
+        let mut downloaded_bytes = 0u64;
+        let mut file = if resumed {
+            let part = part_path.clone();
+            hasher = tokio::task::spawn_blocking(move || -> std::io::Result<Sha256> {
+                let mut hasher = Sha256::new();
+                std::io::copy(&mut std::fs::File::open(part)?, &mut hasher)?;
+                Ok(hasher)
+            })
+            .await
+            .map_err(std::io::Error::other)??;
+            downloaded_bytes = existing;
+
+            tokio::fs::OpenOptions::new().append(true).open(&part_path).await?
+        } else {
+            tokio::fs::File::create(&part_path).await?
+        };
+
+        if let Some(response) = response.as_mut() {
+            let total_bytes = response
+                .content_length()
+                .map(|len| len + downloaded_bytes)
+                .or(spec.size_bytes);
+
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                hasher.update(&chunk);
+                downloaded_bytes += chunk.len() as u64;
+
+                on_progress(ModelDownloadProgress {
+                    name: spec.name.clone(),
+                    downloaded_bytes,
+                    total_bytes,
+                });
+            }
+        }
+
+        file.flush().await?;
+        drop(file);
+
+        if let Some(expected) = spec.size_bytes {
+            if downloaded_bytes != expected {
+                // Too short can still be resumed; too long cannot
+                if downloaded_bytes > expected {
+                    let _ = tokio::fs::remove_file(&part_path).await;
+                }
+                return Err(ModelError::SizeMismatch { expected, actual: downloaded_bytes });
+            }
+        }
+
+        let actual = hex_digest(hasher);
+        if let Some(expected) = &spec.sha256 {
+            if !expected.eq_ignore_ascii_case(&actual) {
+                let _ = tokio::fs::remove_file(&part_path).await;
+                return Err(ModelError::ChecksumMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        } else {
+            println!("Downloaded {} (sha256 {}), no checksum to verify against", spec.name, actual);
+        }
+
+        tokio::fs::rename(&part_path, &final_path).await?;
+
+        if spec.sha256.is_some() {
+            tokio::fs::write(&verified_path, &actual).await?;
+        }
+
+        Ok(final_path)
+    }
+}
+
+fn hex_digest(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_models_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("models_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_missing_and_present_models() {
+        let dir = temp_models_dir();
+        let manager = ModelManager::new(dir.clone(), &HashMap::new());
+
+        let models = manager.list_models();
+        assert!(models.iter().all(|m| m.status == ModelStatus::Missing));
+
+        std::fs::write(manager.model_path("whisper_tiny").unwrap(), b"model").unwrap();
+
+        // Present but never checked against a manifest checksum
+        let tiny = manager
+            .list_models()
+            .into_iter()
+            .find(|m| m.name == "whisper_tiny")
+            .unwrap();
+        assert_eq!(tiny.status, ModelStatus::Unverified);
+        assert_eq!(tiny.size_bytes, Some(5));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_unknown_model() {
+        let manager = ModelManager::new(temp_models_dir(), &HashMap::new());
+        assert!(matches!(manager.model_path("nope"), Err(ModelError::UnknownModel(_))));
+    }
+
+    #[test]
+    fn test_manifest_overrides_and_verification() {
+        let dir = temp_models_dir();
+        let sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string();
+
+        let mut overrides = HashMap::new();
+        overrides.insert("whisper_tiny".to_string(), ModelSource {
+            kind: ModelKind::Whisper,
+            url: "https://example.com/tiny.bin".to_string(),
+            sha256: sha256.clone(),
+            size_bytes: 3,
+        });
+        overrides.insert("custom_pose".to_string(), ModelSource {
+            kind: ModelKind::Pose,
+            url: "https://example.com/models/custom_pose.task".to_string(),
+            sha256: sha256.clone(),
+            size_bytes: 3,
+        });
+        let manager = ModelManager::new(dir.clone(), &overrides);
+
+        // Overrides keep the built-in file name; new entries take it from the URL
+        assert_eq!(manager.model_path("whisper_tiny").unwrap(), dir.join("ggml-tiny.bin"));
+        assert_eq!(manager.model_path("custom_pose").unwrap(), dir.join("custom_pose.task"));
+
+        let status = |name: &str| {
+            manager.list_models().into_iter().find(|m| m.name == name).unwrap().status
+        };
+
+        std::fs::write(dir.join("ggml-tiny.bin.part"), b"ab").unwrap();
+        assert_eq!(status("whisper_tiny"), ModelStatus::Partial);
+
+        std::fs::rename(dir.join("ggml-tiny.bin.part"), dir.join("ggml-tiny.bin")).unwrap();
+        assert_eq!(status("whisper_tiny"), ModelStatus::Unverified);
+
+        std::fs::write(dir.join("ggml-tiny.bin.sha256"), &sha256).unwrap();
+        assert_eq!(status("whisper_tiny"), ModelStatus::Ready);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
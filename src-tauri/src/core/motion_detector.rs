@@ -1,6 +1,52 @@
 // Motion detection - identifies when screen content changes
 
 use crate::models::capture::RawFrame;
+use serde::{Deserialize, Serialize};
+
+/// Largest `min_changed_region_area` accepted, the pixel count of an 8K display
+///
+/// A larger gate could never be met, so motion would never be detected.
+pub const MAX_MIN_CHANGED_REGION_AREA: u64 = 7680 * 4320;
+
+/// How sensitive motion detection should be for the kind of content on screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum MotionProfile {
+    /// Mostly static text (coding, documents); small edits should count
+    Document,
+    /// Video or games; ignore constant small changes
+    Video,
+    /// A mix of both
+    #[default]
+    Mixed,
+    /// Explicit fraction of the frame that must change (0.0-1.0)
+    Custom(f32),
+}
+
+impl MotionProfile {
+    /// Fraction of the frame that must change to count as motion
+    ///
+    /// What counts as a changed pixel depends on the `MotionAlgorithm`.
+    pub fn to_threshold(&self) -> f32 {
+        match self {
+            MotionProfile::Document => 0.01,
+            MotionProfile::Video => 0.15,
+            MotionProfile::Mixed => 0.05,
+            MotionProfile::Custom(threshold) => threshold.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// How two frames are compared to decide how much of the screen changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum MotionAlgorithm {
+    /// Any pixel whose color moves past a fixed threshold counts as changed
+    #[default]
+    PixelThreshold,
+    /// Compare the average color of NxN blocks; ignores antialiasing flicker
+    Block(u32),
+    /// Compare where edges are; ignores brightness shifts, catches text changes
+    Edge,
+}
 
 /// Bounding box representing a region with motion
 #[derive(Debug, Clone)]
@@ -25,23 +71,44 @@ pub struct MotionDetector {
     previous_dimensions: Option<(u32, u32)>,
     threshold: f32,
     pixel_diff_threshold: u8,
+    algorithm: MotionAlgorithm,
+    min_changed_region_area: u64,
 }
 
 impl MotionDetector {
     /// Create a new motion detector
     ///
     /// # Arguments
-    /// * `threshold` - Percentage of changed pixels to trigger motion (0.0-1.0)
-    ///   e.g., 0.05 = 5% of pixels must change
+    /// * `threshold` - Fraction of the frame that must change to trigger motion
+    ///   (0.0-1.0), usually from `MotionProfile::to_threshold`
     pub fn new(threshold: f32) -> Self {
+        Self::with_algorithm(threshold, MotionAlgorithm::default())
+    }
+
+    /// Create a motion detector that compares frames with the given algorithm
+    pub fn with_algorithm(threshold: f32, algorithm: MotionAlgorithm) -> Self {
         Self {
             previous_frame: None,
             previous_dimensions: None,
             threshold,
             pixel_diff_threshold: 10, // RGB diff threshold per pixel
+            algorithm,
+            min_changed_region_area: 0,
         }
     }
 
+    /// Ignore changes whose bounding boxes cover fewer than `area` pixels in total
+    ///
+    /// This keeps a blinking caret or ticking clock from holding motion on.
+    /// Motion regions are tracked on a 10x10 grid, so at 1920x1080 each region
+    /// covers about 20,000 pixels. 0 disables the gate; any value up to one
+    /// region's area drops changes too scattered to fill a region, and larger
+    /// values also drop single-region changes such as a notification badge.
+    pub fn with_min_changed_region_area(mut self, area: u64) -> Self {
+        self.min_changed_region_area = area;
+        self
+    }
+
     /// Detect motion by comparing current frame with previous
     pub fn detect_motion(&mut self, current_frame: &RawFrame) -> MotionResult {
         let current_dims = (current_frame.width, current_frame.height);
@@ -64,15 +131,28 @@ impl MotionDetector {
         }
 
         let previous_data = self.previous_frame.as_ref().unwrap();
-        let changed_pixels = self.count_changed_pixels(previous_data, &current_frame.data);
+        let (width, height) = current_dims;
+        let total_pixels = (width * height) as usize;
 
-        let total_pixels = (current_frame.width * current_frame.height) as usize;
-        let changed_percentage = changed_pixels as f32 / total_pixels as f32;
+        let changed_percentage = match self.algorithm {
+            MotionAlgorithm::PixelThreshold => {
+                self.count_changed_pixels(previous_data, &current_frame.data) as f32 / total_pixels as f32
+            }
+            MotionAlgorithm::Block(block_size) => {
+                self.count_changed_block_pixels(previous_data, &current_frame.data, width, height, block_size)
+                    as f32
+                    / total_pixels as f32
+            }
+            MotionAlgorithm::Edge => {
+                self.count_changed_edges(previous_data, &current_frame.data, width, height) as f32
+                    / total_pixels as f32
+            }
+        };
 
-        let has_motion = changed_percentage >= self.threshold;
+        let mut has_motion = changed_percentage >= self.threshold;
 
         // Calculate bounding boxes if there's motion
-        let bounding_boxes = if has_motion {
+        let mut bounding_boxes = if has_motion {
             self.calculate_bounding_boxes(
                 previous_data,
                 &current_frame.data,
@@ -83,6 +163,19 @@ impl MotionDetector {
             Vec::new()
         };
 
+        // Too small an area to count, e.g. a blinking caret
+        if has_motion && self.min_changed_region_area > 0 {
+            let changed_area: u64 = bounding_boxes
+                .iter()
+                .map(|b| b.width as u64 * b.height as u64)
+                .sum();
+
+            if changed_area < self.min_changed_region_area {
+                has_motion = false;
+                bounding_boxes.clear();
+            }
+        }
+
         // Update previous frame
         self.previous_frame = Some(current_frame.data.clone());
 
@@ -131,6 +224,96 @@ impl MotionDetector {
         changed
     }
 
+    /// Count pixels covered by blocks whose average color changed
+    fn count_changed_block_pixels(
+        &self,
+        previous: &[u8],
+        current: &[u8],
+        width: u32,
+        height: u32,
+        block_size: u32,
+    ) -> usize {
+        let block_size = block_size.max(1);
+        let mut changed = 0;
+
+        for y_start in (0..height).step_by(block_size as usize) {
+            for x_start in (0..width).step_by(block_size as usize) {
+                let x_end = (x_start + block_size).min(width);
+                let y_end = (y_start + block_size).min(height);
+
+                let mut prev_sum = [0u64; 3];
+                let mut curr_sum = [0u64; 3];
+                let mut count = 0u64;
+
+                for y in y_start..y_end {
+                    for x in x_start..x_end {
+                        let i = ((y * width + x) * 4) as usize;
+                        if i + 3 < previous.len() && i + 3 < current.len() {
+                            for (c, (prev, curr)) in prev_sum.iter_mut().zip(curr_sum.iter_mut()).enumerate() {
+                                *prev += previous[i + c] as u64;
+                                *curr += current[i + c] as u64;
+                            }
+                            count += 1;
+                        }
+                    }
+                }
+
+                if count == 0 {
+                    continue;
+                }
+
+                let block_changed = (0..3).any(|c| {
+                    let diff = (prev_sum[c] / count).abs_diff(curr_sum[c] / count);
+                    diff > self.pixel_diff_threshold as u64
+                });
+
+                if block_changed {
+                    changed += count as usize;
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Count pixels that became or stopped being part of an edge
+    fn count_changed_edges(&self, previous: &[u8], current: &[u8], width: u32, height: u32) -> usize {
+        let previous_edges = Self::edge_map(previous, width, height, self.pixel_diff_threshold);
+        let current_edges = Self::edge_map(current, width, height, self.pixel_diff_threshold);
+
+        previous_edges
+            .iter()
+            .zip(&current_edges)
+            .filter(|(prev, curr)| prev != curr)
+            .count()
+    }
+
+    /// Mark pixels whose brightness differs from the right or lower neighbor
+    fn edge_map(data: &[u8], width: u32, height: u32, edge_threshold: u8) -> Vec<bool> {
+        let brightness = |x: u32, y: u32| -> i16 {
+            let i = ((y * width + x) * 4) as usize;
+            if i + 3 < data.len() {
+                ((data[i] as u16 + data[i + 1] as u16 + data[i + 2] as u16) / 3) as i16
+            } else {
+                0
+            }
+        };
+
+        let mut edges = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let here = brightness(x, y);
+                let right = if x + 1 < width { brightness(x + 1, y) } else { here };
+                let below = if y + 1 < height { brightness(x, y + 1) } else { here };
+
+                let gradient = (here - right).abs().max((here - below).abs());
+                edges.push(gradient > edge_threshold as i16);
+            }
+        }
+
+        edges
+    }
+
     /// Calculate bounding boxes for regions with motion
     /// Divides screen into grid and finds regions with changes
     fn calculate_bounding_boxes(
@@ -261,6 +444,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_motion_profile_thresholds() {
+        assert!(MotionProfile::Document.to_threshold() < MotionProfile::Mixed.to_threshold());
+        assert!(MotionProfile::Mixed.to_threshold() < MotionProfile::Video.to_threshold());
+        assert_eq!(MotionProfile::Custom(0.2).to_threshold(), 0.2);
+        assert_eq!(MotionProfile::Custom(1.5).to_threshold(), 1.0);
+        assert_eq!(MotionProfile::default().to_threshold(), 0.05);
+    }
+
     #[test]
     fn test_first_frame_has_motion() {
         let mut detector = MotionDetector::new(0.05);
@@ -300,6 +492,112 @@ mod tests {
         assert!(result.changed_percentage > 0.99);
     }
 
+    /// Gray frame where alternating pixels are nudged up and down, keeping the average
+    fn create_dithered_frame(width: u32, height: u32, base: u8, offset: u8) -> RawFrame {
+        let mut frame = create_test_frame(width, height, [base, base, base, 255]);
+        for (i, pixel) in frame.data.chunks_mut(4).enumerate() {
+            let value = if i % 2 == 0 { base + offset } else { base - offset };
+            pixel[..3].copy_from_slice(&[value, value, value]);
+        }
+        frame
+    }
+
+    #[test]
+    fn test_pixel_threshold_detects_dither() {
+        let mut detector = MotionDetector::with_algorithm(0.05, MotionAlgorithm::PixelThreshold);
+
+        detector.detect_motion(&create_test_frame(64, 64, [128, 128, 128, 255]));
+        let result = detector.detect_motion(&create_dithered_frame(64, 64, 128, 30));
+
+        assert!(result.has_motion, "Per-pixel comparison should see dithering");
+    }
+
+    #[test]
+    fn test_block_ignores_dither() {
+        let mut detector = MotionDetector::with_algorithm(0.05, MotionAlgorithm::Block(8));
+
+        detector.detect_motion(&create_test_frame(64, 64, [128, 128, 128, 255]));
+        let result = detector.detect_motion(&create_dithered_frame(64, 64, 128, 30));
+
+        assert!(!result.has_motion, "Block averages are unchanged by dithering");
+        assert_eq!(result.changed_percentage, 0.0);
+    }
+
+    #[test]
+    fn test_block_detects_region_change() {
+        let mut detector = MotionDetector::with_algorithm(0.05, MotionAlgorithm::Block(8));
+
+        let frame1 = create_test_frame(64, 64, [128, 128, 128, 255]);
+        let mut frame2 = frame1.clone();
+        // Black out a 16x16 corner (6.25% of the frame)
+        for y in 0..16 {
+            for x in 0..16 {
+                let i = ((y * 64 + x) * 4) as usize;
+                frame2.data[i..i + 3].copy_from_slice(&[0, 0, 0]);
+            }
+        }
+
+        detector.detect_motion(&frame1);
+        let result = detector.detect_motion(&frame2);
+
+        assert!(result.has_motion);
+        assert!((result.changed_percentage - 0.0625).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_edge_ignores_brightness_shift() {
+        let mut detector = MotionDetector::with_algorithm(0.05, MotionAlgorithm::Edge);
+
+        detector.detect_motion(&create_test_frame(64, 64, [100, 100, 100, 255]));
+        let result = detector.detect_motion(&create_test_frame(64, 64, [140, 140, 140, 255]));
+
+        assert!(!result.has_motion, "Flat frames have no edges to change");
+    }
+
+    #[test]
+    fn test_edge_detects_new_text() {
+        let mut detector = MotionDetector::with_algorithm(0.05, MotionAlgorithm::Edge);
+
+        let frame1 = create_test_frame(64, 64, [255, 255, 255, 255]);
+        let mut frame2 = frame1.clone();
+        // Dark vertical strokes every fourth column, like glyphs
+        for y in 0..64 {
+            for x in (0..64).step_by(4) {
+                let i = ((y * 64 + x) * 4) as usize;
+                frame2.data[i..i + 3].copy_from_slice(&[0, 0, 0]);
+            }
+        }
+
+        detector.detect_motion(&frame1);
+        let result = detector.detect_motion(&frame2);
+
+        assert!(result.has_motion, "New strokes add edges");
+    }
+
+    #[test]
+    fn test_min_changed_region_area() {
+        // 100x100 frame, so each grid region is 10x10
+        let frame1 = create_test_frame(100, 100, [255, 255, 255, 255]);
+        let mut frame2 = frame1.clone();
+        for y in 0..10 {
+            for x in 0..10 {
+                let i = ((y * 100 + x) * 4) as usize;
+                frame2.data[i..i + 3].copy_from_slice(&[0, 0, 0]);
+            }
+        }
+
+        let mut gated = MotionDetector::new(0.005).with_min_changed_region_area(500);
+        gated.detect_motion(&frame1);
+        let result = gated.detect_motion(&frame2);
+        assert!(!result.has_motion, "One 100px region is below the 500px gate");
+        assert!(result.bounding_boxes.is_empty());
+
+        let mut ungated = MotionDetector::new(0.005).with_min_changed_region_area(50);
+        ungated.detect_motion(&frame1);
+        let result = ungated.detect_motion(&frame2);
+        assert!(result.has_motion, "One 100px region passes a 50px gate");
+    }
+
     #[test]
     fn test_threshold_sensitivity() {
         let mut detector_sensitive = MotionDetector::new(0.01); // 1% threshold
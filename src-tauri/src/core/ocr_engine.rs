@@ -3,8 +3,10 @@
 use crate::models::capture::RawFrame;
 use crate::models::ocr::{BoundingBox, OcrResult, TextBlock};
 use image::{GrayImage, RgbaImage};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::LazyLock;
 use tesseract::Tesseract;
 use thiserror::Error;
 use uuid::Uuid;
@@ -181,7 +183,15 @@ impl OcrEngine {
         let cropped = self.crop_frame(frame, region)?;
 
         // Run OCR on cropped region
-        self.extract_text_from_frame(&cropped).await
+        let mut result = self.extract_text_from_frame(&cropped).await?;
+
+        // Boxes are relative to the crop; move them back into frame coordinates
+        for block in &mut result.text_blocks {
+            block.bounding_box.x += region.x;
+            block.bounding_box.y += region.y;
+        }
+
+        Ok(result)
     }
 
     /// Convert a RawFrame to an RgbaImage
@@ -239,26 +249,12 @@ impl OcrEngine {
             .set_image(image_path.to_str().unwrap())
             .map_err(|e| OcrError::Processing(e.to_string()))?;
 
-        // Get text
-        let text = tesseract
-            .get_text()
+        // hOCR gives each paragraph its box and the language pack that recognized it
+        let hocr = tesseract
+            .get_hocr_text(0)
             .map_err(|e| OcrError::Processing(e.to_string()))?;
 
-        // For now, create a single text block with the full text
-        // The tesseract crate v0.14 doesn't expose detailed bounding box API
-        // We'll return the entire text as one block
-        let text_blocks = if !text.trim().is_empty() {
-            vec![TextBlock::new(
-                text.trim().to_string(),
-                0.85, // Default confidence since we don't have per-word data
-                BoundingBox::new(0, 0, 100, 100), // Placeholder bounding box
-                self.config.languages[0].clone(),
-            )]
-        } else {
-            vec![]
-        };
-
-        Ok(text_blocks)
+        Ok(parse_hocr_paragraphs(&hocr, &self.config.languages[0]))
     }
 
     /// Crop a frame to a specific region
@@ -310,6 +306,11 @@ impl OcrEngine {
         Ok(())
     }
 
+    /// Language packs loaded into Tesseract, in priority order
+    pub fn active_languages(&self) -> &[String] {
+        &self.config.languages
+    }
+
     /// Get the current configuration
     pub fn config(&self) -> &OcrConfig {
         &self.config
@@ -323,6 +324,76 @@ impl OcrEngine {
     }
 }
 
+static HOCR_PARAGRAPH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<p class='ocr_par'([^>]*)>(.*?)</p>").unwrap());
+static HOCR_LANG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"lang='([^']+)'").unwrap());
+static HOCR_BBOX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"bbox (\d+) (\d+) (\d+) (\d+)").unwrap());
+static HOCR_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<span class='ocrx_word'([^>]*)>(.*?)</span>").unwrap());
+static HOCR_WORD_CONFIDENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"x_wconf (\d+)").unwrap());
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+/// Split hOCR output into one text block per paragraph
+///
+/// Each block carries the paragraph's bounding box in image pixels and the
+/// mean of its words' `x_wconf` scores, scaled to 0.0-1.0. Words without a
+/// score don't count towards the mean; a paragraph with no scored words gets
+/// 0.0 so the confidence threshold treats it as unverified.
+/// Paragraphs without a `lang` attribute are tagged with `default_language`.
+fn parse_hocr_paragraphs(hocr: &str, default_language: &str) -> Vec<TextBlock> {
+    HOCR_PARAGRAPH
+        .captures_iter(hocr)
+        .filter_map(|caps| {
+            let language = HOCR_LANG
+                .captures(&caps[1])
+                .map(|l| l[1].to_string())
+                .unwrap_or_else(|| default_language.to_string());
+
+            let bounding_box = HOCR_BBOX.captures(&caps[1]).map(|b| {
+                let coord = |i: usize| b[i].parse::<u32>().unwrap_or(0);
+                let (x0, y0, x1, y1) = (coord(1), coord(2), coord(3), coord(4));
+                BoundingBox::new(x0, y0, x1.saturating_sub(x0), y1.saturating_sub(y0))
+            })?;
+
+            let mut words = Vec::new();
+            let mut scores = Vec::new();
+            for w in HOCR_WORD.captures_iter(&caps[2]) {
+                let text = unescape_html(HTML_TAG.replace_all(&w[2], "").trim());
+                if text.is_empty() {
+                    continue;
+                }
+                if let Some(score) = HOCR_WORD_CONFIDENCE
+                    .captures(&w[1])
+                    .and_then(|c| c[1].parse::<f32>().ok())
+                {
+                    scores.push(score / 100.0);
+                }
+                words.push(text);
+            }
+
+            if words.is_empty() {
+                return None;
+            }
+            let confidence = if scores.is_empty() {
+                0.0
+            } else {
+                scores.iter().sum::<f32>() / scores.len() as f32
+            };
+
+            Some(TextBlock::new(words.join(" "), confidence, bounding_box, language))
+        })
+        .collect()
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -364,4 +435,72 @@ mod tests {
         assert_eq!(adjusted.width(), 100);
         assert_eq!(adjusted.height(), 100);
     }
+
+    #[test]
+    fn test_engine_reports_configured_languages() {
+        let languages = vec!["eng".to_string(), "deu".to_string()];
+        let engine = match OcrEngine::new(OcrConfig::with_languages(languages.clone())) {
+            Ok(engine) => engine,
+            Err(e) => {
+                eprintln!("Skipping test, language packs unavailable: {}", e);
+                return;
+            }
+        };
+
+        assert_eq!(engine.active_languages(), languages.as_slice());
+    }
+
+    #[test]
+    fn test_parse_hocr_tags_paragraph_language() {
+        let hocr = "<div class='ocr_carea'>\
+            <p class='ocr_par' id='par_1_1' lang='eng' title=\"bbox 0 0 10 10\">\
+              <span class='ocr_line'><span class='ocrx_word' title='bbox 0 0 5 5; x_wconf 90'>Hello</span> \
+              <span class='ocrx_word' title='bbox 5 0 10 5; x_wconf 91'><strong>&amp;</strong></span></span>\
+            </p>\
+            <p class='ocr_par' id='par_1_2' lang='deu' title=\"bbox 0 10 10 20\">\
+              <span class='ocr_line'><span class='ocrx_word' title='bbox 0 10 5 15; x_wconf 88'>Straße</span></span>\
+            </p>\
+            <p class='ocr_par' id='par_1_3' title=\"bbox 0 20 10 30\">\
+              <span class='ocr_line'><span class='ocrx_word' title='bbox 0 20 5 25; x_wconf 80'>ok</span></span>\
+            </p>\
+          </div>";
+
+        let blocks = parse_hocr_paragraphs(hocr, "eng");
+        let tagged: Vec<(&str, &str)> = blocks
+            .iter()
+            .map(|b| (b.language.as_str(), b.text.as_str()))
+            .collect();
+
+        assert_eq!(tagged, vec![("eng", "Hello &"), ("deu", "Straße"), ("eng", "ok")]);
+    }
+
+    #[test]
+    fn test_parse_hocr_paragraph_bounding_box() {
+        let hocr = "<p class='ocr_par' id='par_1_1' lang='eng' title=\"bbox 36 92 618 184\">\
+            <span class='ocrx_word' title='bbox 36 92 96 116; x_wconf 90'>Quarterly</span>\
+            </p>";
+
+        let blocks = parse_hocr_paragraphs(hocr, "eng");
+
+        assert_eq!(blocks.len(), 1);
+        let bbox = &blocks[0].bounding_box;
+        assert_eq!((bbox.x, bbox.y, bbox.width, bbox.height), (36, 92, 582, 92));
+    }
+
+    #[test]
+    fn test_parse_hocr_paragraph_confidence_from_word_scores() {
+        let hocr = "<p class='ocr_par' id='par_1_1' lang='eng' title=\"bbox 0 0 100 20\">\
+            <span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 96'>Invoice</span> \
+            <span class='ocrx_word' title='bbox 50 0 100 20; x_wconf 40'>l0g0</span>\
+            </p>\
+            <p class='ocr_par' id='par_1_2' lang='eng' title=\"bbox 0 30 100 50\">\
+            <span class='ocrx_word' title='bbox 0 30 40 50'>unscored</span>\
+            </p>";
+
+        let blocks = parse_hocr_paragraphs(hocr, "eng");
+
+        assert_eq!(blocks.len(), 2);
+        assert!((blocks[0].confidence - 0.68).abs() < 1e-6);
+        assert_eq!(blocks[1].confidence, 0.0);
+    }
 }
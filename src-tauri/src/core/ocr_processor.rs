@@ -54,7 +54,7 @@ pub struct OcrJob {
 // ==============================================================================
 
 pub struct OcrProcessor {
-    ocr_engine: Arc<OcrEngine>,
+    ocr_engine: Arc<RwLock<OcrEngine>>,
     storage: Arc<OcrStorage>,
     processing_queue: Arc<RwLock<VecDeque<OcrJob>>>,
     is_processing: Arc<RwLock<bool>>,
@@ -64,7 +64,7 @@ pub struct OcrProcessor {
 
 impl OcrProcessor {
     pub fn new(
-        ocr_engine: Arc<OcrEngine>,
+        ocr_engine: Arc<RwLock<OcrEngine>>,
         storage: Arc<OcrStorage>,
         config: OcrProcessorConfig,
     ) -> Self {
@@ -184,11 +184,58 @@ impl OcrProcessor {
         Ok(())
     }
 
+    /// Re-run OCR over every stored image of a session with the current engine
+    ///
+    /// The OCR rows of the reprocessed images are replaced in one transaction;
+    /// images that can't be loaded keep their earlier text. Sessions without
+    /// stored frames are left untouched. Returns the number of text blocks
+    /// regenerated.
+    pub async fn reprocess_session(&self, session_id: Uuid) -> Result<u64, OcrError> {
+        let images = self
+            .storage
+            .get_session_images(session_id)
+            .await
+            .map_err(|e| OcrError::Processing(format!("Failed to list session frames: {}", e)))?;
+
+        if images.is_empty() {
+            return Ok(0);
+        }
+
+        let ocr_engine = self.ocr_engine.read().await;
+        let mut results = Vec::with_capacity(images.len());
+
+        for (timestamp, path) in images {
+            let frame = match Self::load_frame(&path) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    eprintln!("Skipping frame {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            results.push(ProcessedOcrResult {
+                session_id,
+                timestamp,
+                frame_path: Some(path),
+                ocr_result: ocr_engine.extract_text_from_frame(&frame).await?,
+            });
+        }
+        drop(ocr_engine);
+
+        self.storage
+            .replace_frame_results(&results)
+            .await
+            .map_err(|e| OcrError::Processing(format!("Failed to store OCR results: {}", e)))
+    }
+
     /// Process a single OCR job
     async fn process_job(
-        ocr_engine: &Arc<OcrEngine>,
+        ocr_engine: &Arc<RwLock<OcrEngine>>,
         job: OcrJob,
     ) -> Result<ProcessedOcrResult, OcrError> {
+        // Hold the engine for the whole job so a language change can't split it
+        let ocr_engine = ocr_engine.read().await;
+
         // Load frame from disk
         let frame = Self::load_frame(&job.frame_path)?;
 
@@ -340,6 +387,148 @@ pub struct OcrMetrics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::database::Database;
+    use crate::core::ocr_engine::OcrConfig;
+    use crate::models::ocr::TextBlock;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_processor() -> Option<(Arc<Database>, OcrProcessor)> {
+        let engine = match OcrEngine::new(OcrConfig::for_screenshots()) {
+            Ok(engine) => engine,
+            Err(e) => {
+                eprintln!("Skipping test, Tesseract unavailable: {}", e);
+                return None;
+            }
+        };
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        let processor = OcrProcessor::new(
+            Arc::new(RwLock::new(engine)),
+            Arc::new(OcrStorage::new(db.clone())),
+            OcrProcessorConfig::default(),
+        );
+
+        Some((db, processor))
+    }
+
+    async fn ocr_texts(db: &Database, session_id: Uuid) -> Vec<String> {
+        sqlx::query_scalar("SELECT text FROM ocr_results WHERE session_id = ? ORDER BY text")
+            .bind(session_id.to_string())
+            .fetch_all(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_session_replaces_results() {
+        let Some((db, processor)) = test_processor().await else {
+            return;
+        };
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 0, "test-device").await.unwrap();
+
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ocr_hello.png");
+        sqlx::query("INSERT INTO frames (id, session_id, timestamp, file_path, width, height) VALUES (?, ?, 1000, ?, 344, 136)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(session_id.to_string())
+            .bind(fixture.to_string_lossy().to_string())
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        // Recognition from before the language change
+        let stale = ProcessedOcrResult {
+            session_id,
+            timestamp: 1000,
+            frame_path: Some(fixture),
+            ocr_result: OcrResult::new(
+                1000,
+                vec![TextBlock::new(
+                    "HEII0".to_string(),
+                    0.9,
+                    BoundingBox::new(0, 0, 10, 10),
+                    "eng".to_string(),
+                )],
+                10,
+            ),
+        };
+        processor.storage.save_ocr_result(stale).await.unwrap();
+
+        // A frame whose file is gone keeps the text recognized earlier
+        sqlx::query("INSERT INTO frames (id, session_id, timestamp, file_path, width, height) VALUES (?, ?, 2000, '/nonexistent/frame.png', 344, 136)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(session_id.to_string())
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let unreadable = ProcessedOcrResult {
+            session_id,
+            timestamp: 2000,
+            frame_path: None,
+            ocr_result: OcrResult::new(
+                2000,
+                vec![TextBlock::new(
+                    "kept".to_string(),
+                    0.9,
+                    BoundingBox::new(0, 0, 10, 10),
+                    "eng".to_string(),
+                )],
+                10,
+            ),
+        };
+        processor.storage.save_ocr_result(unreadable).await.unwrap();
+
+        let blocks = processor.reprocess_session(session_id).await.unwrap();
+
+        let texts = ocr_texts(&db, session_id).await;
+        assert!(blocks > 0);
+        assert_eq!(texts.len() as u64, blocks + 1);
+        assert!(texts.iter().any(|t| t == "kept"));
+        assert!(texts.iter().all(|t| t != "HEII0"));
+        assert!(texts.iter().any(|t| t.to_uppercase().contains("HELLO")));
+
+        // The FTS index follows the replacement
+        let hits = processor.storage.search_text("HELLO", Some(session_id), 10).await.unwrap();
+        assert!(!hits.is_empty());
+        let stale_hits = processor.storage.search_text("HEII0", Some(session_id), 10).await.unwrap();
+        assert!(stale_hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_session_skips_sessions_without_frames() {
+        let Some((db, processor)) = test_processor().await else {
+            return;
+        };
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 0, "test-device").await.unwrap();
+
+        let existing = ProcessedOcrResult {
+            session_id,
+            timestamp: 1000,
+            frame_path: None,
+            ocr_result: OcrResult::new(
+                1000,
+                vec![TextBlock::new(
+                    "kept".to_string(),
+                    0.9,
+                    BoundingBox::new(0, 0, 10, 10),
+                    "eng".to_string(),
+                )],
+                10,
+            ),
+        };
+        processor.storage.save_ocr_result(existing).await.unwrap();
+
+        assert_eq!(processor.reprocess_session(session_id).await.unwrap(), 0);
+        assert_eq!(ocr_texts(&db, session_id).await, vec!["kept".to_string()]);
+    }
 
     #[test]
     fn test_ocr_processor_config_default() {
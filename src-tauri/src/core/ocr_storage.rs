@@ -53,18 +53,53 @@ impl OcrStorage {
 
     /// Save OCR result to database
     pub async fn save_ocr_result(&self, result: ProcessedOcrResult) -> Result<()> {
-        let pool = self.db.pool();
+        let mut tx = self.db.pool().begin().await?;
+        Self::insert_result(&mut tx, &result).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Replace the OCR rows of each result's frame with the freshly processed text
+    ///
+    /// Frames without a new result keep their rows. Runs in one transaction,
+    /// so searches see either the old or the new text. The FTS index follows
+    /// through the ocr_results triggers. Returns the number of text blocks
+    /// written.
+    pub async fn replace_frame_results(&self, results: &[ProcessedOcrResult]) -> Result<u64> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let mut blocks = 0;
+        for result in results {
+            sqlx::query("DELETE FROM ocr_results WHERE session_id = ? AND timestamp = ?")
+                .bind(result.session_id.to_string())
+                .bind(result.timestamp)
+                .execute(&mut *tx)
+                .await?;
+
+            blocks += Self::insert_result(&mut tx, result).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(blocks)
+    }
+
+    /// Insert each text block of a result as a separate row
+    pub(crate) async fn insert_result(
+        conn: &mut sqlx::SqliteConnection,
+        result: &ProcessedOcrResult,
+    ) -> Result<u64> {
         let created_at = chrono::Utc::now().timestamp();
+        let session_id = result.session_id.to_string();
+        let frame_path = result
+            .frame_path
+            .as_ref()
+            .and_then(|p| p.to_str())
+            .unwrap_or("");
 
-        // Save each text block as a separate row
         for text_block in &result.ocr_result.text_blocks {
             let id = Uuid::new_v4().to_string();
-            let session_id = result.session_id.to_string();
-            let frame_path = result
-                .frame_path
-                .as_ref()
-                .and_then(|p| p.to_str())
-                .unwrap_or("");
             let bounding_box = serde_json::to_string(&text_block.bounding_box)?;
 
             sqlx::query(
@@ -76,7 +111,7 @@ impl OcrStorage {
                 "#,
             )
             .bind(id)
-            .bind(session_id)
+            .bind(&session_id)
             .bind(result.timestamp)
             .bind(frame_path)
             .bind(&text_block.text)
@@ -85,11 +120,36 @@ impl OcrStorage {
             .bind(&text_block.language)
             .bind(result.ocr_result.processing_time_ms as i64)
             .bind(created_at)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
 
-        Ok(())
+        Ok(result.ocr_result.text_blocks.len() as u64)
+    }
+
+    /// Images stored for a session, as (timestamp, path), oldest first
+    ///
+    /// Includes the session's base layer, stamped with the session start.
+    pub async fn get_session_images(&self, session_id: Uuid) -> Result<Vec<(i64, PathBuf)>> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT start_timestamp, base_layer_path FROM sessions
+            WHERE id = ? AND base_layer_path IS NOT NULL
+            UNION ALL
+            SELECT timestamp, file_path FROM frames
+            WHERE session_id = ?
+            ORDER BY 1
+            "#,
+        )
+        .bind(session_id.to_string())
+        .bind(session_id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(timestamp, path)| (timestamp, PathBuf::from(path)))
+            .collect())
     }
 
     /// Get OCR results for a session
@@ -335,6 +395,49 @@ pub struct OcrStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn bbox_tuple(bbox: &BoundingBox) -> (u32, u32, u32, u32) {
+        (bbox.x, bbox.y, bbox.width, bbox.height)
+    }
+
+    #[tokio::test]
+    async fn test_bounding_boxes_round_trip_and_reach_search_hits() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 0, "test-device").await.unwrap();
+
+        let storage = OcrStorage::new(db);
+        let blocks = vec![
+            TextBlock::new("Quarterly report".to_string(), 0.9, BoundingBox::new(12, 34, 560, 48), "eng".to_string()),
+            TextBlock::new("Revenue".to_string(), 0.8, BoundingBox::new(12, 120, 200, 30), "eng".to_string()),
+        ];
+        storage
+            .save_ocr_result(ProcessedOcrResult {
+                session_id,
+                timestamp: 1000,
+                frame_path: None,
+                ocr_result: OcrResult::new(1000, blocks, 25),
+            })
+            .await
+            .unwrap();
+
+        let mut stored = storage.get_session_ocr_results(session_id, None).await.unwrap();
+        stored.sort_by_key(|r| r.bounding_box.y);
+        let boxes: Vec<_> = stored.iter().map(|r| bbox_tuple(&r.bounding_box)).collect();
+        assert_eq!(boxes, vec![(12, 34, 560, 48), (12, 120, 200, 30)]);
+
+        let hits = storage.search_text("revenue", Some(session_id), 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(bbox_tuple(&hits[0].bounding_box), (12, 120, 200, 30));
+    }
 
     #[test]
     fn test_processed_ocr_result_serialization() {
@@ -4,11 +4,12 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use uuid::Uuid;
 
 use crate::core::consent::ConsentManager;
 use crate::core::database::Database;
+use crate::core::session_manager::{categorize_app, ActivitySignal};
 
 // ==============================================================================
 // OsMonitor Trait
@@ -80,28 +81,43 @@ impl FocusTracker {
     }
 
     fn switch_focus(&mut self, new_pid: u32, app_name: String, bundle_id: String, timestamp: i64) -> Option<FocusDuration> {
-        if let Some((old_pid, old_name, old_bundle, start)) = self.current_app.take() {
-            let duration_ms = timestamp - start;
-            let duration = Duration::from_millis(duration_ms as u64);
-
-            self.focus_history.entry(old_pid)
-                .and_modify(|d| *d += duration)
-                .or_insert(duration);
-
-            self.current_app = Some((new_pid, app_name, bundle_id, timestamp));
-
-            return Some(FocusDuration {
-                process_id: old_pid,
-                app_name: old_name,
-                bundle_id: old_bundle,
-                duration_ms,
-                start_time: start,
-                end_time: timestamp,
-            });
-        }
-
+        let ended = self.finish(timestamp);
         self.current_app = Some((new_pid, app_name, bundle_id, timestamp));
-        None
+        ended
+    }
+
+    /// End the current focus span without another app taking over
+    fn finish(&mut self, timestamp: i64) -> Option<FocusDuration> {
+        let (old_pid, old_name, old_bundle, start) = self.current_app.take()?;
+        let duration_ms = (timestamp - start).max(0);
+        let duration = Duration::from_millis(duration_ms as u64);
+
+        self.focus_history.entry(old_pid)
+            .and_modify(|d| *d += duration)
+            .or_insert(duration);
+
+        Some(FocusDuration {
+            process_id: old_pid,
+            app_name: old_name,
+            bundle_id: old_bundle,
+            duration_ms,
+            start_time: start,
+            end_time: timestamp,
+        })
+    }
+
+    /// The current focus span as if it ended at `timestamp`, leaving it open
+    fn open_span(&self, timestamp: i64) -> Option<FocusDuration> {
+        let (pid, name, bundle, start) = self.current_app.as_ref()?;
+
+        Some(FocusDuration {
+            process_id: *pid,
+            app_name: name.clone(),
+            bundle_id: bundle.clone(),
+            duration_ms: (timestamp - start).max(0),
+            start_time: *start,
+            end_time: timestamp,
+        })
     }
 
     fn remove_app(&mut self, process_id: u32) {
@@ -140,6 +156,39 @@ pub struct AppUsageStats {
     pub launch_count: i64,
     pub first_launch: i64,
     pub last_terminate: Option<i64>,
+    /// Focus time that overlapped with keyboard/mouse activity
+    #[sqlx(default)]
+    pub active_focus_duration_ms: i64,
+    /// Activity category from the app name, e.g. "development"
+    #[sqlx(default)]
+    pub category: String,
+}
+
+/// How long after an input event the user still counts as present
+const INPUT_ACTIVE_WINDOW_MS: i64 = 60_000;
+
+/// Merge sorted input timestamps into spans where the user was present
+fn input_active_spans(timestamps: &[i64]) -> Vec<(i64, i64)> {
+    let mut spans: Vec<(i64, i64)> = Vec::new();
+
+    for &ts in timestamps {
+        let end = ts + INPUT_ACTIVE_WINDOW_MS;
+        match spans.last_mut() {
+            Some(last) if ts <= last.1 => last.1 = last.1.max(end),
+            _ => spans.push((ts, end)),
+        }
+    }
+
+    spans
+}
+
+/// Total overlap between a focus span and the input-active spans
+fn active_overlap_ms(start: i64, end: i64, active: &[(i64, i64)]) -> i64 {
+    active
+        .iter()
+        .take_while(|(a_start, _)| *a_start < end)
+        .map(|(a_start, a_end)| (end.min(*a_end) - start.max(*a_start)).max(0))
+        .sum()
 }
 
 #[derive(Clone)]
@@ -152,41 +201,6 @@ impl ActivityStorage {
         Self { db }
     }
 
-    pub async fn init_schema(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let pool = self.db.pool();
-
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS app_usage (
-                id TEXT PRIMARY KEY,
-                session_id TEXT NOT NULL,
-                app_name TEXT NOT NULL,
-                bundle_id TEXT NOT NULL,
-                process_id INTEGER NOT NULL,
-                start_timestamp INTEGER NOT NULL,
-                end_timestamp INTEGER,
-                focus_duration_ms INTEGER DEFAULT 0,
-                background_duration_ms INTEGER DEFAULT 0,
-                FOREIGN KEY (session_id) REFERENCES sessions(id)
-            )"
-        )
-        .execute(pool)
-        .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_app_usage_session ON app_usage(session_id)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_app_usage_app ON app_usage(app_name)")
-            .execute(pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_app_usage_time ON app_usage(start_timestamp)")
-            .execute(pool)
-            .await?;
-
-        Ok(())
-    }
-
     pub async fn record_app_launch(&self, session_id: &str, event: AppEvent) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let id = Uuid::new_v4().to_string();
 
@@ -219,7 +233,21 @@ impl ActivityStorage {
         Ok(())
     }
 
-    pub async fn record_focus_duration(&self, duration: FocusDuration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn record_focus_duration(&self, session_id: &str, duration: FocusDuration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            "INSERT INTO app_focus_spans (id, session_id, app_name, bundle_id, start_timestamp, end_timestamp, duration_ms)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(&duration.app_name)
+        .bind(&duration.bundle_id)
+        .bind(duration.start_time)
+        .bind(duration.end_time)
+        .bind(duration.duration_ms)
+        .execute(self.db.pool())
+        .await?;
+
         sqlx::query(
             "UPDATE app_usage
              SET focus_duration_ms = focus_duration_ms + ?
@@ -233,6 +261,40 @@ impl ActivityStorage {
         Ok(())
     }
 
+    /// When the session ended, if it has been ended
+    pub async fn session_end_timestamp(&self, session_id: &str) -> Result<Option<i64>, Box<dyn std::error::Error + Send + Sync>> {
+        let end_timestamp: Option<Option<i64>> = sqlx::query_scalar(
+            "SELECT end_timestamp FROM sessions WHERE id = ?"
+        )
+        .bind(session_id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(end_timestamp.flatten())
+    }
+
+    /// Close out a session's app usage when recording stops
+    ///
+    /// The app still in focus is credited with its last span, then every row
+    /// that never saw a terminate is ended at `end_timestamp` so historical
+    /// sessions don't read as still running.
+    pub async fn finalize_session(&self, session_id: &str, open_focus: Option<FocusDuration>, end_timestamp: i64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(duration) = open_focus {
+            self.record_focus_duration(session_id, duration).await?;
+        }
+
+        sqlx::query(
+            "UPDATE app_usage SET end_timestamp = MAX(start_timestamp, ?)
+             WHERE session_id = ? AND end_timestamp IS NULL"
+        )
+        .bind(end_timestamp)
+        .bind(session_id)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_app_usage_for_session(&self, session_id: String) -> Result<Vec<AppUsage>, Box<dyn std::error::Error + Send + Sync>> {
         let results = sqlx::query_as::<_, AppUsage>(
             "SELECT id, session_id, app_name, bundle_id, process_id,
@@ -248,8 +310,16 @@ impl ActivityStorage {
         Ok(results)
     }
 
-    pub async fn get_app_usage_stats(&self, session_id: String) -> Result<Vec<AppUsageStats>, Box<dyn std::error::Error + Send + Sync>> {
-        let results = sqlx::query_as::<_, AppUsageStats>(
+    /// Per-app totals for a session
+    ///
+    /// `open_focus` is the span of the app still in focus, which isn't stored
+    /// until focus moves on; it's counted as if it ended now.
+    pub async fn get_app_usage_stats(
+        &self,
+        session_id: String,
+        open_focus: Option<FocusDuration>,
+    ) -> Result<Vec<AppUsageStats>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = sqlx::query_as::<_, AppUsageStats>(
             "SELECT
                 app_name,
                 bundle_id,
@@ -263,11 +333,86 @@ impl ActivityStorage {
              GROUP BY app_name, bundle_id
              ORDER BY total_focus_duration_ms DESC"
         )
+        .bind(&session_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        for stat in &mut results {
+            stat.category = categorize_app(&stat.app_name).to_string();
+            if let Some(open) = open_focus.as_ref().filter(|open| open.app_name == stat.app_name && open.bundle_id == stat.bundle_id) {
+                stat.total_focus_duration_ms += open.duration_ms;
+            }
+        }
+
+        self.fill_active_focus(&session_id, results, open_focus).await
+    }
+
+    /// Split focus time into attended vs unattended using input-event presence
+    async fn fill_active_focus(
+        &self,
+        session_id: &str,
+        mut stats: Vec<AppUsageStats>,
+        open_focus: Option<FocusDuration>,
+    ) -> Result<Vec<AppUsageStats>, Box<dyn std::error::Error + Send + Sync>> {
+        #[derive(sqlx::FromRow)]
+        struct FocusSpanRow {
+            app_name: String,
+            bundle_id: String,
+            start_timestamp: i64,
+            end_timestamp: i64,
+        }
+
+        let input_timestamps: Vec<i64> = sqlx::query_scalar(
+            "SELECT timestamp FROM keyboard_events WHERE session_id = ?
+             UNION ALL
+             SELECT timestamp FROM mouse_events WHERE session_id = ?
+             ORDER BY timestamp"
+        )
+        .bind(session_id)
         .bind(session_id)
         .fetch_all(self.db.pool())
         .await?;
 
-        Ok(results)
+        if input_timestamps.is_empty() {
+            // No input data to judge by, so don't discount any focus time
+            for stat in &mut stats {
+                stat.active_focus_duration_ms = stat.total_focus_duration_ms;
+            }
+            return Ok(stats);
+        }
+
+        let active = input_active_spans(&input_timestamps);
+
+        let mut spans = sqlx::query_as::<_, FocusSpanRow>(
+            "SELECT app_name, bundle_id, start_timestamp, end_timestamp
+             FROM app_focus_spans
+             WHERE session_id = ?"
+        )
+        .bind(session_id)
+        .fetch_all(self.db.pool())
+        .await?;
+        spans.extend(open_focus.map(|open| FocusSpanRow {
+            app_name: open.app_name,
+            bundle_id: open.bundle_id,
+            start_timestamp: open.start_time,
+            end_timestamp: open.end_time,
+        }));
+
+        let mut active_by_app: HashMap<(String, String), i64> = HashMap::new();
+        for span in spans {
+            let first = active.partition_point(|(_, a_end)| *a_end <= span.start_timestamp);
+            let overlap = active_overlap_ms(span.start_timestamp, span.end_timestamp, &active[first..]);
+            *active_by_app.entry((span.app_name, span.bundle_id)).or_insert(0) += overlap;
+        }
+
+        for stat in &mut stats {
+            stat.active_focus_duration_ms = active_by_app
+                .get(&(stat.app_name.clone(), stat.bundle_id.clone()))
+                .copied()
+                .unwrap_or(0);
+        }
+
+        Ok(stats)
     }
 }
 
@@ -281,13 +426,15 @@ pub struct OsActivityRecorder {
     storage: ActivityStorage,
     current_session_id: Arc<RwLock<Option<String>>>,
     is_recording: Arc<RwLock<bool>>,
+    activity_sink: Arc<RwLock<Option<mpsc::UnboundedSender<ActivitySignal>>>>,
+    focus_tx: broadcast::Sender<AppEvent>,
+    focus_tracker: Arc<RwLock<FocusTracker>>,
 }
 
 impl OsActivityRecorder {
     pub async fn new(consent_manager: Arc<ConsentManager>, db: Arc<Database>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let monitor = create_os_monitor()?;
         let storage = ActivityStorage::new(db);
-        storage.init_schema().await?;
 
         Ok(Self {
             monitor: Arc::new(RwLock::new(monitor)),
@@ -295,9 +442,22 @@ impl OsActivityRecorder {
             storage,
             current_session_id: Arc::new(RwLock::new(None)),
             is_recording: Arc::new(RwLock::new(false)),
+            activity_sink: Arc::new(RwLock::new(None)),
+            focus_tx: broadcast::channel(32).0,
+            focus_tracker: Arc::new(RwLock::new(FocusTracker::new())),
         })
     }
 
+    /// Report focus changes to the session manager's live metrics
+    pub async fn set_activity_sink(&self, sink: mpsc::UnboundedSender<ActivitySignal>) {
+        *self.activity_sink.write().await = Some(sink);
+    }
+
+    /// Subscribe to focus gains seen while recording
+    pub fn subscribe_focus(&self) -> broadcast::Receiver<AppEvent> {
+        self.focus_tx.subscribe()
+    }
+
     pub async fn start_recording(&self, session_id: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // Check OsActivity consent
         use crate::core::consent::Feature;
@@ -317,6 +477,7 @@ impl OsActivityRecorder {
 
         // Store session ID
         *self.current_session_id.write().await = Some(session_id.clone());
+        *self.focus_tracker.write().await = FocusTracker::new();
 
         // Start monitoring
         let mut monitor = self.monitor.write().await;
@@ -332,9 +493,12 @@ impl OsActivityRecorder {
         let storage = self.storage.clone();
         let current_session_id = self.current_session_id.clone();
         let is_recording_clone = self.is_recording.clone();
+        let activity_sink = self.activity_sink.read().await.clone();
+        let focus_tx = self.focus_tx.clone();
+        let focus_tracker = self.focus_tracker.clone();
 
         tokio::spawn(async move {
-            Self::process_events(event_rx, storage, current_session_id, is_recording_clone).await;
+            Self::process_events(event_rx, storage, current_session_id, is_recording_clone, activity_sink, focus_tx, focus_tracker).await;
         });
 
         Ok(())
@@ -350,13 +514,33 @@ impl OsActivityRecorder {
         monitor.stop_monitoring().await?;
 
         *is_recording = false;
-        *self.current_session_id.write().await = None;
+
+        if let Some(session_id) = self.current_session_id.write().await.take() {
+            let end_timestamp = self.storage
+                .session_end_timestamp(&session_id)
+                .await?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            let open_focus = self.focus_tracker.write().await.finish(end_timestamp);
+            self.storage.finalize_session(&session_id, open_focus, end_timestamp).await?;
+        }
 
         Ok(())
     }
 
     pub async fn get_app_usage_stats(&self, session_id: String) -> Result<Vec<AppUsageStats>, Box<dyn std::error::Error + Send + Sync>> {
-        self.storage.get_app_usage_stats(session_id).await
+        let open_focus = self
+            .open_focus_span()
+            .await
+            .filter(|(open_session, _)| *open_session == session_id)
+            .map(|(_, span)| span);
+        self.storage.get_app_usage_stats(session_id, open_focus).await
+    }
+
+    /// The session being recorded and its app in focus, with the span so far
+    pub async fn open_focus_span(&self) -> Option<(String, FocusDuration)> {
+        let session_id = self.current_session_id.read().await.clone()?;
+        let span = self.focus_tracker.read().await.open_span(chrono::Utc::now().timestamp_millis())?;
+        Some((session_id, span))
     }
 
     pub async fn get_current_app(&self) -> Result<Option<AppInfo>, Box<dyn std::error::Error + Send + Sync>> {
@@ -374,9 +558,10 @@ impl OsActivityRecorder {
         storage: ActivityStorage,
         current_session_id: Arc<RwLock<Option<String>>>,
         is_recording: Arc<RwLock<bool>>,
+        activity_sink: Option<mpsc::UnboundedSender<ActivitySignal>>,
+        focus_tx: broadcast::Sender<AppEvent>,
+        focus_tracker: Arc<RwLock<FocusTracker>>,
     ) {
-        let mut focus_tracker = FocusTracker::new();
-
         while let Some(event) = event_rx.recv().await {
             // Check if still recording
             if !*is_recording.read().await {
@@ -398,16 +583,25 @@ impl OsActivityRecorder {
                     if let Err(e) = storage.record_app_terminate(event.clone()).await {
                         eprintln!("Error recording app terminate: {}", e);
                     }
-                    focus_tracker.remove_app(event.app_info.process_id);
+                    focus_tracker.write().await.remove_app(event.app_info.process_id);
                 }
                 AppEventType::FocusGain => {
-                    if let Some(duration) = focus_tracker.switch_focus(
+                    if let Some(sink) = &activity_sink {
+                        let _ = sink.send(ActivitySignal::FocusChange {
+                            app_name: event.app_info.name.clone(),
+                            timestamp: event.timestamp,
+                        });
+                    }
+                    let _ = focus_tx.send(event.clone());
+
+                    let ended = focus_tracker.write().await.switch_focus(
                         event.app_info.process_id,
                         event.app_info.name.clone(),
                         event.app_info.bundle_id.clone(),
                         event.timestamp,
-                    ) {
-                        if let Err(e) = storage.record_focus_duration(duration).await {
+                    );
+                    if let Some(duration) = ended {
+                        if let Err(e) = storage.record_focus_duration(&session_id, duration).await {
                             eprintln!("Error recording focus duration: {}", e);
                         }
                     }
@@ -419,3 +613,176 @@ impl OsActivityRecorder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn launch(name: &str, process_id: u32, timestamp: i64) -> AppEvent {
+        AppEvent {
+            timestamp,
+            event_type: AppEventType::Launch,
+            app_info: AppInfo::new(name.to_string(), format!("com.example.{}", name), process_id),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finalize_closes_app_focused_at_stop() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+        db.end_session("session-1", 10_000).await.unwrap();
+
+        let storage = ActivityStorage::new(db.clone());
+        storage.record_app_launch("session-1", launch("editor", 1, 1_000)).await.unwrap();
+        storage.record_app_launch("session-1", launch("browser", 2, 1_500)).await.unwrap();
+
+        let mut tracker = FocusTracker::new();
+        tracker.switch_focus(1, "editor".to_string(), "com.example.editor".to_string(), 1_000);
+        let ended = tracker.switch_focus(2, "browser".to_string(), "com.example.browser".to_string(), 3_000);
+        storage.record_focus_duration("session-1", ended.unwrap()).await.unwrap();
+
+        // The browser still has focus when recording stops
+        let end_timestamp = storage.session_end_timestamp("session-1").await.unwrap().unwrap();
+        storage.finalize_session("session-1", tracker.finish(end_timestamp), end_timestamp).await.unwrap();
+
+        let usage = storage.get_app_usage_for_session("session-1".to_string()).await.unwrap();
+        let app = |name: &str| usage.iter().find(|u| u.app_name == name).unwrap();
+
+        assert_eq!(app("browser").end_timestamp, Some(10_000));
+        assert_eq!(app("browser").focus_duration_ms, 7_000);
+        assert_eq!(app("editor").end_timestamp, Some(10_000));
+        assert_eq!(app("editor").focus_duration_ms, 2_000);
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_tagged_with_category() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = ActivityStorage::new(db.clone());
+        storage.record_app_launch("session-1", launch("Visual Studio Code", 1, 1_000)).await.unwrap();
+        storage.record_app_launch("session-1", launch("Slack", 2, 2_000)).await.unwrap();
+
+        let stats = storage.get_app_usage_stats("session-1".to_string(), None).await.unwrap();
+        let category = |name: &str| &stats.iter().find(|s| s.app_name == name).unwrap().category;
+
+        assert_eq!(category("Visual Studio Code"), "development");
+        assert_eq!(category("Slack"), "communication");
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_count_the_app_still_in_focus() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = ActivityStorage::new(db.clone());
+        storage.record_app_launch("session-1", launch("editor", 1, 1_000)).await.unwrap();
+        storage.record_app_launch("session-1", launch("browser", 2, 1_500)).await.unwrap();
+
+        let mut tracker = FocusTracker::new();
+        tracker.switch_focus(1, "editor".to_string(), "com.example.editor".to_string(), 1_000);
+        let ended = tracker.switch_focus(2, "browser".to_string(), "com.example.browser".to_string(), 3_000);
+        storage.record_focus_duration("session-1", ended.unwrap()).await.unwrap();
+
+        // Typing throughout, so all focus time counts as attended
+        for timestamp in (1_000..8_000).step_by(1_000) {
+            sqlx::query(
+                "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id)
+                 VALUES (?, 'session-1', ?, 'key_down', 65, '{}', 'browser', '', 2)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(timestamp)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+
+        // The browser has had focus since 3s and still has it at 8s
+        let open_focus = tracker.open_span(8_000);
+        let stats = storage.get_app_usage_stats("session-1".to_string(), open_focus).await.unwrap();
+        let stat = |name: &str| stats.iter().find(|s| s.app_name == name).unwrap();
+
+        assert_eq!(stat("editor").total_focus_duration_ms, 2_000);
+        assert_eq!(stat("browser").total_focus_duration_ms, 5_000);
+        assert!(stat("browser").active_focus_duration_ms > 0);
+
+        // Peeking leaves the span open
+        assert_eq!(tracker.finish(9_000).unwrap().duration_ms, 6_000);
+    }
+
+    #[tokio::test]
+    async fn test_active_focus_excludes_time_without_input() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = ActivityStorage::new(db.clone());
+        storage.record_app_launch("session-1", launch("editor", 1, 0)).await.unwrap();
+
+        let mut tracker = FocusTracker::new();
+        tracker.switch_focus(1, "editor".to_string(), "com.example.editor".to_string(), 0);
+        storage.record_focus_duration("session-1", tracker.finish(300_000).unwrap()).await.unwrap();
+
+        // Typing for the first 10s, then nothing for the rest of the five minutes
+        for timestamp in [0, 10_000] {
+            sqlx::query(
+                "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id)
+                 VALUES (?, 'session-1', ?, 'key_down', 65, '{}', 'editor', '', 1)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(timestamp)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+
+        let stats = storage.get_app_usage_stats("session-1".to_string(), None).await.unwrap();
+
+        assert_eq!(stats[0].total_focus_duration_ms, 300_000);
+        assert_eq!(stats[0].active_focus_duration_ms, 10_000 + INPUT_ACTIVE_WINDOW_MS);
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_report_input_query_errors() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db.create_session("session-1", 0, "test-device").await.unwrap();
+
+        let storage = ActivityStorage::new(db.clone());
+        storage.record_app_launch("session-1", launch("editor", 1, 0)).await.unwrap();
+
+        // A failing input query must not read as "no input recorded"
+        sqlx::query("DROP TABLE mouse_events").execute(db.pool()).await.unwrap();
+
+        assert!(storage.get_app_usage_stats("session-1".to_string(), None).await.is_err());
+    }
+}
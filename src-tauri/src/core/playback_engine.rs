@@ -1,10 +1,23 @@
 use crate::core::database::Database;
+use crate::core::ffmpeg_wrapper::{self, FFmpegDecoder, FFmpegEncoder};
 use crate::core::storage::RecordingStorage;
+use crate::models::capture::{PixelFormat, RawFrame};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
+/// Frame rate of the freeze-frame clips that stand in for missing segments
+const EXPORT_FREEZE_FPS: u32 = 10;
+
+/// Most frames one `extract_frames` call may write
+pub const MAX_EXTRACTED_FRAMES: u64 = 10_000;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaybackInfo {
     pub session_id: String,
@@ -22,13 +35,43 @@ pub struct VideoSegmentInfo {
     pub start_timestamp: i64,
     pub end_timestamp: i64,
     pub duration_ms: u64,
+    pub is_corrupt: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeekInfo {
     pub video_path: String,
+    /// Segment file being played, or None when showing the base layer
+    pub segment_path: Option<String>,
+    /// Position within `video_path` to seek to
     pub offset_ms: i64,
     pub segment_start: i64,
+    /// True when the requested time falls in a corrupt segment and playback
+    /// shows the last good frame (or the base layer) instead
+    pub missing_footage: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameExtractionProgress {
+    pub session_id: String,
+    pub frames_written: u32,
+    pub total_frames: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub session_id: String,
+    pub processed_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub output_path: String,
+    pub duration_ms: u64,
+    pub segment_count: usize,
+    /// Segments that were missing or corrupt and replaced with a freeze frame
+    pub frozen_segments: usize,
 }
 
 #[derive(sqlx::FromRow)]
@@ -90,16 +133,28 @@ impl PlaybackEngine {
 
         let segment_infos: Vec<VideoSegmentInfo> = segments
             .iter()
-            .map(|seg| VideoSegmentInfo {
-                path: seg.file_path.clone(),
-                start_timestamp: seg.start_timestamp,
-                end_timestamp: seg.end_timestamp,
-                duration_ms: seg.duration_ms as u64,
+            .map(|seg| {
+                let is_corrupt = !is_segment_playable(Path::new(&seg.file_path));
+                if is_corrupt {
+                    eprintln!(
+                        "Warning: Skipping corrupt video segment {} for session {}",
+                        seg.file_path, seg.session_id
+                    );
+                }
+
+                VideoSegmentInfo {
+                    path: seg.file_path.clone(),
+                    start_timestamp: seg.start_timestamp,
+                    end_timestamp: seg.end_timestamp,
+                    duration_ms: seg.duration_ms as u64,
+                    is_corrupt,
+                }
             })
             .collect();
 
         let total_duration: u64 = segment_infos
             .iter()
+            .filter(|s| !s.is_corrupt)
             .map(|s| s.duration_ms)
             .sum();
 
@@ -124,14 +179,134 @@ impl PlaybackEngine {
         session_id: Uuid,
         timestamp: i64,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Find which segment/frame contains this timestamp
+        let seek = self.seek_to_timestamp(session_id, timestamp).await?;
+        Ok(seek.video_path)
+    }
+
+    pub async fn seek_to_timestamp(
+        &self,
+        session_id: Uuid,
+        timestamp: i64,
+    ) -> Result<SeekInfo, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(seg) = self.resolve_segment(session_id, timestamp).await? else {
+            // Before the first or after the last segment, the screen was static
+            return Ok(SeekInfo {
+                video_path: self.get_base_layer_path(session_id).await?,
+                segment_path: None,
+                offset_ms: 0,
+                segment_start: timestamp,
+                missing_footage: false,
+            });
+        };
+
+        if is_segment_playable(Path::new(&seg.file_path)) {
+            return Ok(SeekInfo {
+                video_path: seg.file_path.clone(),
+                segment_path: Some(seg.file_path),
+                // Snapped forward out of a gap, so start at the segment's first frame
+                offset_ms: (timestamp - seg.start_timestamp).max(0),
+                segment_start: seg.start_timestamp,
+                missing_footage: false,
+            });
+        }
+
+        eprintln!(
+            "Warning: Video segment {} is corrupt or truncated, falling back to last good frame",
+            seg.file_path
+        );
+
+        // Show the final frame of the last playable segment before this one
+        let previous = sqlx::query_as::<_, VideoSegmentRow>(
+            r#"
+            SELECT id, session_id, file_path, start_timestamp, end_timestamp, duration_ms
+            FROM video_segments
+            WHERE session_id = ?
+              AND end_timestamp < ?
+            ORDER BY start_timestamp DESC
+            "#
+        )
+        .bind(session_id.to_string())
+        .bind(seg.start_timestamp)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        if let Some(good) = previous
+            .into_iter()
+            .find(|s| is_segment_playable(Path::new(&s.file_path)))
+        {
+            return Ok(SeekInfo {
+                video_path: good.file_path.clone(),
+                segment_path: Some(good.file_path),
+                offset_ms: good.duration_ms.max(0),
+                segment_start: good.start_timestamp,
+                missing_footage: true,
+            });
+        }
+
+        // Nothing playable before it, use the base layer as a placeholder
+        Ok(SeekInfo {
+            video_path: self.get_base_layer_path(session_id).await?,
+            segment_path: None,
+            offset_ms: 0,
+            segment_start: seg.start_timestamp,
+            missing_footage: true,
+        })
+    }
+
+    /// Rebuild the full frame shown at `timestamp` as a base64-encoded PNG
+    ///
+    /// The session's base layer is the lossless backdrop; only the region
+    /// that changed in the segment frame is copied over it, so static parts of
+    /// the screen stay free of compression artifacts.
+    pub async fn reconstruct_frame(
+        &self,
+        session_id: Uuid,
+        timestamp: i64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let base = self.storage.load_base_layer(&session_id).await?;
+
+        let segment = self
+            .resolve_segment(session_id, timestamp)
+            .await?
+            .filter(|seg| is_segment_playable(Path::new(&seg.file_path)));
+
+        let delta = match segment {
+            Some(seg) => {
+                let offset_ms = (timestamp - seg.start_timestamp).max(0);
+                let frame = tokio::task::spawn_blocking(move || {
+                    let mut decoder = FFmpegDecoder::open(Path::new(&seg.file_path))?;
+                    decoder.decode_frame_at(offset_ms)
+                })
+                .await??;
+                Some(frame)
+            }
+            None => None,
+        };
+
+        let frame = match (base, delta) {
+            (Some(base), Some(delta)) => composite_frame(&base, &delta),
+            (Some(base), None) => base,
+            (None, Some(delta)) => delta,
+            (None, None) => return Err("No frame recorded at this time".into()),
+        };
+
+        encode_png_base64(&frame)
+    }
+
+    /// Segment covering `timestamp`, or the next one if it falls in a motion gap
+    async fn resolve_segment(
+        &self,
+        session_id: Uuid,
+        timestamp: i64,
+    ) -> Result<Option<VideoSegmentRow>, Box<dyn std::error::Error + Send + Sync>> {
         let segment = sqlx::query_as::<_, VideoSegmentRow>(
             r#"
             SELECT id, session_id, file_path, start_timestamp, end_timestamp, duration_ms
-            FROM frames
+            FROM video_segments
             WHERE session_id = ?
               AND start_timestamp <= ?
               AND end_timestamp >= ?
+            ORDER BY start_timestamp ASC
             LIMIT 1
             "#
         )
@@ -141,61 +316,297 @@ impl PlaybackEngine {
         .fetch_optional(&self.db.pool)
         .await?;
 
-        if let Some(seg) = segment {
-            Ok(seg.file_path)
-        } else {
-            // No segment at this time, return base layer
-            let recording = sqlx::query_as::<_, ScreenRecordingRow>(
-                r#"
-                SELECT id, session_id, start_timestamp, end_timestamp, base_layer_path, display_id
-                FROM screen_recordings
-                WHERE session_id = ?
-                "#
-            )
-            .bind(session_id.to_string())
-            .fetch_one(&self.db.pool)
-            .await?;
-
-            Ok(recording.base_layer_path)
+        match segment {
+            Some(seg) => Ok(Some(seg)),
+            None => self.segment_after_gap(session_id, timestamp).await,
         }
     }
 
-    pub async fn seek_to_timestamp(
+    /// First segment after `timestamp` when it falls in a motion gap
+    ///
+    /// Only a gap between two segments counts; before the first segment the
+    /// base layer is still the right frame to show.
+    async fn segment_after_gap(
         &self,
         session_id: Uuid,
         timestamp: i64,
-    ) -> Result<SeekInfo, Box<dyn std::error::Error + Send + Sync>> {
-        let frame_path = self.get_frame_at_timestamp(session_id, timestamp).await?;
-
-        // Get segment info
-        let segment_info = sqlx::query_as::<_, VideoSegmentRow>(
+    ) -> Result<Option<VideoSegmentRow>, Box<dyn std::error::Error + Send + Sync>> {
+        let segment = sqlx::query_as::<_, VideoSegmentRow>(
             r#"
             SELECT id, session_id, file_path, start_timestamp, end_timestamp, duration_ms
-            FROM frames
-            WHERE file_path = ?
+            FROM video_segments
+            WHERE session_id = ?
+              AND start_timestamp > ?
+              AND EXISTS (
+                  SELECT 1 FROM video_segments prev
+                  WHERE prev.session_id = video_segments.session_id
+                    AND prev.end_timestamp < ?
+              )
+            ORDER BY start_timestamp ASC
+            LIMIT 1
             "#
         )
-        .bind(&frame_path)
+        .bind(session_id.to_string())
+        .bind(timestamp)
+        .bind(timestamp)
         .fetch_optional(&self.db.pool)
         .await?;
 
-        if let Some(info) = segment_info {
-            // Calculate offset within segment
-            let offset_ms = timestamp - info.start_timestamp;
+        Ok(segment)
+    }
 
-            Ok(SeekInfo {
-                video_path: frame_path,
-                offset_ms,
-                segment_start: info.start_timestamp,
-            })
-        } else {
-            // Base layer (static frame)
-            Ok(SeekInfo {
-                video_path: frame_path,
-                offset_ms: 0,
-                segment_start: timestamp,
-            })
+    /// Timestamp of the first screen change strictly after `after_ts`
+    ///
+    /// Segments are only recorded when motion is detected, so each segment
+    /// start marks a change. Returns `None` past the last segment.
+    pub async fn next_change(
+        &self,
+        session_id: Uuid,
+        after_ts: i64,
+    ) -> Result<Option<i64>, Box<dyn std::error::Error + Send + Sync>> {
+        let next = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT start_timestamp
+            FROM video_segments
+            WHERE session_id = ?
+              AND start_timestamp > ?
+            ORDER BY start_timestamp ASC
+            LIMIT 1
+            "#
+        )
+        .bind(session_id.to_string())
+        .bind(after_ts)
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(next)
+    }
+
+    /// Timestamp of the last screen change strictly before `before_ts`
+    ///
+    /// Returns `None` before the first segment.
+    pub async fn prev_change(
+        &self,
+        session_id: Uuid,
+        before_ts: i64,
+    ) -> Result<Option<i64>, Box<dyn std::error::Error + Send + Sync>> {
+        let prev = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT start_timestamp
+            FROM video_segments
+            WHERE session_id = ?
+              AND start_timestamp < ?
+            ORDER BY start_timestamp DESC
+            LIMIT 1
+            "#
+        )
+        .bind(session_id.to_string())
+        .bind(before_ts)
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(prev)
+    }
+
+    /// Base layer written by `RecordingStorage::save_base_layer`
+    async fn get_base_layer_path(
+        &self,
+        session_id: Uuid,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let base_layer_path: Option<String> = sqlx::query_scalar(
+            "SELECT base_layer_path FROM sessions WHERE id = ?"
+        )
+        .bind(session_id.to_string())
+        .fetch_optional(&self.db.pool)
+        .await?
+        .flatten();
+
+        base_layer_path.ok_or_else(|| format!("No base layer recorded for session {}", session_id).into())
+    }
+
+    /// Write frames between `start` and `end` as numbered PNGs in `output_dir`
+    ///
+    /// Frames are reconstructed through the same seek path used for playback
+    /// and written one at a time, so memory use stays flat for long ranges.
+    /// Timestamps with nothing to show are skipped; files keep the index of
+    /// their timestamp in the range, so a skipped frame leaves a gap in the
+    /// numbering.
+    pub async fn extract_frames<F>(
+        &self,
+        session_id: Uuid,
+        start: i64,
+        end: i64,
+        interval_ms: u64,
+        output_dir: PathBuf,
+        on_progress: F,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: Fn(FrameExtractionProgress) + Send + 'static,
+    {
+        if interval_ms == 0 {
+            return Err("Interval must be greater than zero".into());
+        }
+        if end < start {
+            return Err("End timestamp must not be before start timestamp".into());
         }
+
+        let frame_count = (end - start) as u64 / interval_ms + 1;
+        if frame_count > MAX_EXTRACTED_FRAMES {
+            return Err(format!(
+                "Extracting {} frames exceeds the limit of {}; use a longer interval or a shorter range",
+                frame_count, MAX_EXTRACTED_FRAMES
+            )
+            .into());
+        }
+
+        std::fs::create_dir_all(&output_dir)?;
+
+        // Resolve where every frame comes from before decoding
+        let mut seeks = Vec::new();
+        for index in 0..frame_count {
+            let timestamp = start + (index * interval_ms) as i64;
+            match self.seek_to_timestamp(session_id, timestamp).await {
+                Ok(seek) => seeks.push((index, seek)),
+                Err(e) => eprintln!("Skipping frame at {}: {}", timestamp, e),
+            }
+        }
+
+        let session_id_str = session_id.to_string();
+
+        let written = tokio::task::spawn_blocking(move || {
+            let total_frames = seeks.len() as u32;
+            let mut decoders: HashMap<String, FFmpegDecoder> = HashMap::new();
+            let mut written = Vec::with_capacity(seeks.len());
+
+            for (position, (index, seek)) in seeks.iter().enumerate() {
+                let image = if seek.video_path.ends_with(".mp4") {
+                    if !decoders.contains_key(&seek.video_path) {
+                        let decoder = FFmpegDecoder::open(Path::new(&seek.video_path))?;
+                        decoders.insert(seek.video_path.clone(), decoder);
+                    }
+                    let decoder = decoders.get_mut(&seek.video_path).unwrap();
+                    let frame = decoder.decode_frame_at(seek.offset_ms)?;
+
+                    image::RgbaImage::from_raw(frame.width, frame.height, frame.data)
+                        .ok_or("Decoded frame has invalid dimensions")?
+                } else {
+                    image::open(&seek.video_path)?.to_rgba8()
+                };
+
+                let path = output_dir.join(format!("frame_{:06}.png", index));
+                image.save(&path)?;
+                written.push(path);
+
+                on_progress(FrameExtractionProgress {
+                    session_id: session_id_str.clone(),
+                    frames_written: position as u32 + 1,
+                    total_frames,
+                });
+            }
+
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(written)
+        })
+        .await??;
+
+        Ok(written)
+    }
+
+    /// Join every segment of a session into a single MP4 at `output_path`
+    ///
+    /// Segments are joined in order, stream-copied when they all share encoder
+    /// settings. A missing or corrupt segment is replaced by the last frame of
+    /// the segment before it (or the base layer) held for the segment's
+    /// duration, so the export keeps the session's timing. Such freeze clips
+    /// rarely match the recording's encoder, so they make the join re-encode.
+    pub async fn export_session(
+        &self,
+        session_id: Uuid,
+        output_path: PathBuf,
+        progress: UnboundedSender<ExportProgress>,
+    ) -> Result<ExportSummary, Box<dyn std::error::Error + Send + Sync>> {
+        let segments = sqlx::query_as::<_, VideoSegmentRow>(
+            r#"
+            SELECT id, session_id, file_path, start_timestamp, end_timestamp, duration_ms
+            FROM video_segments
+            WHERE session_id = ?
+            ORDER BY start_timestamp ASC
+            "#
+        )
+        .bind(session_id.to_string())
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        if segments.is_empty() {
+            return Err("Session has no recorded segments".into());
+        }
+
+        let base_layer = self.storage.load_base_layer(&session_id).await?;
+        let total_ms: u64 = segments.iter().map(|s| s.duration_ms.max(0) as u64).sum();
+        let segment_count = segments.len();
+        let session_id_str = session_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let scratch_dir = std::env::temp_dir().join(format!("zero-export-{}", Uuid::new_v4()));
+            std::fs::create_dir_all(&scratch_dir)?;
+
+            let result = (|| {
+                let mut inputs = Vec::with_capacity(segments.len());
+                let mut frozen_segments = 0;
+                let mut last_good: Option<&VideoSegmentRow> = None;
+
+                for (index, seg) in segments.iter().enumerate() {
+                    if is_segment_playable(Path::new(&seg.file_path)) {
+                        inputs.push(PathBuf::from(&seg.file_path));
+                        last_good = Some(seg);
+                        continue;
+                    }
+
+                    let freeze = match last_good {
+                        Some(good) => Some(
+                            FFmpegDecoder::open(Path::new(&good.file_path))?
+                                .decode_frame_at(good.duration_ms)?,
+                        ),
+                        None => base_layer.clone(),
+                    };
+
+                    let Some(frame) = freeze else {
+                        eprintln!(
+                            "Warning: Skipping missing segment {} with nothing to freeze on",
+                            seg.file_path
+                        );
+                        continue;
+                    };
+
+                    eprintln!(
+                        "Warning: Segment {} is missing or corrupt, exporting a freeze frame",
+                        seg.file_path
+                    );
+                    let clip_path = scratch_dir.join(format!("freeze_{:04}.mp4", index));
+                    write_freeze_clip(&frame, seg.duration_ms, &clip_path)?;
+                    inputs.push(clip_path);
+                    frozen_segments += 1;
+                }
+
+                let duration_ms = ffmpeg_wrapper::concat_videos(&inputs, &output_path, |processed_ms| {
+                    let _ = progress.send(ExportProgress {
+                        session_id: session_id_str.clone(),
+                        processed_ms: processed_ms.max(0) as u64,
+                        total_ms,
+                    });
+                })?;
+
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(ExportSummary {
+                    output_path: output_path.to_string_lossy().to_string(),
+                    duration_ms: duration_ms.max(0) as u64,
+                    segment_count,
+                    frozen_segments,
+                })
+            })();
+
+            let _ = std::fs::remove_dir_all(&scratch_dir);
+            result
+        })
+        .await?
     }
 
     pub async fn generate_thumbnail(
@@ -211,3 +622,440 @@ impl PlaybackEngine {
         Ok(frame_path)
     }
 }
+
+/// Encode `frame` held still for `duration_ms` as a short clip
+fn write_freeze_clip(
+    frame: &RawFrame,
+    duration_ms: i64,
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let rgba = RawFrame {
+        data: to_rgba(frame),
+        format: PixelFormat::RGBA8,
+        ..frame.clone()
+    };
+    let frame_count = (duration_ms.max(0) as u64 * EXPORT_FREEZE_FPS as u64 / 1000).max(1);
+
+    let mut encoder = FFmpegEncoder::new(
+        path,
+        rgba.width,
+        rgba.height,
+        EXPORT_FREEZE_FPS,
+        "libx264",
+        23,
+        EXPORT_FREEZE_FPS * 2,
+    )?;
+    for _ in 0..frame_count {
+        encoder.encode_frame(&rgba)?;
+    }
+    encoder.finish()?;
+
+    Ok(())
+}
+
+/// Per-channel difference below which a pixel counts as encoder noise
+const COMPOSITE_NOISE_TOLERANCE: u8 = 24;
+
+/// Overlay the region of `delta` that differs from `base` onto the base layer
+///
+/// The changed region is the bounding box of every pixel whose difference
+/// exceeds `COMPOSITE_NOISE_TOLERANCE`. Frames of different sizes can't be
+/// aligned, so the delta is returned whole in that case.
+fn composite_frame(base: &RawFrame, delta: &RawFrame) -> RawFrame {
+    let base_rgba = to_rgba(base);
+    let delta_rgba = to_rgba(delta);
+
+    if base.width != delta.width || base.height != delta.height {
+        return RawFrame {
+            data: delta_rgba,
+            format: PixelFormat::RGBA8,
+            ..delta.clone()
+        };
+    }
+
+    let width = base.width as usize;
+    let mut region: Option<(usize, usize, usize, usize)> = None;
+
+    for (index, (a, b)) in base_rgba
+        .chunks_exact(4)
+        .zip(delta_rgba.chunks_exact(4))
+        .enumerate()
+    {
+        let changed = a
+            .iter()
+            .zip(b)
+            .any(|(x, y)| x.abs_diff(*y) > COMPOSITE_NOISE_TOLERANCE);
+        if changed {
+            let (x, y) = (index % width, index / width);
+            region = Some(match region {
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                None => (x, y, x, y),
+            });
+        }
+    }
+
+    let mut data = base_rgba;
+    if let Some((x0, y0, x1, y1)) = region {
+        for y in y0..=y1 {
+            let start = (y * width + x0) * 4;
+            let end = (y * width + x1 + 1) * 4;
+            data[start..end].copy_from_slice(&delta_rgba[start..end]);
+        }
+    }
+
+    RawFrame {
+        timestamp: delta.timestamp,
+        width: base.width,
+        height: base.height,
+        data,
+        format: PixelFormat::RGBA8,
+    }
+}
+
+fn to_rgba(frame: &RawFrame) -> Vec<u8> {
+    match frame.format {
+        PixelFormat::RGBA8 => frame.data.clone(),
+        PixelFormat::BGRA8 => frame
+            .data
+            .chunks_exact(4)
+            .flat_map(|px| [px[2], px[1], px[0], px[3]])
+            .collect(),
+    }
+}
+
+fn encode_png_base64(frame: &RawFrame) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let image = image::RgbaImage::from_raw(frame.width, frame.height, to_rgba(frame))
+        .ok_or("Frame has invalid dimensions")?;
+
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+/// Check that a segment file exists and is a complete MP4.
+///
+/// A segment truncated by a crash mid-encode is missing its `moov` atom,
+/// which the muxer only writes when the file is finalized.
+fn is_segment_playable(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let Ok(len) = file.metadata().map(|m| m.len()) else {
+        return false;
+    };
+    if len == 0 {
+        return false;
+    }
+
+    let mut has_moov = false;
+    let mut has_mdat = false;
+    let mut pos = 0u64;
+
+    while pos + 8 <= len {
+        let mut header = [0u8; 8];
+        if file.seek(SeekFrom::Start(pos)).is_err() || file.read_exact(&mut header).is_err() {
+            return false;
+        }
+
+        let mut box_size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let box_type = &header[4..8];
+
+        if box_size == 1 {
+            // 64-bit extended size follows the header
+            let mut ext = [0u8; 8];
+            if file.read_exact(&mut ext).is_err() {
+                return false;
+            }
+            box_size = u64::from_be_bytes(ext);
+        } else if box_size == 0 {
+            // Box extends to end of file
+            box_size = len - pos;
+        }
+
+        if box_size < 8 || pos + box_size > len {
+            return false;
+        }
+
+        match box_type {
+            b"moov" => has_moov = true,
+            b"mdat" => has_mdat = true,
+            _ => {}
+        }
+
+        pos += box_size;
+    }
+
+    has_moov && has_mdat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::io::Write;
+
+    fn mp4_box(box_type: &[u8; 4], payload_len: usize) -> Vec<u8> {
+        let mut data = ((payload_len + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(box_type);
+        data.extend(std::iter::repeat(0u8).take(payload_len));
+        data
+    }
+
+    fn write_temp(data: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("segment_{}.mp4", Uuid::new_v4()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_complete_segment_is_playable() {
+        let mut data = mp4_box(b"ftyp", 16);
+        data.extend(mp4_box(b"mdat", 64));
+        data.extend(mp4_box(b"moov", 32));
+        let path = write_temp(&data);
+
+        assert!(is_segment_playable(&path));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_truncated_segment_is_corrupt() {
+        let mut data = mp4_box(b"ftyp", 16);
+        data.extend(mp4_box(b"mdat", 64));
+        data.truncate(data.len() - 10);
+        let path = write_temp(&data);
+
+        assert!(!is_segment_playable(&path));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_missing_segment_is_corrupt() {
+        let path = std::env::temp_dir().join(format!("missing_{}.mp4", Uuid::new_v4()));
+        assert!(!is_segment_playable(&path));
+    }
+
+    /// Engine over a session with playable segments covering each `(start, end)`
+    async fn engine_with_segments(ranges: &[(i64, i64)]) -> (PlaybackEngine, Uuid, Vec<PathBuf>) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 0, "test-device").await.unwrap();
+
+        let mut data = mp4_box(b"ftyp", 16);
+        data.extend(mp4_box(b"mdat", 64));
+        data.extend(mp4_box(b"moov", 32));
+
+        let mut paths = Vec::new();
+        for &(start, end) in ranges {
+            let path = write_temp(&data);
+            sqlx::query(
+                "INSERT INTO video_segments
+                 (id, session_id, start_timestamp, end_timestamp, file_path, frame_count, file_size_bytes, duration_ms)
+                 VALUES (?, ?, ?, ?, ?, 1, ?, ?)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(session_id.to_string())
+            .bind(start)
+            .bind(end)
+            .bind(path.to_string_lossy().to_string())
+            .bind(data.len() as i64)
+            .bind(end - start)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            paths.push(path);
+        }
+
+        let storage_dir = std::env::temp_dir().join(format!("playback_{}", Uuid::new_v4()));
+        let storage = Arc::new(RecordingStorage::new(storage_dir, db.clone()).await.unwrap());
+
+        (PlaybackEngine::new(storage, db), session_id, paths)
+    }
+
+    #[tokio::test]
+    async fn test_seek_resolves_segment_and_offset() {
+        let (engine, session_id, paths) =
+            engine_with_segments(&[(1_000, 2_000), (2_000, 3_000), (3_000, 4_000)]).await;
+
+        let seek = engine.seek_to_timestamp(session_id, 2_500).await.unwrap();
+        assert_eq!(seek.segment_path.as_deref(), Some(paths[1].to_str().unwrap()));
+        assert_eq!(seek.video_path, paths[1].to_string_lossy());
+        assert_eq!(seek.offset_ms, 500);
+        assert_eq!(seek.segment_start, 2_000);
+        assert!(!seek.missing_footage);
+
+        let seek = engine.seek_to_timestamp(session_id, 3_999).await.unwrap();
+        assert_eq!(seek.segment_path.as_deref(), Some(paths[2].to_str().unwrap()));
+        assert_eq!(seek.offset_ms, 999);
+
+        for path in paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seek_in_gap_snaps_to_next_segment() {
+        let (engine, session_id, paths) =
+            engine_with_segments(&[(1_000, 2_000), (5_000, 6_000), (9_000, 10_000)]).await;
+
+        let seek = engine.seek_to_timestamp(session_id, 3_500).await.unwrap();
+        assert_eq!(seek.segment_path.as_deref(), Some(paths[1].to_str().unwrap()));
+        assert_eq!(seek.offset_ms, 0);
+        assert_eq!(seek.segment_start, 5_000);
+
+        let seek = engine.seek_to_timestamp(session_id, 8_999).await.unwrap();
+        assert_eq!(seek.segment_path.as_deref(), Some(paths[2].to_str().unwrap()));
+        assert_eq!(seek.offset_ms, 0);
+
+        for path in paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    /// Save a solid base layer for the session, returning its path
+    async fn save_base_layer(engine: &PlaybackEngine, session_id: Uuid) -> String {
+        engine.storage.attach_session(session_id).await.unwrap();
+        let frame = RawFrame {
+            timestamp: 0,
+            width: 4,
+            height: 4,
+            data: vec![128; 4 * 4 * 4],
+            format: PixelFormat::RGBA8,
+        };
+        engine.storage.save_base_layer(&session_id, &frame).await.unwrap();
+        engine
+            .storage
+            .get_session_path(&session_id)
+            .join("base_layer.png")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_seek_into_corrupt_first_segment_shows_base_layer() {
+        let (engine, session_id, paths) = engine_with_segments(&[(1_000, 2_000), (2_000, 3_000)]).await;
+        let base_layer_path = save_base_layer(&engine, session_id).await;
+
+        // Truncated before its moov box, as if the recorder crashed mid-write
+        let mut data = mp4_box(b"ftyp", 16);
+        data.extend(mp4_box(b"mdat", 64));
+        std::fs::write(&paths[0], data).unwrap();
+
+        let seek = engine.seek_to_timestamp(session_id, 1_500).await.unwrap();
+        assert_eq!(seek.video_path, base_layer_path);
+        assert_eq!(seek.segment_path, None);
+        assert_eq!(seek.segment_start, 1_000);
+        assert!(seek.missing_footage);
+
+        for path in paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seek_outside_segments_shows_base_layer() {
+        let (engine, session_id, paths) = engine_with_segments(&[(1_000, 2_000), (5_000, 6_000)]).await;
+        let base_layer_path = save_base_layer(&engine, session_id).await;
+
+        let before = engine.seek_to_timestamp(session_id, 500).await.unwrap();
+        assert_eq!(before.video_path, base_layer_path);
+        assert_eq!(before.segment_path, None);
+        assert_eq!(before.segment_start, 500);
+        assert!(!before.missing_footage);
+
+        let between = engine.seek_to_timestamp(session_id, 3_000).await.unwrap();
+        assert_eq!(between.segment_path.as_deref(), Some(paths[1].to_str().unwrap()));
+        assert_eq!(between.offset_ms, 0);
+
+        let after = engine.seek_to_timestamp(session_id, 7_000).await.unwrap();
+        assert_eq!(after.video_path, base_layer_path);
+        assert_eq!(after.segment_path, None);
+        assert!(!after.missing_footage);
+
+        for path in paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_frames_skips_timestamps_with_nothing_to_show() {
+        let (engine, session_id, _) = engine_with_segments(&[]).await;
+        let output_dir = std::env::temp_dir().join(format!("extract_{}", Uuid::new_v4()));
+
+        // No segments and no base layer, so no timestamp has a frame
+        let written = engine
+            .extract_frames(session_id, 0, 1_000, 500, output_dir.clone(), |_| {})
+            .await
+            .unwrap();
+        assert!(written.is_empty());
+
+        save_base_layer(&engine, session_id).await;
+        let written = engine
+            .extract_frames(session_id, 0, 1_000, 500, output_dir.clone(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(written.len(), 3);
+        assert!(written[2].ends_with("frame_000002.png"));
+
+        std::fs::remove_dir_all(output_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_extract_frames_rejects_too_many_frames() {
+        let (engine, session_id, _) = engine_with_segments(&[]).await;
+        let output_dir = std::env::temp_dir().join(format!("extract_{}", Uuid::new_v4()));
+
+        let result = engine
+            .extract_frames(session_id, 0, MAX_EXTRACTED_FRAMES as i64, 1, output_dir.clone(), |_| {})
+            .await;
+        assert!(result.is_err());
+        assert!(!output_dir.exists());
+    }
+
+    #[test]
+    fn test_reconstruct_composites_motion_region() {
+        let solid = |value: u8| RawFrame {
+            timestamp: 0,
+            width: 8,
+            height: 6,
+            data: vec![value; 8 * 6 * 4],
+            format: PixelFormat::RGBA8,
+        };
+        let pixel = |frame: &RawFrame, x: usize, y: usize| {
+            let offset = (y * frame.width as usize + x) * 4;
+            frame.data[offset..offset + 4].to_vec()
+        };
+
+        let base = solid(100);
+        // Compression noise everywhere, plus a real change at (5..7, 2..4)
+        let mut delta = solid(105);
+        delta.timestamp = 1_500;
+        for y in 2..4 {
+            for x in 5..7 {
+                let offset = (y * 8 + x) * 4;
+                delta.data[offset..offset + 4].copy_from_slice(&[255, 0, 0, 255]);
+            }
+        }
+
+        let frame = composite_frame(&base, &delta);
+        assert_eq!((frame.width, frame.height), (8, 6));
+        assert_eq!(frame.timestamp, 1_500);
+        assert_eq!(pixel(&frame, 6, 3), vec![255, 0, 0, 255]);
+        assert_eq!(pixel(&frame, 0, 0), vec![100; 4]);
+
+        let encoded = encode_png_base64(&frame).unwrap();
+        let png = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (8, 6));
+        assert_eq!(decoded.get_pixel(5, 2).0, [255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,438 @@
+// Retention - prunes every data type to its configured window
+
+use crate::core::database::Database;
+use crate::core::storage::RecordingStorage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// How often the scheduled cleanup runs
+pub const CLEANUP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Recording directories with no session are left alone this long, so a
+/// recording that is just being attached is not mistaken for an orphan
+const ORPHAN_GRACE_MS: i64 = 60 * 60 * 1000;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Rows pruned by their own timestamp: retention key, then table
+const ROW_RETENTION: [(&str, &str); 5] = [
+    ("ocr", "ocr_results"),
+    ("keyboard", "keyboard_events"),
+    ("keyboard", "commands"),
+    ("mouse", "mouse_events"),
+    ("clipboard", "clipboard_events"),
+];
+
+/// Retention key for screen recordings and their files
+const SCREEN_KEY: &str = "screen";
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// What one cleanup pass removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CleanupReport {
+    /// Rows deleted, by retention key
+    pub deleted_rows: HashMap<String, u64>,
+    /// Recording files deleted, including orphans
+    pub deleted_files: u64,
+}
+
+pub struct RetentionManager {
+    db: Arc<Database>,
+    /// Told about every recording file removed, so its quota usage stays accurate
+    storage: Arc<RecordingStorage>,
+}
+
+impl RetentionManager {
+    pub fn new(db: Arc<Database>, storage: Arc<RecordingStorage>) -> Self {
+        Self { db, storage }
+    }
+
+    /// Delete everything older than its data type's window in `retention_days`
+    ///
+    /// Data types without a configured window are kept. Screen recordings are
+    /// pruned per finished session, which keeps the session itself for the
+    /// other data types. Ends with one VACUUM to hand the space back.
+    pub async fn run_cleanup(&self, retention_days: &HashMap<String, u32>, now: i64) -> Result<CleanupReport> {
+        let mut report = CleanupReport::default();
+        let cutoff = |key: &str| retention_days.get(key).map(|days| now - *days as i64 * DAY_MS);
+
+        for (key, table) in ROW_RETENTION {
+            let Some(cutoff) = cutoff(key) else {
+                continue;
+            };
+
+            let result = sqlx::query(&format!("DELETE FROM {} WHERE timestamp < ?", table))
+                .bind(cutoff)
+                .execute(self.db.pool())
+                .await?;
+            *report.deleted_rows.entry(key.to_string()).or_default() += result.rows_affected();
+        }
+
+        if let Some(cutoff) = cutoff(SCREEN_KEY) {
+            let (rows, files) = self.prune_recordings(cutoff).await?;
+            report.deleted_rows.insert(SCREEN_KEY.to_string(), rows);
+            report.deleted_files += files;
+        }
+
+        report.deleted_files += self.remove_orphaned_recordings(now).await?;
+
+        sqlx::query("VACUUM").execute(self.db.pool()).await?;
+
+        Ok(report)
+    }
+
+    /// Drop the frames, segments and files of sessions that ended before `cutoff`
+    async fn prune_recordings(&self, cutoff: i64) -> Result<(u64, u64)> {
+        let expired: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, recording_path FROM sessions
+             WHERE recording_path IS NOT NULL AND end_timestamp IS NOT NULL AND end_timestamp < ?"
+        )
+        .bind(cutoff)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut deleted_rows = 0;
+        let mut deleted_files = 0;
+
+        for (session_id, recording_path) in expired {
+            let mut tx = self.db.pool().begin().await?;
+
+            for table in ["frames", "video_segments"] {
+                let result = sqlx::query(&format!("DELETE FROM {} WHERE session_id = ?", table))
+                    .bind(&session_id)
+                    .execute(&mut *tx)
+                    .await?;
+                deleted_rows += result.rows_affected();
+            }
+
+            sqlx::query(
+                "UPDATE sessions
+                 SET recording_path = NULL, base_layer_path = NULL,
+                     segment_count = 0, frame_count = 0, total_size_bytes = 0
+                 WHERE id = ?"
+            )
+            .bind(&session_id)
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            // Rows go first; a directory left behind is picked up as an orphan next time
+            deleted_files += self.remove_directory(Path::new(&recording_path));
+        }
+
+        Ok((deleted_rows, deleted_files))
+    }
+
+    /// Remove session directories under the recordings path that no session points to
+    async fn remove_orphaned_recordings(&self, now: i64) -> Result<u64> {
+        let recordings_path = self.storage.base_path();
+        if !recordings_path.exists() {
+            return Ok(0);
+        }
+
+        let mut deleted_files = 0;
+
+        for entry in std::fs::read_dir(recordings_path)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if !path.is_dir() || Uuid::parse_str(&name).is_err() {
+                continue;
+            }
+            let past_grace = modified_ms(&path).is_some_and(|modified| now - modified >= ORPHAN_GRACE_MS);
+            if !past_grace {
+                continue;
+            }
+
+            let referenced: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM sessions WHERE id = ? AND recording_path IS NOT NULL"
+            )
+            .bind(&name)
+            .fetch_one(self.db.pool())
+            .await?;
+
+            if referenced == 0 {
+                deleted_files += self.remove_directory(&path);
+            }
+        }
+
+        Ok(deleted_files)
+    }
+
+    /// Remove a directory tree, returning how many files it held
+    fn remove_directory(&self, path: &Path) -> u64 {
+        if !path.exists() {
+            return 0;
+        }
+
+        let (files, bytes) = tree_size(path);
+        match std::fs::remove_dir_all(path) {
+            Ok(()) => {
+                self.storage.record_removed(bytes);
+                files
+            }
+            Err(e) => {
+                eprintln!("Failed to remove {}: {}", path.display(), e);
+                0
+            }
+        }
+    }
+}
+
+fn modified_ms(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64)
+}
+
+/// Number of files under `path` and their total size in bytes
+fn tree_size(path: &Path) -> (u64, u64) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return (0, 0);
+    };
+
+    entries.flatten().fold((0, 0), |(files, bytes), entry| {
+        let path = entry.path();
+        let (entry_files, entry_bytes) = if path.is_dir() {
+            tree_size(&path)
+        } else {
+            (1, entry.metadata().map(|m| m.len()).unwrap_or(0))
+        };
+        (files + entry_files, bytes + entry_bytes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_db() -> Arc<Database> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db
+    }
+
+    async fn count(db: &Database, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_each_data_type_pruned_to_its_window() {
+        let db = test_db().await;
+        let now = 100 * DAY_MS;
+        db.create_session("session", 0, "test-device").await.unwrap();
+
+        for age_days in [2, 10] {
+            let timestamp = now - age_days * DAY_MS;
+            sqlx::query(
+                "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id)
+                 VALUES (?, 'session', ?, 'key_down', 65, '{}', 'Editor', '', 1)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(timestamp)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO mouse_events (id, session_id, timestamp, event_type, position_x, position_y, app_name, window_title, process_id)
+                 VALUES (?, 'session', ?, 'move', 0, 0, 'Editor', '', 1)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(timestamp)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO ocr_results (id, session_id, timestamp, text, confidence, bounding_box, created_at)
+                 VALUES (?, 'session', ?, 'hello', 0.9, '{}', 0)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(timestamp)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO clipboard_events (id, session_id, timestamp, content_type, size_bytes)
+                 VALUES (?, 'session', ?, 'text', 5)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(timestamp)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+
+        let retention_days = HashMap::from([
+            ("keyboard".to_string(), 5),
+            ("mouse".to_string(), 1),
+            ("ocr".to_string(), 30),
+        ]);
+        let storage = Arc::new(
+            RecordingStorage::new(std::env::temp_dir().join("observer_test_retention_rows"), db.clone())
+                .await
+                .unwrap(),
+        );
+        let manager = RetentionManager::new(db.clone(), storage);
+        let report = manager.run_cleanup(&retention_days, now).await.unwrap();
+
+        assert_eq!(count(&db, "keyboard_events").await, 1);
+        assert_eq!(count(&db, "mouse_events").await, 0);
+        assert_eq!(count(&db, "ocr_results").await, 2);
+        // No window configured, so nothing is pruned
+        assert_eq!(count(&db, "clipboard_events").await, 2);
+
+        assert_eq!(report.deleted_rows.get("keyboard"), Some(&1));
+        assert_eq!(report.deleted_rows.get("mouse"), Some(&2));
+        assert_eq!(report.deleted_rows.get("ocr"), Some(&0));
+        assert_eq!(report.deleted_rows.get("clipboard"), None);
+    }
+
+    #[tokio::test]
+    async fn test_recording_files_removed_with_rows() {
+        let db = test_db().await;
+        let recordings = std::env::temp_dir().join("observer_test_retention_files");
+        let _ = std::fs::remove_dir_all(&recordings);
+
+        // Far enough ahead that directories created just now are past the orphan grace period
+        let now = chrono::Utc::now().timestamp_millis() + 2 * ORPHAN_GRACE_MS;
+
+        let mut sessions = Vec::new();
+        for ended_days_ago in [40, 3] {
+            let id = Uuid::new_v4().to_string();
+            let end = now - ended_days_ago * DAY_MS;
+            db.create_session(&id, end - 60_000, "test-device").await.unwrap();
+            db.end_session(&id, end).await.unwrap();
+
+            let dir = recordings.join(&id);
+            std::fs::create_dir_all(dir.join("segments")).unwrap();
+            let segment = dir.join("segments").join("segment_0000.mp4");
+            std::fs::write(&segment, b"video").unwrap();
+            std::fs::write(dir.join("base_layer.png"), b"png").unwrap();
+
+            sqlx::query("UPDATE sessions SET recording_path = ?, base_layer_path = ? WHERE id = ?")
+                .bind(dir.to_string_lossy().to_string())
+                .bind(dir.join("base_layer.png").to_string_lossy().to_string())
+                .bind(&id)
+                .execute(db.pool())
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO video_segments (id, session_id, start_timestamp, end_timestamp, file_path, frame_count, file_size_bytes, duration_ms)
+                 VALUES (?, ?, ?, ?, ?, 1, 5, 60000)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&id)
+            .bind(end - 60_000)
+            .bind(end)
+            .bind(segment.to_string_lossy().to_string())
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+            sessions.push((id, dir));
+        }
+
+        // A directory no session points to
+        let orphan = recordings.join(Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&orphan).unwrap();
+        std::fs::write(orphan.join("base_layer.png"), b"png").unwrap();
+
+        let retention_days = HashMap::from([("screen".to_string(), 30)]);
+        let storage = Arc::new(RecordingStorage::new(recordings.clone(), db.clone()).await.unwrap());
+        let manager = RetentionManager::new(db.clone(), storage);
+        let report = manager.run_cleanup(&retention_days, now).await.unwrap();
+
+        let (old_id, old_dir) = &sessions[0];
+        let (recent_id, recent_dir) = &sessions[1];
+
+        assert!(!old_dir.exists());
+        assert!(!orphan.exists());
+        assert!(recent_dir.join("segments").join("segment_0000.mp4").exists());
+        assert_eq!(report.deleted_files, 3);
+        assert_eq!(report.deleted_rows.get("screen"), Some(&1));
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT session_id FROM video_segments")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec![recent_id.clone()]);
+
+        // The old session itself stays for the other data types
+        let recording_path: Option<String> = sqlx::query_scalar("SELECT recording_path FROM sessions WHERE id = ?")
+            .bind(old_id)
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(recording_path, None);
+
+        let _ = std::fs::remove_dir_all(&recordings);
+    }
+
+    #[tokio::test]
+    async fn test_pruned_recordings_free_quota_for_the_next_segment() {
+        let db = test_db().await;
+        let recordings = std::env::temp_dir().join(format!("observer_test_retention_quota_{}", Uuid::new_v4()));
+        let storage = Arc::new(RecordingStorage::new(recordings.clone(), db.clone()).await.unwrap());
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut sessions = Vec::new();
+        for (ended_days_ago, bytes) in [(40, 600), (3, 300)] {
+            let id = Uuid::new_v4();
+            let end = now - ended_days_ago * DAY_MS;
+            db.create_session(&id.to_string(), end - 60_000, "test-device").await.unwrap();
+            db.end_session(&id.to_string(), end).await.unwrap();
+            storage.attach_session(id).await.unwrap();
+            std::fs::write(storage.get_segment_path(&id, 0), vec![0u8; bytes]).unwrap();
+            sessions.push(id);
+        }
+
+        storage.set_max_storage_bytes(Some(1000));
+        assert_eq!(storage.get_disk_usage().unwrap(), 900);
+
+        let retention_days = HashMap::from([("screen".to_string(), 30)]);
+        let manager = RetentionManager::new(db.clone(), storage.clone());
+        manager.run_cleanup(&retention_days, now).await.unwrap();
+
+        // 300 bytes remain, so another 400 fit without evicting anything
+        let current = Uuid::new_v4();
+        db.create_session(&current.to_string(), now, "test-device").await.unwrap();
+        storage.attach_session(current).await.unwrap();
+        let segment_path = storage.get_segment_path(&current, 0);
+        std::fs::write(&segment_path, vec![0u8; 400]).unwrap();
+        let segment = crate::core::video_encoder::VideoSegment {
+            path: segment_path,
+            start_timestamp: now,
+            end_timestamp: now + 1000,
+            frame_count: 1,
+            duration_ms: 1000,
+            file_size_bytes: 400,
+        };
+        storage.save_segment(&current, &segment).await.unwrap();
+
+        let recent = sessions[1];
+        assert!(storage.get_segment_path(&recent, 0).exists());
+        let recording_path: Option<String> = sqlx::query_scalar("SELECT recording_path FROM sessions WHERE id = ?")
+            .bind(recent.to_string())
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert!(recording_path.is_some());
+
+        let _ = std::fs::remove_dir_all(&recordings);
+    }
+}
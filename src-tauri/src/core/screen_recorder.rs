@@ -1,19 +1,27 @@
 // Screen recorder abstraction layer - unified interface for all platforms
 
 use crate::core::consent::{ConsentManager, Feature};
-use crate::core::motion_detector::{MotionDetector, MotionResult};
+use crate::core::motion_detector::{
+    MotionAlgorithm, MotionDetector, MotionProfile, MotionResult, MAX_MIN_CHANGED_REGION_AREA,
+};
+use crate::core::session_manager::IdleDetector;
 use crate::core::storage::RecordingStorage;
 use crate::core::video_encoder::{CompressionQuality, VideoCodec, VideoEncoder};
 use crate::models::capture::{CaptureError, CaptureResult, Display, RawFrame};
 use crate::platform::capture::PlatformCapture;
-use crate::platform::power::{PowerEvent, PowerManager};
+use crate::platform::power::{PowerEvent, PowerManager, DEFAULT_LOW_BATTERY_PERCENT};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, Notify, RwLock};
 use tokio::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// Smallest allowed `RecordingConfig::capture_scale`
+pub const MIN_CAPTURE_SCALE: f32 = 0.25;
+
 /// Platform-agnostic screen capture trait
 #[async_trait]
 pub trait ScreenCapture: Send + Sync {
@@ -85,18 +93,69 @@ pub struct RecordingStatus {
     pub segment_count: usize,
     pub total_motion_percentage: f32,
     pub is_paused: bool,
+    pub is_suspended: bool,
+    /// Frames discarded because processing fell behind capture
+    pub dropped_frames: u64,
+    /// Captured size before `capture_scale` was applied
+    pub source_width: Option<u32>,
+    pub source_height: Option<u32>,
+    /// FFmpeg encoder segments are written with (empty when not recording)
+    pub active_encoder: String,
+    /// Hardware encoding was requested but isn't available
+    pub hardware_fallback: bool,
+}
+
+/// Events emitted by the recorder while a recording is running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordingEvent {
+    /// Capture stopped because the system was idle on battery
+    SuspendedIdle { session_id: Uuid, idle_seconds: u64 },
+    /// Capture restarted after input or AC power returned
+    ResumedFromIdle { session_id: Uuid },
+    /// Capture backend was recreated after repeated frame failures
+    CaptureRecovered { session_id: Uuid, failures: u32 },
 }
 
 /// Recording configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingConfig {
     pub target_fps: u32,
     pub buffer_size: usize,
     pub no_motion_threshold: usize,
-    pub motion_detection_threshold: f32,
+    pub motion_profile: MotionProfile,
+    /// How frames are compared when detecting motion
+    pub motion_algorithm: MotionAlgorithm,
+    /// Changes covering fewer pixels than this are ignored (0 = off, at most
+    /// `MAX_MIN_CHANGED_REGION_AREA`); see
+    /// `MotionDetector::with_min_changed_region_area` for typical values
+    pub min_changed_region_area: u64,
     pub codec: VideoCodec,
     pub quality: CompressionQuality,
     pub hardware_acceleration: bool,
+    /// Stop grabbing frames entirely while idle on battery; off by default,
+    /// since an idle screen can still be worth recording
+    pub suspend_on_battery_idle: bool,
+    /// Seconds of idle time on battery before capture is suspended
+    pub battery_idle_threshold_secs: u64,
+    /// How often to re-check idle time and power source
+    pub power_check_interval_secs: u64,
+    /// Extra attempts at a frame after a transient capture failure
+    pub capture_retries: u32,
+    /// Delay before the first retry, doubled for each further attempt
+    pub capture_retry_backoff_ms: u64,
+    /// Consecutive failed frames before the capture backend is recreated
+    pub max_consecutive_failures: u32,
+    /// Captured frames that can wait for processing before the oldest is dropped
+    pub capture_queue_size: usize,
+    /// Factor (0.25-1.0) frames are downscaled by before motion detection and encoding
+    pub capture_scale: f32,
+    /// Battery percentage at or below which capture slows down (or pauses)
+    pub low_battery_percent: u8,
+    /// Frame rate used while the battery is low
+    pub low_battery_fps: u32,
+    /// Pause instead of slowing down while the battery is low
+    pub pause_on_low_battery: bool,
 }
 
 impl Default for RecordingConfig {
@@ -105,10 +164,23 @@ impl Default for RecordingConfig {
             target_fps: 10,
             buffer_size: 60,          // 6 seconds at 10fps
             no_motion_threshold: 20,  // ~2 seconds at 10fps
-            motion_detection_threshold: 0.05, // 5% pixels changed
+            motion_profile: MotionProfile::Mixed, // motion once 5% of the frame changes
+            motion_algorithm: MotionAlgorithm::PixelThreshold,
+            min_changed_region_area: 0,
             codec: VideoCodec::H264,
             quality: CompressionQuality::Medium,
             hardware_acceleration: true,
+            suspend_on_battery_idle: false,
+            battery_idle_threshold_secs: 300, // 5 minutes
+            power_check_interval_secs: 5,
+            capture_retries: 2,
+            capture_retry_backoff_ms: 10,
+            max_consecutive_failures: 10,
+            capture_queue_size: 30, // 3 seconds at 10fps
+            capture_scale: 1.0,
+            low_battery_percent: DEFAULT_LOW_BATTERY_PERCENT,
+            low_battery_fps: 2,
+            pause_on_low_battery: false,
         }
     }
 }
@@ -116,6 +188,8 @@ impl Default for RecordingConfig {
 /// Recording state
 struct RecordingState {
     session_id: Uuid,
+    /// Whether this recorder created the session and so should end it
+    owns_session: bool,
     display_id: u32,
     motion_detector: MotionDetector,
     video_encoder: VideoEncoder,
@@ -125,7 +199,76 @@ struct RecordingState {
     total_frames: usize,
     motion_frames: usize,
     segment_count: usize,
-    is_paused: bool,
+    /// Why capture is paused, so automatic resumes leave other pauses alone
+    pause_reason: Option<PauseReason>,
+    is_suspended: bool,
+    dropped_frames: u64,
+    /// Size of the last captured frame before downscaling
+    source_dimensions: Option<(u32, u32)>,
+}
+
+/// What paused a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseReason {
+    /// The user paused it; only the user resumes it
+    Manual,
+    Sleep,
+    LowBattery,
+}
+
+/// Bounded hand-off between the capture and processing tasks
+///
+/// When processing falls behind, the oldest queued frame is discarded so
+/// capture never waits on encoding.
+struct FrameQueue {
+    frames: std::sync::Mutex<VecDeque<RawFrame>>,
+    capacity: usize,
+    closed: std::sync::atomic::AtomicBool,
+    notify: Notify,
+}
+
+impl FrameQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            frames: std::sync::Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            closed: std::sync::atomic::AtomicBool::new(false),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Queue a frame, returning true if an older frame was dropped to make room
+    fn push(&self, frame: RawFrame) -> bool {
+        let dropped = {
+            let mut frames = self.frames.lock().unwrap();
+            let dropped = frames.len() >= self.capacity;
+            if dropped {
+                frames.pop_front();
+            }
+            frames.push_back(frame);
+            dropped
+        };
+        self.notify.notify_one();
+        dropped
+    }
+
+    /// Wait for the next frame; None once closed and drained
+    async fn pop(&self) -> Option<RawFrame> {
+        loop {
+            if let Some(frame) = self.frames.lock().unwrap().pop_front() {
+                return Some(frame);
+            }
+            if self.closed.load(std::sync::atomic::Ordering::Acquire) {
+                return None;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, std::sync::atomic::Ordering::Release);
+        self.notify.notify_one();
+    }
 }
 
 /// High-level screen recorder with consent management
@@ -133,10 +276,16 @@ pub struct ScreenRecorder {
     capture: Arc<Mutex<Box<dyn ScreenCapture>>>,
     consent_manager: Arc<ConsentManager>,
     storage: Arc<RecordingStorage>,
-    config: RecordingConfig,
+    config: Arc<std::sync::RwLock<RecordingConfig>>,
     state: Arc<RwLock<Option<RecordingState>>>,
     stop_signal: Arc<RwLock<bool>>,
     power_manager: Arc<PowerManager>,
+    /// Set between `BatteryLow` and the next wake or charge event
+    low_battery: Arc<AtomicBool>,
+    event_tx: broadcast::Sender<RecordingEvent>,
+    progress_tx: broadcast::Sender<RecordingStatus>,
+    /// Capture and encode loop of the current recording
+    recording_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
 }
 
 impl ScreenRecorder {
@@ -158,10 +307,14 @@ impl ScreenRecorder {
             capture: Arc::new(Mutex::new(capture)),
             consent_manager,
             storage,
-            config: RecordingConfig::default(),
+            config: Arc::new(std::sync::RwLock::new(RecordingConfig::default())),
             state: Arc::new(RwLock::new(None)),
             stop_signal: Arc::new(RwLock::new(false)),
             power_manager,
+            low_battery: Arc::new(AtomicBool::new(false)),
+            event_tx: broadcast::channel(16).0,
+            progress_tx: broadcast::channel(16).0,
+            recording_task: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -173,6 +326,7 @@ impl ScreenRecorder {
     ) -> CaptureResult<Self> {
         let capture = create_screen_capture().await?;
         let power_manager = Arc::new(PowerManager::new());
+        power_manager.set_low_battery_threshold(config.low_battery_percent);
 
         // Start power monitoring
         let pm = Arc::clone(&power_manager);
@@ -184,13 +338,107 @@ impl ScreenRecorder {
             capture: Arc::new(Mutex::new(capture)),
             consent_manager,
             storage,
-            config,
+            config: Arc::new(std::sync::RwLock::new(config)),
             state: Arc::new(RwLock::new(None)),
             stop_signal: Arc::new(RwLock::new(false)),
             power_manager,
+            low_battery: Arc::new(AtomicBool::new(false)),
+            event_tx: broadcast::channel(16).0,
+            progress_tx: broadcast::channel(16).0,
+            recording_task: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Current recording configuration
+    pub fn config(&self) -> RecordingConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Replace the recording configuration
+    ///
+    /// Only allowed while stopped, since a running loop has already derived
+    /// its frame interval and encoder settings from the old config.
+    pub async fn update_config(&self, config: RecordingConfig) -> CaptureResult<()> {
+        if config.target_fps == 0 || config.target_fps > 60 {
+            return Err(CaptureError::InvalidConfig(format!(
+                "target_fps must be between 1 and 60, got {}",
+                config.target_fps
+            )));
+        }
+        if config.buffer_size == 0 {
+            return Err(CaptureError::InvalidConfig("buffer_size must be greater than 0".to_string()));
+        }
+        if config.motion_algorithm == MotionAlgorithm::Block(0) {
+            return Err(CaptureError::InvalidConfig("Block size must be greater than 0".to_string()));
+        }
+        if config.capture_queue_size == 0 {
+            return Err(CaptureError::InvalidConfig(
+                "capture_queue_size must be greater than 0".to_string(),
+            ));
+        }
+        if config.max_consecutive_failures == 0 {
+            return Err(CaptureError::InvalidConfig(
+                "max_consecutive_failures must be greater than 0".to_string(),
+            ));
+        }
+        if config.min_changed_region_area > MAX_MIN_CHANGED_REGION_AREA {
+            return Err(CaptureError::InvalidConfig(format!(
+                "min_changed_region_area must be at most {} pixels, got {}",
+                MAX_MIN_CHANGED_REGION_AREA, config.min_changed_region_area
+            )));
+        }
+        if let MotionProfile::Custom(threshold) = config.motion_profile {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(CaptureError::InvalidConfig(format!(
+                    "Custom motion threshold must be between 0.0 and 1.0, got {}",
+                    threshold
+                )));
+            }
+        }
+        config
+            .quality
+            .validate()
+            .map_err(|e| CaptureError::InvalidConfig(e.to_string()))?;
+        if !(MIN_CAPTURE_SCALE..=1.0).contains(&config.capture_scale) {
+            return Err(CaptureError::InvalidConfig(format!(
+                "capture_scale must be between {} and 1.0, got {}",
+                MIN_CAPTURE_SCALE, config.capture_scale
+            )));
+        }
+        if config.low_battery_fps == 0 || config.low_battery_fps > 60 {
+            return Err(CaptureError::InvalidConfig(format!(
+                "low_battery_fps must be between 1 and 60, got {}",
+                config.low_battery_fps
+            )));
+        }
+        if config.low_battery_percent > 100 {
+            return Err(CaptureError::InvalidConfig(format!(
+                "low_battery_percent must be at most 100, got {}",
+                config.low_battery_percent
+            )));
+        }
+
+        // Hold the state lock so a recording can't start mid-update
+        let state = self.state.read().await;
+        if state.is_some() {
+            return Err(CaptureError::AlreadyCapturing);
+        }
+
+        self.power_manager.set_low_battery_threshold(config.low_battery_percent);
+        *self.config.write().unwrap() = config;
+        Ok(())
+    }
+
+    /// Subscribe to recording events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<RecordingEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Subscribe to status updates sent each time a segment is saved
+    pub fn subscribe_progress(&self) -> broadcast::Receiver<RecordingStatus> {
+        self.progress_tx.subscribe()
+    }
+
     /// Get list of available displays
     pub async fn get_available_displays(&self) -> CaptureResult<Vec<Display>> {
         let capture = self.capture.lock().await;
@@ -205,8 +453,20 @@ impl ScreenRecorder {
             .map_err(|e| CaptureError::CaptureFailed(format!("Failed to check consent: {}", e)))
     }
 
-    /// Start recording from the specified display
+    /// Start recording from the specified display under a new storage-owned session
+    ///
+    /// Prefer `start_recording_for_session` with the SessionManager's session
+    /// id so the recording joins with input and activity data.
     pub async fn start_recording(&self, display_id: u32) -> CaptureResult<()> {
+        self.start_recording_with(display_id, None).await
+    }
+
+    /// Start recording from the specified display under an existing session
+    pub async fn start_recording_for_session(&self, display_id: u32, session_id: Uuid) -> CaptureResult<()> {
+        self.start_recording_with(display_id, Some(session_id)).await
+    }
+
+    async fn start_recording_with(&self, display_id: u32, session_id: Option<Uuid>) -> CaptureResult<()> {
         // Check consent first
         if !self.check_consent().await? {
             return Err(CaptureError::PermissionDenied(
@@ -227,30 +487,47 @@ impl ScreenRecorder {
         let display = displays.iter().find(|d| d.id == display_id)
             .ok_or(CaptureError::DisplayNotFound(display_id))?;
 
-        // Create recording session
-        let session_id = self.storage.create_session(display_id).await
-            .map_err(|e| CaptureError::CaptureFailed(format!("Failed to create session: {}", e)))?;
+        // Record into the given session, or create one if none was given
+        let owns_session = session_id.is_none();
+        let session_id = match session_id {
+            Some(id) => {
+                self.storage.attach_session(id).await
+                    .map_err(|e| CaptureError::CaptureFailed(format!("Failed to attach session: {}", e)))?;
+                id
+            }
+            None => self.storage.create_session(display_id).await
+                .map_err(|e| CaptureError::CaptureFailed(format!("Failed to create session: {}", e)))?,
+        };
 
         // Initialize recording state
-        let motion_detector = MotionDetector::new(self.config.motion_detection_threshold);
+        let config = self.config();
+        let motion_detector = MotionDetector::with_algorithm(
+            config.motion_profile.to_threshold(),
+            config.motion_algorithm,
+        )
+        .with_min_changed_region_area(config.min_changed_region_area);
         let video_encoder = VideoEncoder::new(
-            self.config.codec,
-            self.config.quality,
-            self.config.hardware_acceleration,
+            config.codec,
+            config.quality,
+            config.hardware_acceleration,
         ).map_err(|e| CaptureError::CaptureFailed(format!("Failed to create encoder: {}", e)))?;
 
         let recording_state = RecordingState {
             session_id,
+            owns_session,
             display_id,
             motion_detector,
             video_encoder,
-            frame_buffer: Vec::with_capacity(self.config.buffer_size),
+            frame_buffer: Vec::with_capacity(config.buffer_size),
             base_layer: None,
             no_motion_count: 0,
             total_frames: 0,
             motion_frames: 0,
             segment_count: 0,
-            is_paused: false,
+            pause_reason: None,
+            is_suspended: false,
+            dropped_frames: 0,
+            source_dimensions: None,
         };
 
         *self.state.write().await = Some(recording_state);
@@ -262,11 +539,12 @@ impl ScreenRecorder {
 
         // Start recording loop in background
         let recorder = Arc::new(self.clone_for_recording());
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             if let Err(e) = recorder.recording_loop().await {
                 eprintln!("Recording loop error: {}", e);
             }
         });
+        *self.recording_task.lock().await = Some(task);
 
         Ok(())
     }
@@ -276,19 +554,26 @@ impl ScreenRecorder {
         // Signal stop
         *self.stop_signal.write().await = true;
 
-        // Wait for recording loop to stop
-        tokio::time::sleep(Duration::from_millis(500)).await;
+        // Wait for recording loop to stop and encode its last frames
+        if let Some(task) = self.recording_task.lock().await.take() {
+            let _ = task.await;
+        }
 
         // Get session ID before clearing state
-        let session_id = {
+        let session = {
             let state = self.state.read().await;
-            state.as_ref().map(|s| s.session_id)
+            state.as_ref().map(|s| (s.session_id, s.owns_session))
         };
 
-        if let Some(session_id) = session_id {
-            // End the session
-            self.storage.end_session(session_id).await
-                .map_err(|e| CaptureError::CaptureFailed(format!("Failed to end session: {}", e)))?;
+        if let Some((session_id, owns_session)) = session {
+            if owns_session {
+                self.storage.end_session(session_id).await
+                    .map_err(|e| CaptureError::CaptureFailed(format!("Failed to end session: {}", e)))?;
+            } else {
+                // The SessionManager decides when a shared session ends
+                self.storage.update_session_stats(session_id).await
+                    .map_err(|e| CaptureError::CaptureFailed(format!("Failed to update session: {}", e)))?;
+            }
 
             println!("Stopped recording session: {}", session_id);
         }
@@ -303,7 +588,7 @@ impl ScreenRecorder {
     pub async fn pause_recording(&self) -> CaptureResult<()> {
         let mut state = self.state.write().await;
         if let Some(ref mut s) = *state {
-            s.is_paused = true;
+            s.pause_reason = Some(PauseReason::Manual);
             println!("Recording paused");
             Ok(())
         } else {
@@ -311,11 +596,11 @@ impl ScreenRecorder {
         }
     }
 
-    /// Resume recording
+    /// Resume recording, whatever paused it
     pub async fn resume_recording(&self) -> CaptureResult<()> {
         let mut state = self.state.write().await;
         if let Some(ref mut s) = *state {
-            s.is_paused = false;
+            s.pause_reason = None;
             println!("Recording resumed");
             Ok(())
         } else {
@@ -323,51 +608,89 @@ impl ScreenRecorder {
         }
     }
 
+    /// Pause for `reason` unless the recording is already paused
+    async fn pause_for(&self, reason: PauseReason) {
+        if let Some(ref mut s) = *self.state.write().await {
+            s.pause_reason.get_or_insert(reason);
+        }
+    }
+
+    /// End a pause made for `reason`, switching it to `next` if that still applies
+    ///
+    /// Pauses made for any other reason, including by the user, are kept.
+    async fn end_pause_for(&self, reason: PauseReason, next: Option<PauseReason>) {
+        if let Some(ref mut s) = *self.state.write().await {
+            if s.pause_reason == Some(reason) {
+                s.pause_reason = next;
+            }
+        }
+    }
+
     /// Clone for recording thread
     fn clone_for_recording(&self) -> Self {
         Self {
             capture: Arc::clone(&self.capture),
             consent_manager: Arc::clone(&self.consent_manager),
             storage: Arc::clone(&self.storage),
-            config: self.config.clone(),
+            config: Arc::clone(&self.config),
             state: Arc::clone(&self.state),
             stop_signal: Arc::clone(&self.stop_signal),
             power_manager: Arc::clone(&self.power_manager),
+            low_battery: Arc::clone(&self.low_battery),
+            event_tx: self.event_tx.clone(),
+            progress_tx: self.progress_tx.clone(),
+            recording_task: Arc::clone(&self.recording_task),
         }
     }
 
-    /// Main recording loop - runs continuously until stopped
+    /// Main recording loop - captures at the target cadence until stopped
+    ///
+    /// Frames are handed to a separate processing task through a bounded
+    /// queue so motion detection and encoding can't stall capture.
     async fn recording_loop(&self) -> CaptureResult<()> {
-        let frame_interval = Duration::from_millis(1000 / self.config.target_fps as u64);
+        let config = self.config();
+        let queue = Arc::new(FrameQueue::new(config.capture_queue_size));
+
+        let processor = self.clone_for_recording();
+        let processor_queue = Arc::clone(&queue);
+        let processing = tokio::spawn(async move {
+            processor.processing_loop(processor_queue).await;
+        });
+
+        let result = self.capture_loop(&queue).await;
+
+        // Let processing drain the queue, then encode any remaining frames
+        queue.close();
+        let _ = processing.await;
+        self.flush_buffer().await?;
+
+        result
+    }
+
+    /// Grab frames at the target cadence and queue them until stopped
+    async fn capture_loop(&self, queue: &FrameQueue) -> CaptureResult<()> {
+        let config = self.config();
         let mut last_frame_time = Instant::now();
         let mut power_events = self.power_manager.subscribe();
+        let power_check_interval = Duration::from_secs(config.power_check_interval_secs);
+        let mut last_power_check: Option<Instant> = None;
+        let mut consecutive_failures = 0u32;
 
         loop {
             // Check stop signal
             if *self.stop_signal.read().await {
-                // Encode any remaining frames
-                self.flush_buffer().await?;
                 break;
             }
 
             // Check for power events (non-blocking)
             if let Ok(event) = power_events.try_recv() {
-                match event {
-                    PowerEvent::Sleep => {
-                        println!("System going to sleep - pausing recording");
-                        let _ = self.pause_recording().await;
-                    }
-                    PowerEvent::Wake => {
-                        println!("System waking up - resuming recording");
-                        let _ = self.resume_recording().await;
-                    }
-                }
+                self.handle_power_event(event).await;
             }
 
             // Check if paused
             let is_paused = {
                 let state = self.state.read().await;
-                state.as_ref().map(|s| s.is_paused).unwrap_or(false)
+                state.as_ref().is_some_and(|s| s.pause_reason.is_some())
             };
 
             if is_paused {
@@ -375,43 +698,190 @@ impl ScreenRecorder {
                 continue;
             }
 
+            // Periodically check whether we should suspend for idle on battery
+            if self.config().suspend_on_battery_idle
+                && last_power_check.map_or(true, |t| t.elapsed() >= power_check_interval)
+            {
+                last_power_check = Some(Instant::now());
+                self.update_idle_suspension().await?;
+            }
+
+            let is_suspended = {
+                let state = self.state.read().await;
+                state.as_ref().map(|s| s.is_suspended).unwrap_or(false)
+            };
+
+            if is_suspended {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                continue;
+            }
+
             // Maintain frame rate
+            let frame_interval = self.frame_interval();
             let elapsed = last_frame_time.elapsed();
             if elapsed < frame_interval {
                 tokio::time::sleep(frame_interval - elapsed).await;
             }
             last_frame_time = Instant::now();
 
-            // Process one frame
-            if let Err(e) = self.process_frame().await {
-                eprintln!("Frame processing error: {}", e);
+            // Capture one frame
+            match self.capture_next_frame().await {
+                Ok(frame) => {
+                    consecutive_failures = 0;
+                    if queue.push(frame) {
+                        if let Some(s) = self.state.write().await.as_mut() {
+                            s.dropped_frames += 1;
+                        }
+                    }
+                }
+                Err(e) => {
+                    consecutive_failures += 1;
+                    // Only log the start of a burst
+                    if consecutive_failures == 1 {
+                        eprintln!("Frame capture error: {}", e);
+                    }
+                    if consecutive_failures >= config.max_consecutive_failures {
+                        self.recover_capture(consecutive_failures).await;
+                        consecutive_failures = 0;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// React to sleep/wake and battery changes from the power manager
+    async fn handle_power_event(&self, event: PowerEvent) {
+        match event {
+            PowerEvent::Sleep => {
+                println!("System going to sleep - pausing recording");
+                self.pause_for(PauseReason::Sleep).await;
+            }
+            PowerEvent::Wake => {
+                // The battery poller keeps tracking the level across sleep and
+                // reports any change since, so the low battery state stands
+                let still_low = self.low_battery.load(Ordering::Relaxed) && self.config().pause_on_low_battery;
+                println!("System waking up - resuming recording");
+                self.end_pause_for(PauseReason::Sleep, still_low.then_some(PauseReason::LowBattery)).await;
+            }
+            PowerEvent::BatteryLow => {
+                self.low_battery.store(true, Ordering::Relaxed);
+                if self.config().pause_on_low_battery {
+                    println!("Battery low - pausing recording");
+                    self.pause_for(PauseReason::LowBattery).await;
+                } else {
+                    println!("Battery low - reducing frame rate");
+                }
+            }
+            PowerEvent::Charging => {
+                if self.low_battery.swap(false, Ordering::Relaxed) {
+                    println!("Charging - resuming recording");
+                    self.end_pause_for(PauseReason::LowBattery, None).await;
+                }
             }
         }
+    }
+
+    /// Time between captured frames, stretched while the battery is low
+    fn frame_interval(&self) -> Duration {
+        let config = self.config();
+        let fps = if self.low_battery.load(Ordering::Relaxed) {
+            config.low_battery_fps.min(config.target_fps)
+        } else {
+            config.target_fps
+        };
+
+        Duration::from_millis(1000 / fps.max(1) as u64)
+    }
+
+    /// Suspend capture when idle on battery, resume on input or AC power
+    async fn update_idle_suspension(&self) -> CaptureResult<()> {
+        let on_battery = PowerManager::is_on_battery();
+        let idle_seconds = IdleDetector::get_idle_time()
+            .await
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let should_suspend =
+            on_battery && idle_seconds >= self.config().battery_idle_threshold_secs;
+
+        let (session_id, was_suspended) = {
+            let mut state = self.state.write().await;
+            let s = state.as_mut().ok_or(CaptureError::NotCapturing)?;
+            let was_suspended = s.is_suspended;
+            s.is_suspended = should_suspend;
+            (s.session_id, was_suspended)
+        };
+
+        if should_suspend && !was_suspended {
+            // Don't leave buffered motion sitting in memory while suspended
+            self.flush_buffer().await?;
+
+            println!(
+                "Idle for {}s on battery - suspending capture",
+                idle_seconds
+            );
+            let _ = self.event_tx.send(RecordingEvent::SuspendedIdle {
+                session_id,
+                idle_seconds,
+            });
+        } else if !should_suspend && was_suspended {
+            println!("Activity or AC power detected - resuming capture");
+            let _ = self.event_tx.send(RecordingEvent::ResumedFromIdle { session_id });
+        }
 
         Ok(())
     }
 
-    /// Process a single frame
-    async fn process_frame(&self) -> CaptureResult<()> {
+    /// Run queued frames through motion detection and encoding
+    async fn processing_loop(&self, queue: Arc<FrameQueue>) {
+        while let Some(frame) = queue.pop().await {
+            if let Err(e) = self.process_frame(frame).await {
+                eprintln!("Frame processing error: {}", e);
+            }
+        }
+    }
+
+    /// Capture the next frame from the recorded display
+    async fn capture_next_frame(&self) -> CaptureResult<RawFrame> {
         let display_id = {
             let state = self.state.read().await;
             let s = state.as_ref().ok_or(CaptureError::NotCapturing)?;
             s.display_id
         };
 
-        // Capture frame
-        let capture = self.capture.lock().await;
-        let frame = capture.capture_frame(display_id).await?;
-        drop(capture);
+        self.capture_with_retry(display_id).await
+    }
+
+    /// Process a single captured frame
+    async fn process_frame(&self, frame: RawFrame) -> CaptureResult<()> {
+        let source_dimensions = (frame.width, frame.height);
+        let scale = self.config().capture_scale;
+        let frame = if scale < 1.0 {
+            downscale_frame(&frame, scale)
+        } else {
+            frame
+        };
 
         // Detect motion
-        let motion = {
+        let (motion, resized_session) = {
             let mut state = self.state.write().await;
             let s = state.as_mut().ok_or(CaptureError::NotCapturing)?;
             s.total_frames += 1;
-            s.motion_detector.detect_motion(&frame)
+            let resized = s.source_dimensions != Some(source_dimensions);
+            s.source_dimensions = Some(source_dimensions);
+            (s.motion_detector.detect_motion(&frame), resized.then_some(s.session_id))
         };
 
+        // Keep the capture size with the session so it outlives the status
+        if let Some(session_id) = resized_session {
+            let (width, height) = source_dimensions;
+            if let Err(e) = self.storage.save_source_dimensions(&session_id, width, height).await {
+                eprintln!("Failed to save source dimensions: {}", e);
+            }
+        }
+
         // Handle based on motion
         if motion.has_motion {
             self.handle_motion_frame(frame, motion).await?;
@@ -422,6 +892,45 @@ impl ScreenRecorder {
         Ok(())
     }
 
+    /// Capture a frame, retrying transient failures with exponential backoff
+    async fn capture_with_retry(&self, display_id: u32) -> CaptureResult<RawFrame> {
+        let config = self.config();
+        let mut backoff = Duration::from_millis(config.capture_retry_backoff_ms);
+        let mut attempt = 0;
+
+        loop {
+            let result = self.capture.lock().await.capture_frame(display_id).await;
+            match result {
+                Err(CaptureError::CaptureFailed(_)) if attempt < config.capture_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Recreate the platform capture backend after repeated failures
+    async fn recover_capture(&self, failures: u32) {
+        eprintln!(
+            "{} consecutive frame failures - recreating capture backend",
+            failures
+        );
+
+        match create_screen_capture().await {
+            Ok(capture) => {
+                *self.capture.lock().await = capture;
+
+                let session_id = self.state.read().await.as_ref().map(|s| s.session_id);
+                if let Some(session_id) = session_id {
+                    let _ = self.event_tx.send(RecordingEvent::CaptureRecovered { session_id, failures });
+                }
+            }
+            Err(e) => eprintln!("Failed to recreate capture backend: {}", e),
+        }
+    }
+
     /// Handle a frame with motion detected
     async fn handle_motion_frame(&self, frame: RawFrame, motion: MotionResult) -> CaptureResult<()> {
         // Check if we need to update base layer and encode
@@ -446,7 +955,7 @@ impl ScreenRecorder {
             };
 
             // Buffer full - encode segment
-            let should_encode = s.frame_buffer.len() >= self.config.buffer_size;
+            let should_encode = s.frame_buffer.len() >= self.config().buffer_size;
 
             (should_save_base, should_encode)
         };
@@ -475,7 +984,7 @@ impl ScreenRecorder {
             s.no_motion_count += 1;
 
             // Motion stopped - encode what we have
-            s.no_motion_count >= self.config.no_motion_threshold && !s.frame_buffer.is_empty()
+            s.no_motion_count >= self.config().no_motion_threshold && !s.frame_buffer.is_empty()
         };
 
         if should_encode {
@@ -486,7 +995,7 @@ impl ScreenRecorder {
         let should_update_base = {
             let state = self.state.read().await;
             let s = state.as_ref().ok_or(CaptureError::NotCapturing)?;
-            s.no_motion_count == self.config.no_motion_threshold
+            s.no_motion_count == self.config().no_motion_threshold
         };
 
         if should_update_base {
@@ -525,7 +1034,7 @@ impl ScreenRecorder {
             let s = state.as_ref().ok_or(CaptureError::NotCapturing)?;
 
             s.video_encoder
-                .encode_frames(frames, output_path, self.config.target_fps)
+                .encode_frames(frames, output_path, self.config().target_fps)
                 .await
                 .map_err(|e| CaptureError::CaptureFailed(format!("Encoding failed: {}", e)))?
         };
@@ -541,6 +1050,12 @@ impl ScreenRecorder {
             segment_num, segment.frame_count, segment.file_size_bytes
         );
 
+        if self.progress_tx.receiver_count() > 0 {
+            if let Ok(status) = self.get_status().await {
+                let _ = self.progress_tx.send(status);
+            }
+        }
+
         Ok(())
     }
 
@@ -610,7 +1125,13 @@ impl ScreenRecorder {
                 session_id: Some(s.session_id),
                 segment_count: s.segment_count,
                 total_motion_percentage,
-                is_paused: s.is_paused,
+                is_paused: s.pause_reason.is_some(),
+                is_suspended: s.is_suspended,
+                dropped_frames: s.dropped_frames,
+                source_width: s.source_dimensions.map(|(width, _)| width),
+                source_height: s.source_dimensions.map(|(_, height)| height),
+                active_encoder: s.video_encoder.active_encoder(),
+                hardware_fallback: s.video_encoder.hardware_fallback(),
             })
         } else {
             Ok(RecordingStatus {
@@ -622,6 +1143,12 @@ impl ScreenRecorder {
                 segment_count: 0,
                 total_motion_percentage: 0.0,
                 is_paused: false,
+                is_suspended: false,
+                dropped_frames: 0,
+                source_width: None,
+                source_height: None,
+                active_encoder: String::new(),
+                hardware_fallback: false,
             })
         }
     }
@@ -640,6 +1167,59 @@ impl ScreenRecorder {
     }
 }
 
+/// Shrink a frame by `scale` using a box filter over 4-byte pixels.
+///
+/// Output dimensions are rounded down to even numbers (at least 2) so the
+/// encoder's yuv420p conversion never sees an odd size.
+pub fn downscale_frame(frame: &RawFrame, scale: f32) -> RawFrame {
+    let target = |size: u32| (((size as f32 * scale) as u32) & !1).max(2).min(size);
+    let width = target(frame.width);
+    let height = target(frame.height);
+
+    if width == frame.width && height == frame.height {
+        return frame.clone();
+    }
+
+    let src_stride = frame.width as usize * 4;
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+
+    for y in 0..height as usize {
+        // Source rows and columns that fall inside this output pixel
+        let y0 = y * frame.height as usize / height as usize;
+        let y1 = ((y + 1) * frame.height as usize / height as usize).max(y0 + 1);
+
+        for x in 0..width as usize {
+            let x0 = x * frame.width as usize / width as usize;
+            let x1 = ((x + 1) * frame.width as usize / width as usize).max(x0 + 1);
+
+            let mut sums = [0u32; 4];
+            for sy in y0..y1 {
+                let row = sy * src_stride;
+                for sx in x0..x1 {
+                    let offset = row + sx * 4;
+                    for (sum, &value) in sums.iter_mut().zip(&frame.data[offset..offset + 4]) {
+                        *sum += value as u32;
+                    }
+                }
+            }
+
+            let count = ((y1 - y0) * (x1 - x0)) as u32;
+            let out = (y * width as usize + x) * 4;
+            for (channel, sum) in sums.iter().enumerate() {
+                data[out + channel] = (sum / count) as u8;
+            }
+        }
+    }
+
+    RawFrame {
+        timestamp: frame.timestamp,
+        width,
+        height,
+        data,
+        format: frame.format,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -842,10 +1422,168 @@ mod tests {
         let status = recorder.get_status().await.expect("Failed to get status");
         assert!(!status.is_paused);
 
+        // Power events only end the pauses they caused
+        recorder.pause_recording().await.expect("Failed to pause");
+        recorder.handle_power_event(PowerEvent::BatteryLow).await;
+        recorder.handle_power_event(PowerEvent::Charging).await;
+        recorder.handle_power_event(PowerEvent::Sleep).await;
+        recorder.handle_power_event(PowerEvent::Wake).await;
+        assert!(recorder.get_status().await.expect("Failed to get status").is_paused);
+
+        recorder.resume_recording().await.expect("Failed to resume");
+        recorder.handle_power_event(PowerEvent::Sleep).await;
+        assert!(recorder.get_status().await.expect("Failed to get status").is_paused);
+        recorder.handle_power_event(PowerEvent::Wake).await;
+        assert!(!recorder.get_status().await.expect("Failed to get status").is_paused);
+
         // Stop
         recorder.stop_recording().await.expect("Failed to stop");
 
         // Cleanup
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
+
+    #[tokio::test]
+    async fn test_update_config() {
+        let db = Arc::new(Database::init().await.expect("Failed to init database"));
+        let consent_manager = Arc::new(
+            ConsentManager::new(db.clone()).await.expect("Failed to create consent manager")
+        );
+
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_config");
+        let storage = Arc::new(
+            RecordingStorage::new(temp_dir.clone(), db.clone())
+                .await
+                .expect("Failed to create storage")
+        );
+
+        let recorder = match ScreenRecorder::new(consent_manager, storage).await {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("Failed to create recorder: {}", e);
+                let _ = std::fs::remove_dir_all(&temp_dir);
+                return;
+            }
+        };
+
+        assert_eq!(recorder.config().target_fps, 10);
+
+        let config = RecordingConfig { target_fps: 5, ..RecordingConfig::default() };
+        recorder.update_config(config).await.expect("Failed to update config");
+        assert_eq!(recorder.config().target_fps, 5);
+
+        // Zero FPS would divide by zero in the recording loop
+        let invalid = RecordingConfig { target_fps: 0, ..RecordingConfig::default() };
+        assert!(matches!(
+            recorder.update_config(invalid).await,
+            Err(CaptureError::InvalidConfig(_))
+        ));
+        assert_eq!(recorder.config().target_fps, 5);
+
+        // A gate bigger than any display would switch motion detection off
+        let invalid = RecordingConfig {
+            min_changed_region_area: MAX_MIN_CHANGED_REGION_AREA + 1,
+            ..RecordingConfig::default()
+        };
+        assert!(matches!(
+            recorder.update_config(invalid).await,
+            Err(CaptureError::InvalidConfig(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_battery_low_slows_capture() {
+        let db = Arc::new(Database::init().await.expect("Failed to init database"));
+        let consent_manager = Arc::new(
+            ConsentManager::new(db.clone()).await.expect("Failed to create consent manager")
+        );
+
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_battery");
+        let storage = Arc::new(
+            RecordingStorage::new(temp_dir.clone(), db.clone())
+                .await
+                .expect("Failed to create storage")
+        );
+
+        let recorder = match ScreenRecorder::new(consent_manager, storage).await {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("Failed to create recorder: {}", e);
+                let _ = std::fs::remove_dir_all(&temp_dir);
+                return;
+            }
+        };
+
+        let normal = recorder.frame_interval();
+        assert_eq!(normal, Duration::from_millis(100));
+
+        let mut events = recorder.power_manager.subscribe();
+        recorder.power_manager.trigger_event(PowerEvent::BatteryLow);
+        recorder.handle_power_event(events.recv().await.unwrap()).await;
+        assert_eq!(recorder.frame_interval(), Duration::from_millis(500));
+
+        // Waking doesn't end low battery; only the poller's Charging does
+        recorder.handle_power_event(PowerEvent::Sleep).await;
+        recorder.handle_power_event(PowerEvent::Wake).await;
+        assert_eq!(recorder.frame_interval(), Duration::from_millis(500));
+
+        recorder.power_manager.trigger_event(PowerEvent::Charging);
+        recorder.handle_power_event(events.recv().await.unwrap()).await;
+        assert_eq!(recorder.frame_interval(), normal);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_frame_queue_drops_oldest() {
+        let frame = |timestamp| RawFrame {
+            timestamp,
+            width: 1,
+            height: 1,
+            data: vec![0; 4],
+            format: crate::models::capture::PixelFormat::RGBA8,
+        };
+
+        let queue = FrameQueue::new(2);
+        assert!(!queue.push(frame(1)));
+        assert!(!queue.push(frame(2)));
+        assert!(queue.push(frame(3)));
+        queue.close();
+
+        assert_eq!(queue.pop().await.map(|f| f.timestamp), Some(2));
+        assert_eq!(queue.pop().await.map(|f| f.timestamp), Some(3));
+        assert!(queue.pop().await.is_none());
+    }
+
+    #[test]
+    fn test_capture_scale_halves_frame() {
+        // Left half black, right half white
+        let (width, height) = (8u32, 4u32);
+        let mut data = vec![0u8; (width * height * 4) as usize];
+        for y in 0..height {
+            for x in width / 2..width {
+                let offset = ((y * width + x) * 4) as usize;
+                data[offset..offset + 4].fill(255);
+            }
+        }
+        let frame = RawFrame {
+            timestamp: 42,
+            width,
+            height,
+            data,
+            format: crate::models::capture::PixelFormat::BGRA8,
+        };
+
+        let scaled = downscale_frame(&frame, 0.5);
+        assert_eq!((scaled.width, scaled.height), (4, 2));
+        assert_eq!(scaled.data.len(), (scaled.width * scaled.height * 4) as usize);
+        assert_eq!(scaled.timestamp, 42);
+        assert_eq!(scaled.format, crate::models::capture::PixelFormat::BGRA8);
+        assert_eq!(&scaled.data[0..4], &[0, 0, 0, 0]);
+        assert_eq!(&scaled.data[12..16], &[255, 255, 255, 255]);
+
+        assert_eq!(downscale_frame(&frame, 1.0).data, frame.data);
+    }
 }
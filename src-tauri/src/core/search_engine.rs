@@ -1,8 +1,11 @@
 // Full-text search engine for OCR results using FTS5
 
-use crate::core::database::Database;
+use crate::core::database::{placeholders, Database};
 use crate::models::ocr::BoundingBox;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sqlx::query::QueryAs;
+use sqlx::Sqlite;
 use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
@@ -29,31 +32,96 @@ pub enum SearchError {
 
 type Result<T> = std::result::Result<T, SearchError>;
 
+/// A value bound to a placeholder of a filter clause
+#[derive(Debug, Clone, PartialEq)]
+enum FilterValue {
+    Text(String),
+    Integer(i64),
+    Real(f64),
+}
+
+/// Bind a filter clause's values, in placeholder order
+fn bind_filters<'q, O>(
+    mut query: QueryAs<'q, Sqlite, O, <Sqlite as sqlx::Database>::Arguments<'q>>,
+    values: &'q [FilterValue],
+) -> QueryAs<'q, Sqlite, O, <Sqlite as sqlx::Database>::Arguments<'q>> {
+    for value in values {
+        query = match value {
+            FilterValue::Text(text) => query.bind(text.as_str()),
+            FilterValue::Integer(integer) => query.bind(*integer),
+            FilterValue::Real(real) => query.bind(*real),
+        };
+    }
+    query
+}
+
 // ==============================================================================
 // Search Query
 // ==============================================================================
 
+/// How the query string is matched against OCR text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Word prefix match, or a phrase when the query has several words
+    #[default]
+    Substring,
+    /// The exact sequence of words
+    Phrase,
+    /// A regular expression, applied to each candidate row
+    Regex,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchQuery {
     pub query: String,
     #[serde(default)]
+    pub mode: SearchMode,
+    #[serde(default)]
     pub filters: SearchFilters,
     #[serde(default = "default_limit")]
     pub limit: u32,
     #[serde(default)]
     pub offset: u32,
+    /// Wrap the matched term in the snippet with <mark> tags
+    #[serde(default)]
+    pub highlight: bool,
+    /// Characters of context kept on each side of the match in the snippet
+    #[serde(default = "default_snippet_chars")]
+    pub snippet_chars: usize,
 }
 
 fn default_limit() -> u32 {
     50
 }
 
+fn default_snippet_chars() -> usize {
+    50
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchFilters {
     pub session_ids: Option<Vec<Uuid>>,
     pub date_range: Option<TimeRange>,
+    /// Only results at or after this timestamp
+    pub start_timestamp: Option<i64>,
+    /// Only results at or before this timestamp
+    pub end_timestamp: Option<i64>,
     pub min_confidence: Option<f32>,
+    /// Only results captured while one of these apps had focus
     pub app_names: Option<Vec<String>>,
+    /// The app in focus now, whose span isn't stored until focus moves on;
+    /// filled in by the backend for `app_names` and result app context
+    #[serde(skip)]
+    pub open_focus: Option<OpenFocus>,
+}
+
+/// The focus span still in progress in the session being recorded
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenFocus {
+    pub session_id: Uuid,
+    pub app_name: String,
+    pub start_timestamp: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,13 +175,23 @@ struct SearchResultRow {
     confidence: f64,
     bounding_box: String,
     app_context: Option<String>,
-    rank: f64,
+    score: f64,
 }
 
 // ==============================================================================
 // Search Engine
 // ==============================================================================
 
+/// OCR rows read per query while scanning for regex matches
+const REGEX_SCAN_BATCH: i64 = 1_000;
+
+/// App in focus when an OCR row was captured, from the recorded focus spans
+const APP_CONTEXT_SQL: &str = "(SELECT f.app_name FROM app_focus_spans f
+                 WHERE f.session_id = o.session_id
+                   AND o.timestamp >= f.start_timestamp
+                   AND o.timestamp < f.end_timestamp
+                 LIMIT 1)";
+
 pub struct SearchEngine {
     db: Arc<Database>,
 }
@@ -124,14 +202,26 @@ impl SearchEngine {
     }
 
     /// Search OCR results using full-text search
+    ///
+    /// Hits are ordered by BM25 relevance, best first.
     pub async fn search(&self, query: SearchQuery) -> Result<SearchResults> {
+        match query.mode {
+            SearchMode::Substring | SearchMode::Phrase => self.search_fts(query).await,
+            SearchMode::Regex => self.search_regex(query).await,
+        }
+    }
+
+    async fn search_fts(&self, query: SearchQuery) -> Result<SearchResults> {
         let start_time = std::time::Instant::now();
 
         // Build FTS5 query
-        let fts_query = self.build_fts_query(&query.query)?;
+        let fts_query = match query.mode {
+            SearchMode::Phrase => self.build_phrase_query(&query.query)?,
+            _ => self.build_fts_query(&query.query)?,
+        };
 
         // Build filter clauses
-        let filter_clause = self.build_filter_clause(&query.filters)?;
+        let (filter_clause, filter_values) = self.build_filter_clause(&query.filters)?;
 
         // Execute search
         let sql = format!(
@@ -143,20 +233,20 @@ impl SearchEngine {
                 o.text,
                 o.confidence,
                 o.bounding_box,
-                NULL as app_context,
-                rank as rank
-            FROM ocr_fts fts
-            JOIN ocr_results o ON fts.rowid = o.rowid
-            WHERE fts.text MATCH ?
+                {} as app_context,
+                bm25(ocr_fts) as score
+            FROM ocr_fts
+            JOIN ocr_results o ON ocr_fts.rowid = o.rowid
+            WHERE ocr_fts MATCH ?
             {}
-            ORDER BY rank
+            ORDER BY score
             LIMIT ? OFFSET ?
             "#,
+            APP_CONTEXT_SQL,
             filter_clause
         );
 
-        let rows = sqlx::query_as::<_, SearchResultRow>(&sql)
-            .bind(&fts_query)
+        let rows = bind_filters(sqlx::query_as::<_, SearchResultRow>(&sql).bind(&fts_query), &filter_values)
             .bind(query.limit as i64)
             .bind(query.offset as i64)
             .fetch_all(self.db.pool())
@@ -166,23 +256,22 @@ impl SearchEngine {
         let count_sql = format!(
             r#"
             SELECT COUNT(*) as count
-            FROM ocr_fts fts
-            JOIN ocr_results o ON fts.rowid = o.rowid
-            WHERE fts.text MATCH ?
+            FROM ocr_fts
+            JOIN ocr_results o ON ocr_fts.rowid = o.rowid
+            WHERE ocr_fts MATCH ?
             {}
             "#,
             filter_clause
         );
 
-        let total_count: i64 = sqlx::query_scalar(&count_sql)
-            .bind(&fts_query)
+        let (total_count,): (i64,) = bind_filters(sqlx::query_as(&count_sql).bind(&fts_query), &filter_values)
             .fetch_one(self.db.pool())
             .await?;
 
         // Convert to SearchResult
         let search_results: Vec<SearchResult> = rows
             .into_iter()
-            .map(|row| self.row_to_search_result(row, &query.query))
+            .map(|row| self.row_to_search_result(row, &query, None))
             .collect::<Result<Vec<_>>>()?;
 
         let query_time = start_time.elapsed();
@@ -194,6 +283,74 @@ impl SearchEngine {
         })
     }
 
+    /// Match a regular expression against every row that passes the filters
+    ///
+    /// The index cannot narrow a regex, so this scans, reading rows in batches
+    /// and keeping only the requested page; hits are newest first.
+    async fn search_regex(&self, query: SearchQuery) -> Result<SearchResults> {
+        let start_time = std::time::Instant::now();
+
+        let regex = Regex::new(&query.query)
+            .map_err(|e| SearchError::InvalidQuery(format!("Invalid regex: {}", e)))?;
+
+        let (filter_clause, filter_values) = self.build_filter_clause(&query.filters)?;
+        let sql = format!(
+            r#"
+            SELECT
+                o.id,
+                o.session_id,
+                o.timestamp,
+                o.text,
+                o.confidence,
+                o.bounding_box,
+                {} as app_context,
+                0.0 as score
+            FROM ocr_results o
+            WHERE 1 = 1
+            {}
+            ORDER BY o.timestamp DESC, o.id
+            LIMIT ? OFFSET ?
+            "#,
+            APP_CONTEXT_SQL,
+            filter_clause
+        );
+
+        let mut total_count = 0usize;
+        let mut page = Vec::new();
+        let mut scanned = 0i64;
+        loop {
+            let rows = bind_filters(sqlx::query_as::<_, SearchResultRow>(&sql), &filter_values)
+                .bind(REGEX_SCAN_BATCH)
+                .bind(scanned)
+                .fetch_all(self.db.pool())
+                .await?;
+            let batch_len = rows.len() as i64;
+
+            for row in rows.into_iter().filter(|row| regex.is_match(&row.text)) {
+                if total_count >= query.offset as usize && page.len() < query.limit as usize {
+                    page.push(row);
+                }
+                total_count += 1;
+            }
+
+            scanned += batch_len;
+            if batch_len < REGEX_SCAN_BATCH {
+                break;
+            }
+        }
+
+        let search_results: Vec<SearchResult> = page
+            .into_iter()
+            .map(|row| self.row_to_search_result(row, &query, Some(&regex)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SearchResults {
+            results: search_results,
+            total_count: total_count as u32,
+            query_time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+
     /// Search with surrounding context
     pub async fn search_with_context(
         &self,
@@ -277,86 +434,181 @@ impl SearchEngine {
         }
     }
 
-    /// Build SQL filter clause from filters
-    fn build_filter_clause(&self, filters: &SearchFilters) -> Result<String> {
+    /// Build an FTS5 phrase query matching the words exactly, in order
+    fn build_phrase_query(&self, query: &str) -> Result<String> {
+        let trimmed = query.trim();
+        if trimmed.is_empty() {
+            return Err(SearchError::InvalidQuery("Query cannot be empty".to_string()));
+        }
+
+        // Double quotes are escaped by doubling them inside an FTS5 string
+        Ok(format!("\"{}\"", trimmed.replace('"', "\"\"")))
+    }
+
+    /// Build SQL filter clause from filters, with the values for its placeholders
+    fn build_filter_clause(&self, filters: &SearchFilters) -> Result<(String, Vec<FilterValue>)> {
         let mut clauses = Vec::new();
+        let mut values = Vec::new();
 
         if let Some(ref session_ids) = filters.session_ids {
-            let ids: Vec<String> = session_ids
-                .iter()
-                .map(|id| format!("'{}'", id))
-                .collect();
-            clauses.push(format!("o.session_id IN ({})", ids.join(", ")));
+            clauses.push(format!("o.session_id IN ({})", placeholders(session_ids.len())));
+            values.extend(session_ids.iter().map(|id| FilterValue::Text(id.to_string())));
         }
 
         if let Some(ref range) = filters.date_range {
-            clauses.push(format!(
-                "o.timestamp BETWEEN {} AND {}",
-                range.start, range.end
-            ));
+            clauses.push("o.timestamp BETWEEN ? AND ?".to_string());
+            values.push(FilterValue::Integer(range.start));
+            values.push(FilterValue::Integer(range.end));
+        }
+
+        if let Some(start) = filters.start_timestamp {
+            clauses.push("o.timestamp >= ?".to_string());
+            values.push(FilterValue::Integer(start));
+        }
+
+        if let Some(end) = filters.end_timestamp {
+            clauses.push("o.timestamp <= ?".to_string());
+            values.push(FilterValue::Integer(end));
         }
 
         if let Some(min_conf) = filters.min_confidence {
-            clauses.push(format!("o.confidence >= {}", min_conf));
+            clauses.push("o.confidence >= ?".to_string());
+            values.push(FilterValue::Real(min_conf as f64));
+        }
+
+        if let Some(ref app_names) = filters.app_names {
+            let mut clause = format!(
+                "EXISTS (SELECT 1 FROM app_focus_spans f
+                         WHERE f.session_id = o.session_id
+                           AND f.app_name IN ({})
+                           AND o.timestamp >= f.start_timestamp
+                           AND o.timestamp < f.end_timestamp)",
+                placeholders(app_names.len())
+            );
+            values.extend(app_names.iter().cloned().map(FilterValue::Text));
+
+            if let Some(open) = filters.open_focus.as_ref().filter(|open| app_names.contains(&open.app_name)) {
+                clause = format!("({} OR (o.session_id = ? AND o.timestamp >= ?))", clause);
+                values.push(FilterValue::Text(open.session_id.to_string()));
+                values.push(FilterValue::Integer(open.start_timestamp));
+            }
+            clauses.push(clause);
         }
 
         if clauses.is_empty() {
-            Ok(String::new())
+            Ok((String::new(), values))
         } else {
-            Ok(format!("AND {}", clauses.join(" AND ")))
+            Ok((format!("AND {}", clauses.join(" AND ")), values))
         }
     }
 
     /// Convert database row to SearchResult
-    fn row_to_search_result(&self, row: SearchResultRow, query: &str) -> Result<SearchResult> {
-        let snippet = self.generate_snippet(&row.text, query, 100);
+    fn row_to_search_result(
+        &self,
+        row: SearchResultRow,
+        query: &SearchQuery,
+        regex: Option<&Regex>,
+    ) -> Result<SearchResult> {
+        let snippet = match regex {
+            Some(regex) => {
+                let found = regex.find(&row.text).map(|m| (m.start(), m.end()));
+                self.snippet_around(&row.text, found, query.snippet_chars, query.highlight)
+            }
+            None => self.generate_snippet(&row.text, &query.query, query.snippet_chars, query.highlight),
+        };
         let bounding_box: BoundingBox = serde_json::from_str(&row.bounding_box)?;
+        let session_id = Uuid::parse_str(&row.session_id)?;
+
+        // Rows captured since the last focus change fall in the open span
+        let app_context = row.app_context.or_else(|| {
+            query
+                .filters
+                .open_focus
+                .as_ref()
+                .filter(|open| open.session_id == session_id && row.timestamp >= open.start_timestamp)
+                .map(|open| open.app_name.clone())
+        });
 
         Ok(SearchResult {
             id: row.id,
-            session_id: Uuid::parse_str(&row.session_id)?,
+            session_id,
             timestamp: row.timestamp,
             text_snippet: snippet,
             full_text: row.text,
             confidence: row.confidence as f32,
             bounding_box,
             frame_path: None,
-            app_context: row.app_context,
-            relevance_score: -row.rank as f32, // FTS5 rank is negative
+            app_context,
+            relevance_score: -row.score as f32, // bm25() is negative, lower is better
         })
     }
 
-    /// Generate text snippet highlighting the query
-    fn generate_snippet(&self, text: &str, query: &str, max_length: usize) -> String {
+    /// Extract the text around the first match of the query
+    ///
+    /// Keeps `context_chars` characters on each side of the match, marking
+    /// truncation with an ellipsis. With `highlight`, the text is HTML-escaped
+    /// and the match is wrapped in <mark> tags.
+    fn generate_snippet(&self, text: &str, query: &str, context_chars: usize, highlight: bool) -> String {
         // Remove quotes from phrase queries
         let query_clean = query.replace('"', "").replace('*', "");
-        let query_lower = query_clean.to_lowercase();
-        let text_lower = text.to_lowercase();
-
-        if let Some(pos) = text_lower.find(&query_lower) {
-            // Extract snippet around query
-            let start = pos.saturating_sub(max_length / 2);
-            let end = (pos + query_clean.len() + max_length / 2).min(text.len());
-            let mut snippet = text[start..end].to_string();
-
-            // Add ellipsis if truncated
-            if start > 0 {
-                snippet = format!("...{}", snippet);
-            }
+        let found = find_ignore_ascii_case(text, query_clean.trim());
+        self.snippet_around(text, found, context_chars, highlight)
+    }
+
+    /// Build a snippet around a byte range of `text`, or from its start when there is none
+    fn snippet_around(
+        &self,
+        text: &str,
+        found: Option<(usize, usize)>,
+        context_chars: usize,
+        highlight: bool,
+    ) -> String {
+        let escape = |s: &str| if highlight { escape_html(s) } else { s.to_string() };
+
+        let Some((match_start, match_end)) = found else {
+            // Query not found (shouldn't happen), return start of text
+            let end = text
+                .char_indices()
+                .nth(context_chars * 2)
+                .map(|(i, _)| i)
+                .unwrap_or(text.len());
+            let mut snippet = escape(&text[..end]);
             if end < text.len() {
-                snippet = format!("{}...", snippet);
+                snippet.push_str("...");
             }
+            return snippet;
+        };
 
-            snippet
+        let start = text[..match_start]
+            .char_indices()
+            .rev()
+            .take(context_chars)
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(match_start);
+        let end = text[match_end..]
+            .char_indices()
+            .nth(context_chars)
+            .map(|(i, _)| match_end + i)
+            .unwrap_or(text.len());
+
+        let matched = escape(&text[match_start..match_end]);
+        let mut snippet = String::new();
+        if start > 0 {
+            snippet.push_str("...");
+        }
+        snippet.push_str(&escape(&text[start..match_start]));
+        if highlight {
+            snippet.push_str(&format!("<mark>{}</mark>", matched));
         } else {
-            // Query not found (shouldn't happen), return start of text
-            let chars: String = text.chars().take(max_length).collect();
-            if text.len() > max_length {
-                format!("{}...", chars)
-            } else {
-                chars
-            }
+            snippet.push_str(&matched);
         }
+        snippet.push_str(&escape(&text[match_end..end]));
+        if end < text.len() {
+            snippet.push_str("...");
+        }
+
+        snippet
     }
 
     /// Get OCR text in a time range
@@ -385,23 +637,294 @@ impl SearchEngine {
     }
 }
 
+/// Byte range of the first ASCII case-insensitive occurrence of `needle`
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    if needle.is_empty() {
+        return None;
+    }
+
+    haystack.char_indices().find_map(|(i, _)| {
+        let end = i + needle.len();
+        haystack
+            .get(i..end)
+            .filter(|candidate| candidate.eq_ignore_ascii_case(needle))
+            .map(|_| (i, end))
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_search_db() -> Arc<Database> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.expect("Failed to run migrations");
+        db
+    }
+
+    async fn insert_ocr(db: &Database, session_id: &str, timestamp: i64, text: &str) {
+        sqlx::query(
+            "INSERT INTO ocr_results (id, session_id, timestamp, text, confidence, bounding_box, created_at)
+             VALUES (?, ?, ?, ?, 0.9, '{\"x\":0,\"y\":0,\"width\":10,\"height\":10}', ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(timestamp)
+        .bind(text)
+        .bind(timestamp)
+        .execute(db.pool())
+        .await
+        .expect("Failed to insert OCR row");
+    }
+
+    /// Best of several runs, to keep scheduler noise out of the comparison
+    async fn fastest_run(db: &Database, sql: &str, arg: &str) -> std::time::Duration {
+        let mut best = std::time::Duration::MAX;
+        for _ in 0..10 {
+            let started = std::time::Instant::now();
+            let rows: Vec<String> = sqlx::query_scalar(sql)
+                .bind(arg)
+                .fetch_all(db.pool())
+                .await
+                .expect("Query failed");
+            assert!(!rows.is_empty());
+            best = best.min(started.elapsed());
+        }
+        best
+    }
+
+    #[tokio::test]
+    async fn test_fts_ranked_and_faster_than_like() {
+        let db = setup_search_db().await;
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), 0, "test-device")
+            .await
+            .expect("Failed to create session");
+
+        let filler = "lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor ";
+        let mut tx = db.pool().begin().await.unwrap();
+        for i in 0..5000i64 {
+            sqlx::query(
+                "INSERT INTO ocr_results (id, session_id, timestamp, text, confidence, bounding_box, created_at)
+                 VALUES (?, ?, ?, ?, 0.9, '{}', ?)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(session_id.to_string())
+            .bind(i)
+            .bind(format!("{} frame {}", filler.repeat(4), i))
+            .bind(i)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        // One strong match and one weak match for the ranking check
+        insert_ocr(&db, &session_id.to_string(), 10_000, "quarterly quarterly quarterly report").await;
+        insert_ocr(&db, &session_id.to_string(), 10_001, &format!("{} quarterly", filler.repeat(4))).await;
+
+        let engine = SearchEngine::new(db.clone());
+        let results = engine
+            .search(SearchQuery {
+                query: "quarterly".to_string(),
+                mode: SearchMode::Substring,
+                filters: SearchFilters::default(),
+                limit: 10,
+                offset: 0,
+                highlight: false,
+                snippet_chars: default_snippet_chars(),
+            })
+            .await
+            .expect("Search failed");
+
+        assert_eq!(results.total_count, 2);
+        assert_eq!(results.results[0].timestamp, 10_000);
+        assert!(results.results[0].relevance_score > results.results[1].relevance_score);
+
+        let fts = fastest_run(
+            &db,
+            "SELECT o.text FROM ocr_fts JOIN ocr_results o ON ocr_fts.rowid = o.rowid
+             WHERE ocr_fts MATCH ? ORDER BY bm25(ocr_fts)",
+            "quarterly",
+        )
+        .await;
+        let like = fastest_run(
+            &db,
+            "SELECT text FROM ocr_results WHERE text LIKE ?",
+            "%quarterly%",
+        )
+        .await;
+
+        assert!(fts < like, "FTS took {:?}, LIKE took {:?}", fts, like);
+    }
 
     #[test]
     fn test_search_query_default() {
         let query = SearchQuery {
             query: "test".to_string(),
+            mode: SearchMode::default(),
             filters: SearchFilters::default(),
             limit: default_limit(),
             offset: 0,
+            highlight: false,
+            snippet_chars: default_snippet_chars(),
         };
 
         assert_eq!(query.limit, 50);
         assert_eq!(query.offset, 0);
     }
 
+    fn mode_query(query: &str, mode: SearchMode) -> SearchQuery {
+        SearchQuery {
+            query: query.to_string(),
+            mode,
+            filters: SearchFilters::default(),
+            limit: default_limit(),
+            offset: 0,
+            highlight: true,
+            snippet_chars: default_snippet_chars(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_modes() {
+        let db = setup_search_db().await;
+        let session_id = Uuid::new_v4().to_string();
+        db.create_session(&session_id, 0, "test-device")
+            .await
+            .expect("Failed to create session");
+
+        insert_ocr(&db, &session_id, 1, "server returned error 500 to the client").await;
+        insert_ocr(&db, &session_id, 2, "error while saving, code 500").await;
+        insert_ocr(&db, &session_id, 3, "ticket ABC-1234 closed").await;
+
+        let engine = SearchEngine::new(db.clone());
+
+        let substring = engine.search(mode_query("error", SearchMode::Substring)).await.unwrap();
+        assert_eq!(substring.total_count, 2);
+
+        let phrase = engine.search(mode_query("error 500", SearchMode::Phrase)).await.unwrap();
+        assert_eq!(phrase.total_count, 1);
+        assert_eq!(phrase.results[0].timestamp, 1);
+
+        let regex = engine.search(mode_query(r"[A-Z]{3}-\d{4}", SearchMode::Regex)).await.unwrap();
+        assert_eq!(regex.total_count, 1);
+        assert!(regex.results[0].text_snippet.contains("<mark>ABC-1234</mark>"));
+
+        let invalid = engine.search(mode_query("(unclosed", SearchMode::Regex)).await;
+        assert!(matches!(invalid, Err(SearchError::InvalidQuery(msg)) if msg.starts_with("Invalid regex")));
+    }
+
+    #[tokio::test]
+    async fn test_app_and_time_filters() {
+        let db = setup_search_db().await;
+        let session_id = Uuid::new_v4().to_string();
+        db.create_session(&session_id, 0, "test-device")
+            .await
+            .expect("Failed to create session");
+
+        for (app, start, end) in [("Safari", 0, 100), ("Code", 100, 200)] {
+            sqlx::query(
+                "INSERT INTO app_focus_spans (id, session_id, app_name, bundle_id, start_timestamp, end_timestamp, duration_ms)
+                 VALUES (?, ?, ?, '', ?, ?, ?)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&session_id)
+            .bind(app)
+            .bind(start)
+            .bind(end)
+            .bind(end - start)
+            .execute(db.pool())
+            .await
+            .expect("Failed to insert focus span");
+        }
+
+        insert_ocr(&db, &session_id, 50, "invoice draft in the browser").await;
+        insert_ocr(&db, &session_id, 150, "invoice template in the editor").await;
+
+        let engine = SearchEngine::new(db.clone());
+        let mut query = mode_query("invoice", SearchMode::Substring);
+
+        query.filters.app_names = Some(vec!["Code".to_string()]);
+        let results = engine.search(query.clone()).await.unwrap();
+        assert_eq!(results.total_count, 1);
+        assert_eq!(results.results[0].timestamp, 150);
+        assert_eq!(results.results[0].app_context.as_deref(), Some("Code"));
+
+        query.filters.app_names = Some(vec!["Terminal".to_string()]);
+        assert_eq!(engine.search(query.clone()).await.unwrap().total_count, 0);
+
+        // Terminal has had focus since 200, but that span isn't stored yet
+        insert_ocr(&db, &session_id, 250, "invoice total in the terminal").await;
+        query.filters.open_focus = Some(OpenFocus {
+            session_id: Uuid::parse_str(&session_id).unwrap(),
+            app_name: "Terminal".to_string(),
+            start_timestamp: 200,
+        });
+        let results = engine.search(query.clone()).await.unwrap();
+        assert_eq!(results.total_count, 1);
+        assert_eq!(results.results[0].timestamp, 250);
+        assert_eq!(results.results[0].app_context.as_deref(), Some("Terminal"));
+        query.filters.open_focus = None;
+        sqlx::query("DELETE FROM ocr_results WHERE timestamp = 250")
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        // Names are bound, not spliced into the SQL
+        query.filters.app_names = Some(vec!["Code') OR 1=1 OR ('".to_string()]);
+        assert_eq!(engine.search(query.clone()).await.unwrap().total_count, 0);
+
+        query.filters.app_names = None;
+        query.filters.session_ids = Some(vec![Uuid::parse_str(&session_id).unwrap()]);
+        query.filters.min_confidence = Some(0.5);
+        assert_eq!(engine.search(query.clone()).await.unwrap().total_count, 2);
+        query.filters.session_ids = Some(vec![Uuid::new_v4()]);
+        assert_eq!(engine.search(query.clone()).await.unwrap().total_count, 0);
+        query.filters.session_ids = None;
+        query.filters.min_confidence = None;
+
+        query.filters.app_names = None;
+        query.filters.end_timestamp = Some(99);
+        let results = engine.search(query.clone()).await.unwrap();
+        assert_eq!(results.total_count, 1);
+        assert_eq!(results.results[0].timestamp, 50);
+
+        query.filters.end_timestamp = None;
+        query.filters.start_timestamp = Some(100);
+        let results = engine.search(query).await.unwrap();
+        assert_eq!(results.total_count, 1);
+        assert_eq!(results.results[0].timestamp, 150);
+    }
+
+    #[tokio::test]
+    async fn test_highlighted_snippet() {
+        let engine = SearchEngine::new(setup_search_db().await);
+        let text = format!("{} Quarterly <Report> {}", "a".repeat(500), "b".repeat(500));
+
+        let snippet = engine.generate_snippet(&text, "quarterly", 20, true);
+
+        assert!(snippet.contains("<mark>Quarterly</mark>"));
+        assert!(snippet.contains("&lt;Report&gt;"));
+        assert!(snippet.starts_with("..."));
+        assert!(snippet.ends_with("..."));
+        assert!(snippet.len() < 100);
+
+        let plain = engine.generate_snippet(&text, "quarterly", 20, false);
+        assert!(!plain.contains("<mark>"));
+        assert!(plain.contains("Quarterly <Report>"));
+    }
+
     #[test]
     fn test_time_range() {
         let range = TimeRange {
@@ -0,0 +1,299 @@
+// Pipeline self-test - exercises capture, motion detection, encoding, storage and the database
+
+use crate::core::database::Database;
+use crate::core::motion_detector::MotionDetector;
+use crate::core::screen_recorder::ScreenRecorder;
+use crate::core::storage::RecordingStorage;
+use crate::core::video_encoder::{CompressionQuality, VideoCodec, VideoEncoder, VideoSegment};
+use crate::models::capture::{PixelFormat, RawFrame};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Dimensions of the synthetic frames (kept even for yuv420p)
+const SYNTHETIC_WIDTH: u32 = 64;
+const SYNTHETIC_HEIGHT: u32 = 64;
+
+/// Number of synthetic frames in the encoded segment
+const SYNTHETIC_FRAME_COUNT: usize = 5;
+
+type StepResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Outcome of a single self-test step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestStep {
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Outcome of a full self-test run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub steps: Vec<SelfTestStep>,
+    pub total_duration_ms: u64,
+}
+
+/// Run every pipeline step in order and report on each.
+///
+/// A failing step does not stop the later ones, so a single run shows every
+/// broken stage. Artifacts are written to a scratch directory that is removed
+/// before returning.
+pub async fn run_self_test(db: &Arc<Database>, screen_recorder: Option<&ScreenRecorder>) -> SelfTestReport {
+    let started = Instant::now();
+    let scratch_dir = std::env::temp_dir().join(format!("zero-self-test-{}", Uuid::new_v4()));
+    let segment_path = scratch_dir.join("segment.mp4");
+
+    let mut steps = vec![
+        run_step("capture", capture_step(screen_recorder)).await,
+        run_step("motion_detection", async { motion_step() }).await,
+        run_step("encode", encode_step(&scratch_dir, &segment_path)).await,
+        run_step("storage", storage_step(db, &scratch_dir, &segment_path)).await,
+        run_step("database", database_step(db)).await,
+    ];
+
+    let cleanup = tokio::fs::remove_dir_all(&scratch_dir).await;
+    if let Err(e) = cleanup {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            steps.push(SelfTestStep {
+                name: "cleanup".to_string(),
+                passed: false,
+                duration_ms: 0,
+                error: Some(e.to_string()),
+            });
+        }
+    }
+
+    SelfTestReport {
+        passed: steps.iter().all(|step| step.passed),
+        steps,
+        total_duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+async fn run_step(name: &str, step: impl Future<Output = StepResult>) -> SelfTestStep {
+    let started = Instant::now();
+    let result = step.await;
+
+    SelfTestStep {
+        name: name.to_string(),
+        passed: result.is_ok(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+/// Capture one real frame from the primary display
+async fn capture_step(screen_recorder: Option<&ScreenRecorder>) -> StepResult {
+    let recorder = screen_recorder.ok_or("Screen recorder not initialized")?;
+
+    let displays = recorder.get_available_displays().await?;
+    let display = displays
+        .iter()
+        .find(|d| d.is_primary)
+        .or_else(|| displays.first())
+        .ok_or("No displays available")?;
+
+    let frame = recorder.capture_frame(display.id).await?;
+    if frame.data.is_empty() {
+        return Err("Captured frame is empty".into());
+    }
+
+    Ok(())
+}
+
+/// Detect motion between two synthetic frames that differ in one quadrant
+fn motion_step() -> StepResult {
+    let mut detector = MotionDetector::new(0.01);
+
+    let first = synthetic_frame(0, 0);
+    let second = synthetic_frame(1, 255);
+
+    detector.detect_motion(&first);
+    let result = detector.detect_motion(&second);
+
+    if !result.has_motion {
+        return Err(format!(
+            "Expected motion between synthetic frames, got {:.2}% changed",
+            result.changed_percentage * 100.0
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Encode a short synthetic segment into the scratch directory
+async fn encode_step(scratch_dir: &Path, segment_path: &Path) -> StepResult {
+    tokio::fs::create_dir_all(scratch_dir).await?;
+
+    let encoder = VideoEncoder::new(VideoCodec::H264, CompressionQuality::Low, false)?;
+    let frames = (0..SYNTHETIC_FRAME_COUNT)
+        .map(|i| synthetic_frame(i as i64 * 100, (i * 50) as u8))
+        .collect();
+
+    let segment = encoder
+        .encode_frames(frames, segment_path.to_path_buf(), 10)
+        .await?;
+
+    if segment.file_size_bytes == 0 {
+        return Err("Encoded segment is empty".into());
+    }
+
+    Ok(())
+}
+
+/// Save the encoded segment through a recording storage and read it back
+/// byte for byte, deleting the scratch session afterwards
+///
+/// The storage lives in the scratch directory and has no quota, so the step
+/// never evicts real sessions or counts towards the real storage usage.
+async fn storage_step(db: &Arc<Database>, scratch_dir: &Path, segment_path: &Path) -> StepResult {
+    let original = tokio::fs::read(segment_path)
+        .await
+        .map_err(|e| format!("Encoded segment unavailable: {}", e))?;
+
+    let storage = RecordingStorage::new(scratch_dir.join("recordings"), db.clone()).await?;
+    let session_id = storage.create_session(0).await?;
+    let result = storage_round_trip(&storage, &session_id, &original).await;
+    storage.delete_session(session_id).await?;
+
+    result
+}
+
+async fn storage_round_trip(storage: &RecordingStorage, session_id: &Uuid, original: &[u8]) -> StepResult {
+    let stored_path = storage.get_segment_path(session_id, 0);
+    tokio::fs::write(&stored_path, original).await?;
+
+    let segment = VideoSegment {
+        path: stored_path,
+        start_timestamp: 0,
+        end_timestamp: (SYNTHETIC_FRAME_COUNT as i64 - 1) * 100,
+        frame_count: SYNTHETIC_FRAME_COUNT as u32,
+        duration_ms: (SYNTHETIC_FRAME_COUNT as u64 - 1) * 100,
+        file_size_bytes: original.len() as u64,
+    };
+    storage.save_segment(session_id, &segment).await?;
+
+    let segments = storage.get_session_segments(*session_id).await?;
+    let stored = segments.first().ok_or("Saved segment was not listed for its session")?;
+    let read_back = tokio::fs::read(&stored.path).await?;
+
+    if read_back != original {
+        return Err(format!(
+            "Read back {} bytes, expected {}",
+            read_back.len(),
+            original.len()
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Round-trip a value through SQLite
+async fn database_step(db: &Database) -> StepResult {
+    let nonce = Uuid::new_v4().to_string();
+    let echoed: String = sqlx::query_scalar("SELECT ?")
+        .bind(&nonce)
+        .fetch_one(db.pool())
+        .await?;
+
+    if echoed != nonce {
+        return Err("Database returned a different value".into());
+    }
+
+    Ok(())
+}
+
+/// Solid gray frame with the top-left quadrant filled with `quadrant_value`
+fn synthetic_frame(timestamp: i64, quadrant_value: u8) -> RawFrame {
+    let mut data = vec![128u8; (SYNTHETIC_WIDTH * SYNTHETIC_HEIGHT * 4) as usize];
+
+    for y in 0..SYNTHETIC_HEIGHT / 2 {
+        for x in 0..SYNTHETIC_WIDTH / 2 {
+            let offset = ((y * SYNTHETIC_WIDTH + x) * 4) as usize;
+            data[offset..offset + 3].fill(quadrant_value);
+            data[offset + 3] = 255;
+        }
+    }
+
+    RawFrame {
+        timestamp,
+        width: SYNTHETIC_WIDTH,
+        height: SYNTHETIC_HEIGHT,
+        data,
+        format: PixelFormat::RGBA8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_self_test_reports_each_step() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        let report = run_self_test(&db, None).await;
+        let step = |name: &str| report.steps.iter().find(|s| s.name == name).unwrap();
+
+        assert!(!step("capture").passed);
+        assert!(step("capture").error.is_some());
+        assert!(step("motion_detection").passed);
+        assert!(step("database").passed);
+        assert!(!report.passed);
+    }
+
+    #[tokio::test]
+    async fn test_storage_step_leaves_real_storage_untouched() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        // A real storage already at its quota, with one finished session
+        let real_dir = std::env::temp_dir().join(format!("zero-self-test-real-{}", Uuid::new_v4()));
+        let real = RecordingStorage::new(real_dir.clone(), db.clone()).await.unwrap();
+        let finished = real.create_session(0).await.unwrap();
+        std::fs::write(real.get_segment_path(&finished, 0), vec![0u8; 100]).unwrap();
+        real.end_session(finished).await.unwrap();
+        real.set_max_storage_bytes(Some(100));
+        assert_eq!(real.get_disk_usage().unwrap(), 100);
+
+        let scratch_dir = std::env::temp_dir().join(format!("zero-self-test-storage-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&scratch_dir).unwrap();
+        let segment_path = scratch_dir.join("segment.mp4");
+        std::fs::write(&segment_path, b"not really an mp4").unwrap();
+
+        storage_step(&db, &scratch_dir, &segment_path).await.unwrap();
+
+        // Only the real session is left, and nothing was evicted to make room
+        let sessions: Vec<String> = sqlx::query_scalar("SELECT id FROM sessions")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(sessions, vec![finished.to_string()]);
+        assert!(real.get_segment_path(&finished, 0).exists());
+        assert_eq!(std::fs::read_dir(scratch_dir.join("recordings")).unwrap().count(), 0);
+        assert_eq!(real.enforce_quota().await.unwrap(), Vec::<Uuid>::new());
+
+        std::fs::remove_dir_all(scratch_dir).ok();
+        std::fs::remove_dir_all(real_dir).ok();
+    }
+}
@@ -1,9 +1,12 @@
+use crate::core::config::Config;
 use crate::core::database::Database;
+use crate::platform::power;
+use chrono::{Local, TimeZone, Timelike};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use uuid::Uuid;
 
 // ==============================================================================
@@ -15,6 +18,24 @@ pub struct SessionConfig {
     pub idle_timeout_minutes: u32,           // Default: 30
     pub minimum_session_duration_minutes: u32, // Default: 5
     pub auto_end_on_sleep: bool,             // Default: true
+    #[serde(default = "default_idle_timeout_by_type")]
+    pub idle_timeout_by_type: HashMap<SessionType, u32>, // Overrides idle_timeout_minutes
+    #[serde(default = "default_metrics_update_interval")]
+    pub metrics_update_interval_secs: u64,   // Default: 5
+    #[serde(default)]
+    pub productivity_model: ProductivityModelKind, // Default: focus_weighted
+}
+
+fn default_metrics_update_interval() -> u64 {
+    5
+}
+
+fn default_idle_timeout_by_type() -> HashMap<SessionType, u32> {
+    let mut timeouts = HashMap::new();
+    timeouts.insert(SessionType::Research, 60);
+    timeouts.insert(SessionType::Entertainment, 60);
+    timeouts.insert(SessionType::Development, 20);
+    timeouts
 }
 
 impl Default for SessionConfig {
@@ -23,10 +44,32 @@ impl Default for SessionConfig {
             idle_timeout_minutes: 30,
             minimum_session_duration_minutes: 5,
             auto_end_on_sleep: true,
+            idle_timeout_by_type: default_idle_timeout_by_type(),
+            metrics_update_interval_secs: default_metrics_update_interval(),
+            productivity_model: ProductivityModelKind::default(),
         }
     }
 }
 
+impl SessionConfig {
+    /// Idle timeout for a session of the given type, falling back to the global timeout
+    pub fn idle_timeout_for(&self, session_type: Option<SessionType>) -> u32 {
+        session_type
+            .and_then(|t| self.idle_timeout_by_type.get(&t).copied())
+            .unwrap_or(self.idle_timeout_minutes)
+    }
+
+    /// Shortest idle timeout that could apply to any session
+    fn min_idle_timeout_minutes(&self) -> u32 {
+        self.idle_timeout_by_type
+            .values()
+            .copied()
+            .chain(std::iter::once(self.idle_timeout_minutes))
+            .min()
+            .unwrap_or(self.idle_timeout_minutes)
+    }
+}
+
 // ==============================================================================
 // Session Types
 // ==============================================================================
@@ -86,6 +129,44 @@ pub struct SessionMetrics {
     pub productivity_score: f32, // 0.0 - 1.0
 }
 
+/// Everything a session detail view needs, in one call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub session: Session,
+    pub metrics: SessionMetrics,
+    pub session_type: SessionType,
+    pub has_screen_recording: bool,
+    pub has_input_recording: bool,
+    pub has_clipboard_events: bool,
+    pub has_ocr_text: bool,
+    pub keyboard_event_count: i64,
+    pub command_count: i64,
+}
+
+/// Live metrics for the current session, broadcast periodically
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMetricsUpdate {
+    pub session_id: String,
+    pub metrics: SessionMetrics,
+}
+
+/// Focus time in one local hour-of-day bucket, split by work hours
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyActivity {
+    pub hour: u32,
+    pub work_hours_ms: u64,
+    pub off_hours_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductivityReport {
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub hourly: Vec<HourlyActivity>,
+    pub work_hours_focus_ms: u64,
+    pub off_hours_focus_ms: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct AppUsageInfo {
     pub app_name: String,
@@ -123,16 +204,100 @@ impl IdleDetector {
 
     #[cfg(target_os = "macos")]
     fn get_idle_time_macos() -> Result<Duration, Box<dyn std::error::Error + Send + Sync>> {
-        // For now, return a mock value
-        // TODO: Implement using IOKit's kIOHIDIdleTimeKey
-        // This requires Core Foundation bindings
-        Ok(Duration::from_secs(0))
+        use std::ffi::{c_char, c_void};
+
+        #[link(name = "IOKit", kind = "framework")]
+        extern "C" {
+            fn IOServiceMatching(name: *const c_char) -> *mut c_void;
+            fn IOServiceGetMatchingService(main_port: u32, matching: *mut c_void) -> u32;
+            fn IORegistryEntryCreateCFProperty(
+                entry: u32,
+                key: *const c_void,
+                allocator: *const c_void,
+                options: u32,
+            ) -> *const c_void;
+            fn IOObjectRelease(object: u32) -> i32;
+        }
+
+        #[link(name = "CoreFoundation", kind = "framework")]
+        extern "C" {
+            fn CFStringCreateWithCString(
+                allocator: *const c_void,
+                c_str: *const c_char,
+                encoding: u32,
+            ) -> *const c_void;
+            fn CFGetTypeID(cf: *const c_void) -> usize;
+            fn CFNumberGetTypeID() -> usize;
+            fn CFNumberGetValue(number: *const c_void, number_type: i64, value: *mut c_void) -> u8;
+            fn CFRelease(cf: *const c_void);
+        }
+
+        const IO_MAIN_PORT_DEFAULT: u32 = 0;
+        const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+        const CF_NUMBER_SINT64_TYPE: i64 = 4;
+
+        unsafe {
+            // IOServiceGetMatchingService consumes the matching dictionary
+            let matching = IOServiceMatching(c"IOHIDSystem".as_ptr());
+            if matching.is_null() {
+                return Err("Failed to create IOHIDSystem matching dictionary".into());
+            }
+
+            let service = IOServiceGetMatchingService(IO_MAIN_PORT_DEFAULT, matching);
+            if service == 0 {
+                return Err("IOHIDSystem service not found".into());
+            }
+
+            let key = CFStringCreateWithCString(
+                std::ptr::null(),
+                c"HIDIdleTime".as_ptr(),
+                CF_STRING_ENCODING_UTF8,
+            );
+            let property = IORegistryEntryCreateCFProperty(service, key, std::ptr::null(), 0);
+            CFRelease(key);
+            IOObjectRelease(service);
+
+            if property.is_null() {
+                return Err("HIDIdleTime property not available".into());
+            }
+
+            let mut idle_ns: i64 = 0;
+            let ok = CFGetTypeID(property) == CFNumberGetTypeID()
+                && CFNumberGetValue(
+                    property,
+                    CF_NUMBER_SINT64_TYPE,
+                    &mut idle_ns as *mut i64 as *mut c_void,
+                ) != 0;
+            CFRelease(property);
+
+            if !ok {
+                return Err("HIDIdleTime is not a number".into());
+            }
+
+            Ok(hid_idle_time(idle_ns))
+        }
     }
 
     #[cfg(target_os = "windows")]
     fn get_idle_time_windows() -> Result<Duration, Box<dyn std::error::Error + Send + Sync>> {
-        // TODO: Implement using GetLastInputInfo
-        Ok(Duration::from_secs(0))
+        use windows::Win32::System::SystemInformation::GetTickCount;
+        use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+
+        unsafe {
+            if !GetLastInputInfo(&mut info).as_bool() {
+                return Err("GetLastInputInfo failed".into());
+            }
+
+            // Both counters wrap after ~49.7 days; a negative delta across
+            // the wrap is clamped to zero rather than read as a huge idle time
+            let elapsed_ms = (GetTickCount() as i64 - info.dwTime as i64).max(0);
+            Ok(Duration::from_millis(elapsed_ms as u64))
+        }
     }
 
     #[cfg(target_os = "linux")]
@@ -142,6 +307,12 @@ impl IdleDetector {
     }
 }
 
+/// Convert IOHIDSystem's HIDIdleTime, in nanoseconds, to an idle duration
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn hid_idle_time(idle_ns: i64) -> Duration {
+    Duration::from_nanos(idle_ns.max(0) as u64)
+}
+
 // ==============================================================================
 // Power Event Monitoring
 // ==============================================================================
@@ -155,20 +326,38 @@ pub enum PowerEvent {
 
 pub struct PowerEventMonitor {
     event_tx: mpsc::Sender<PowerEvent>,
+    started: std::sync::atomic::AtomicBool,
 }
 
 impl PowerEventMonitor {
     pub fn new() -> (Self, mpsc::Receiver<PowerEvent>) {
         let (tx, rx) = mpsc::channel(10);
-        (Self { event_tx: tx }, rx)
+        (Self { event_tx: tx, started: std::sync::atomic::AtomicBool::new(false) }, rx)
     }
 
+    /// Forward the platform's sleep and wake notifications; later calls do nothing
     pub async fn start_monitoring(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // TODO: Platform-specific implementation
-        // macOS: IORegisterForSystemPower
-        // Windows: RegisterPowerSettingNotification
-        // Linux: D-Bus org.freedesktop.login1.Manager PrepareForSleep
-        Ok(())
+        if self.started.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let event_tx = self.event_tx.clone();
+        power::watch_sleep_wake(move |event| {
+            let event = match event {
+                power::PowerEvent::Sleep => PowerEvent::WillSleep,
+                power::PowerEvent::Wake => PowerEvent::DidWake,
+                power::PowerEvent::BatteryLow => PowerEvent::BatteryLow,
+                power::PowerEvent::Charging => return,
+            };
+            // Called from an OS thread, so never block it
+            let _ = event_tx.try_send(event);
+        })
+    }
+
+    /// Manually deliver a power event (for testing)
+    #[cfg(test)]
+    pub fn trigger_event(&self, event: PowerEvent) {
+        let _ = self.event_tx.try_send(event);
     }
 }
 
@@ -176,7 +365,20 @@ impl PowerEventMonitor {
 // Session Classification
 // ==============================================================================
 
-fn categorize_app(app_name: &str) -> SessionType {
+/// Keyboard and mouse events per minute over [start, end], normalized to 0.0-1.0
+///
+/// Callers leave out mouse moves, which are sampled continuously while the
+/// pointer moves and would swamp deliberate input. Anything shorter than a
+/// minute is measured as a full minute.
+pub fn input_intensity(events: i64, start: i64, end: i64) -> f32 {
+    let minutes = ((end - start) as f64 / 60_000.0).max(1.0);
+    let per_minute = events as f64 / minutes;
+
+    (per_minute / FULL_INTENSITY_EVENTS_PER_MINUTE).min(1.0) as f32
+}
+
+/// Map an app name to the kind of activity it usually means, by keyword
+pub fn categorize_app(app_name: &str) -> SessionType {
     let name_lower = app_name.to_lowercase();
 
     if name_lower.contains("code")
@@ -227,26 +429,200 @@ fn categorize_app(app_name: &str) -> SessionType {
     SessionType::Unknown
 }
 
-fn calculate_productivity_score(apps: &[AppUsageInfo]) -> f32 {
-    if apps.is_empty() {
-        return 0.0;
+// ==============================================================================
+// Productivity Scoring
+// ==============================================================================
+
+/// A definition of productivity, scoring a session's app usage from 0.0 to 1.0
+pub trait ProductivityModel: Send + Sync {
+    fn score(&self, apps: &[AppUsageInfo]) -> f32;
+}
+
+/// Rewards long stretches in few apps, penalizing context switches
+pub struct FocusWeighted;
+
+impl ProductivityModel for FocusWeighted {
+    fn score(&self, apps: &[AppUsageInfo]) -> f32 {
+        if apps.is_empty() {
+            return 0.0;
+        }
+
+        let total_focus: i64 = apps.iter().map(|a| a.focus_duration_ms).sum();
+        if total_focus == 0 {
+            return 0.0;
+        }
+
+        // Average focus time per app (higher is better - means more sustained focus)
+        let avg_focus_per_app = total_focus as f32 / apps.len() as f32;
+
+        // Penalty for too many app switches (context switching is bad for productivity)
+        let switch_penalty = 1.0 / (1.0 + apps.len() as f32 * 0.1);
+
+        // Normalize average focus time to minutes, cap at 1.0 for 60+ minutes per app
+        let focus_score = (avg_focus_per_app / 60000.0).min(1.0);
+
+        focus_score * switch_penalty
+    }
+}
+
+/// Share of focus time spent in development and work apps
+///
+/// Entertainment counts for nothing, and everything else (communication,
+/// research, unrecognized apps) counts for half.
+pub struct CategoryWeighted;
+
+impl ProductivityModel for CategoryWeighted {
+    fn score(&self, apps: &[AppUsageInfo]) -> f32 {
+        let total_focus: i64 = apps.iter().map(|a| a.focus_duration_ms).sum();
+        if total_focus <= 0 {
+            return 0.0;
+        }
+
+        let weighted: f32 = apps
+            .iter()
+            .map(|app| {
+                let weight = match categorize_app(&app.app_name) {
+                    SessionType::Development | SessionType::Work => 1.0,
+                    SessionType::Entertainment => 0.0,
+                    _ => 0.5,
+                };
+                app.focus_duration_ms as f32 * weight
+            })
+            .sum();
+
+        (weighted / total_focus as f32).clamp(0.0, 1.0)
+    }
+}
+
+/// Which productivity model scores sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductivityModelKind {
+    #[default]
+    FocusWeighted,
+    CategoryWeighted,
+}
+
+impl ProductivityModelKind {
+    pub fn model(self) -> &'static dyn ProductivityModel {
+        match self {
+            ProductivityModelKind::FocusWeighted => &FocusWeighted,
+            ProductivityModelKind::CategoryWeighted => &CategoryWeighted,
+        }
     }
+}
+
+// ==============================================================================
+// Live Metrics Accumulation
+// ==============================================================================
+
+/// Tables holding per-session rows, with the column that places each row in time
+pub(crate) const SESSION_CHILD_TABLES: &[(&str, &str)] = &[
+    ("app_usage", "start_timestamp"),
+    ("app_focus_spans", "start_timestamp"),
+    ("keyboard_events", "timestamp"),
+    ("mouse_events", "timestamp"),
+    ("clipboard_events", "timestamp"),
+    ("commands", "timestamp"),
+    ("ocr_results", "timestamp"),
+    ("frames", "timestamp"),
+    ("video_segments", "start_timestamp"),
+    ("screen_recordings", "start_timestamp"),
+];
+
+/// Gap between input events after which the user is treated as away
+const INPUT_IDLE_GAP_MS: i64 = 60_000;
+
+/// Keystrokes plus clicks, drags and scrolls per minute that count as full activity intensity
+const FULL_INTENSITY_EVENTS_PER_MINUTE: f64 = 120.0;
+
+/// Activity reported by the recorders for the current session
+#[derive(Debug, Clone)]
+pub enum ActivitySignal {
+    FocusChange { app_name: String, timestamp: i64 },
+    Input { timestamp: i64 },
+}
 
-    let total_focus: i64 = apps.iter().map(|a| a.focus_duration_ms).sum();
-    if total_focus == 0 {
-        return 0.0;
+/// Running metrics for the current session so reads don't re-query app_usage
+#[derive(Debug, Clone)]
+struct MetricsAccumulator {
+    session_id: String,
+    start_timestamp: i64,
+    focus_by_app: HashMap<String, i64>,
+    current_app: Option<(String, i64)>, // (app name, focus start)
+    app_switches: u32,
+    last_input_timestamp: Option<i64>,
+    away_duration_ms: i64,
+}
+
+impl MetricsAccumulator {
+    fn new(session_id: String, start_timestamp: i64) -> Self {
+        Self {
+            session_id,
+            start_timestamp,
+            focus_by_app: HashMap::new(),
+            current_app: None,
+            app_switches: 0,
+            last_input_timestamp: None,
+            away_duration_ms: 0,
+        }
     }
 
-    // Average focus time per app (higher is better - means more sustained focus)
-    let avg_focus_per_app = total_focus as f32 / apps.len() as f32;
+    fn record_focus_change(&mut self, app_name: String, timestamp: i64) {
+        if let Some((previous, since)) = self.current_app.take() {
+            if previous == app_name {
+                self.current_app = Some((previous, since));
+                return;
+            }
+            *self.focus_by_app.entry(previous).or_insert(0) += (timestamp - since).max(0);
+            self.app_switches += 1;
+        }
 
-    // Penalty for too many app switches (context switching is bad for productivity)
-    let switch_penalty = 1.0 / (1.0 + apps.len() as f32 * 0.1);
+        self.focus_by_app.entry(app_name.clone()).or_insert(0);
+        self.current_app = Some((app_name, timestamp));
+    }
 
-    // Normalize average focus time to minutes, cap at 1.0 for 60+ minutes per app
-    let focus_score = (avg_focus_per_app / 60000.0).min(1.0);
+    fn record_input(&mut self, timestamp: i64) {
+        if let Some(last) = self.last_input_timestamp {
+            let gap = timestamp - last;
+            if gap > INPUT_IDLE_GAP_MS {
+                self.away_duration_ms += gap;
+            }
+        }
+        self.last_input_timestamp = Some(timestamp);
+    }
+
+    fn snapshot(&self, now: i64, model: &dyn ProductivityModel) -> SessionMetrics {
+        let mut focus = self.focus_by_app.clone();
+        if let Some((app, since)) = &self.current_app {
+            *focus.entry(app.clone()).or_insert(0) += (now - since).max(0);
+        }
+
+        let total_duration = (now - self.start_timestamp).max(0);
+        let focus_total: i64 = focus.values().sum();
+        let active_duration = (focus_total - self.away_duration_ms).clamp(0, total_duration);
+
+        let apps: Vec<AppUsageInfo> = focus
+            .into_iter()
+            .map(|(app_name, focus_duration_ms)| AppUsageInfo { app_name, focus_duration_ms })
+            .collect();
+
+        let most_used_app = apps
+            .iter()
+            .max_by_key(|a| a.focus_duration_ms)
+            .map(|a| a.app_name.clone())
+            .unwrap_or_else(|| "None".to_string());
 
-    focus_score * switch_penalty
+        SessionMetrics {
+            total_duration_ms: total_duration as u64,
+            active_duration_ms: active_duration as u64,
+            idle_duration_ms: (total_duration - active_duration) as u64,
+            app_switches: self.app_switches,
+            unique_apps: apps.len() as u32,
+            most_used_app,
+            productivity_score: model.score(&apps),
+        }
+    }
 }
 
 // ==============================================================================
@@ -258,21 +634,115 @@ pub struct SessionManager {
     current_session_id: Arc<RwLock<Option<String>>>,
     config: SessionConfig,
     monitoring: Arc<RwLock<bool>>,
+    metrics_tx: broadcast::Sender<SessionMetricsUpdate>,
+    accumulator: Arc<RwLock<Option<MetricsAccumulator>>>,
+    activity_tx: mpsc::UnboundedSender<ActivitySignal>,
+    /// Stable id of this machine, stamped on every new session
+    device_id: String,
+    power_monitor: PowerEventMonitor,
+    /// Held by the running monitor loop
+    power_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<PowerEvent>>>,
 }
 
 impl SessionManager {
     pub async fn new(
         db: Arc<Database>,
         config: SessionConfig,
+        device_id: String,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let current_session_id = Arc::new(RwLock::new(None));
+        let accumulator = Arc::new(RwLock::new(None));
+
+        let (power_monitor, power_rx) = PowerEventMonitor::new();
+
+        // Fold activity from the recorders into the running metrics
+        let (activity_tx, activity_rx) = mpsc::unbounded_channel();
+        tokio::spawn(Self::accumulate_activity(
+            db.clone(),
+            current_session_id.clone(),
+            accumulator.clone(),
+            activity_rx,
+        ));
+
         Ok(Self {
             db,
-            current_session_id: Arc::new(RwLock::new(None)),
+            current_session_id,
             config,
             monitoring: Arc::new(RwLock::new(false)),
+            metrics_tx: broadcast::channel(16).0,
+            accumulator,
+            activity_tx,
+            device_id,
+            power_monitor,
+            power_rx: Arc::new(tokio::sync::Mutex::new(power_rx)),
         })
     }
 
+    /// Sender the recorders use to report focus changes and input activity
+    pub fn activity_sender(&self) -> mpsc::UnboundedSender<ActivitySignal> {
+        self.activity_tx.clone()
+    }
+
+    async fn accumulate_activity(
+        db: Arc<Database>,
+        current_session_id: Arc<RwLock<Option<String>>>,
+        accumulator: Arc<RwLock<Option<MetricsAccumulator>>>,
+        mut activity_rx: mpsc::UnboundedReceiver<ActivitySignal>,
+    ) {
+        while let Some(signal) = activity_rx.recv().await {
+            let Some(session_id) = current_session_id.read().await.clone() else {
+                continue;
+            };
+
+            let mut acc = accumulator.write().await;
+
+            // Start a fresh accumulator when the session changes
+            if acc.as_ref().map(|a| a.session_id != session_id).unwrap_or(true) {
+                let start_timestamp = match db.get_session(&session_id).await {
+                    Ok(session) => session.start_timestamp,
+                    Err(e) => {
+                        eprintln!("Failed to load session for metrics: {}", e);
+                        continue;
+                    }
+                };
+                *acc = Some(MetricsAccumulator::new(session_id, start_timestamp));
+            }
+
+            if let Some(acc) = acc.as_mut() {
+                match signal {
+                    ActivitySignal::FocusChange { app_name, timestamp } => {
+                        acc.record_focus_change(app_name, timestamp);
+                    }
+                    ActivitySignal::Input { timestamp } => acc.record_input(timestamp),
+                }
+            }
+        }
+    }
+
+    /// Metrics from the accumulator when it tracks this session, otherwise a full recompute
+    async fn metrics_for_session(
+        db: &Arc<Database>,
+        current_session_id: &Arc<RwLock<Option<String>>>,
+        accumulator: &Arc<RwLock<Option<MetricsAccumulator>>>,
+        session_id: &str,
+        model: &dyn ProductivityModel,
+    ) -> Result<SessionMetrics, Box<dyn std::error::Error + Send + Sync>> {
+        let is_current = current_session_id.read().await.as_deref() == Some(session_id);
+
+        if let Some(acc) = accumulator.read().await.as_ref() {
+            if is_current && acc.session_id == session_id {
+                return Ok(acc.snapshot(chrono::Utc::now().timestamp_millis(), model));
+            }
+        }
+
+        Self::calculate_metrics_internal(db, session_id, model).await
+    }
+
+    /// Subscribe to live metrics updates for the current session
+    pub fn subscribe_metrics(&self) -> broadcast::Receiver<SessionMetricsUpdate> {
+        self.metrics_tx.subscribe()
+    }
+
     pub async fn start_monitoring(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let mut monitoring = self.monitoring.write().await;
         if *monitoring {
@@ -281,58 +751,141 @@ impl SessionManager {
 
         *monitoring = true;
 
+        if let Err(e) = self.power_monitor.start_monitoring().await {
+            eprintln!("Sleep detection unavailable: {}", e);
+        }
+
         // Spawn background task to monitor for session start/end
         let db = self.db.clone();
         let current_session_id = self.current_session_id.clone();
         let config = self.config.clone();
         let monitoring_flag = self.monitoring.clone();
+        let device_id = self.device_id.clone();
+        let power_rx = self.power_rx.clone();
+
+        tokio::spawn(async move {
+            Self::monitor_loop(db, current_session_id, config, monitoring_flag, device_id, power_rx).await;
+        });
+
+        // Spawn background task to publish live metrics
+        let db = self.db.clone();
+        let current_session_id = self.current_session_id.clone();
+        let interval = Duration::from_secs(self.config.metrics_update_interval_secs.max(1));
+        let monitoring_flag = self.monitoring.clone();
+        let metrics_tx = self.metrics_tx.clone();
+        let accumulator = self.accumulator.clone();
+        let model = self.config.productivity_model.model();
 
         tokio::spawn(async move {
-            Self::monitor_loop(db, current_session_id, config, monitoring_flag).await;
+            Self::metrics_loop(db, current_session_id, accumulator, interval, monitoring_flag, metrics_tx, model).await;
         });
 
         Ok(())
     }
 
+    async fn metrics_loop(
+        db: Arc<Database>,
+        current_session_id: Arc<RwLock<Option<String>>>,
+        accumulator: Arc<RwLock<Option<MetricsAccumulator>>>,
+        interval: Duration,
+        monitoring: Arc<RwLock<bool>>,
+        metrics_tx: broadcast::Sender<SessionMetricsUpdate>,
+        model: &'static dyn ProductivityModel,
+    ) {
+        loop {
+            if !*monitoring.read().await {
+                break;
+            }
+
+            // Only publish while a session is active
+            let session_id = current_session_id.read().await.clone();
+            if let Some(session_id) = session_id {
+                if metrics_tx.receiver_count() > 0 {
+                    match Self::metrics_for_session(&db, &current_session_id, &accumulator, &session_id, model).await {
+                        Ok(metrics) => {
+                            let _ = metrics_tx.send(SessionMetricsUpdate { session_id, metrics });
+                        }
+                        Err(e) => eprintln!("Failed to calculate live session metrics: {}", e),
+                    }
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+
     async fn monitor_loop(
         db: Arc<Database>,
         current_session_id: Arc<RwLock<Option<String>>>,
         config: SessionConfig,
         monitoring: Arc<RwLock<bool>>,
+        device_id: String,
+        power_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<PowerEvent>>>,
     ) {
+        let mut power_rx = power_rx.lock().await;
+        let mut asleep = false;
+
         loop {
             // Check if still monitoring
             if !*monitoring.read().await {
                 break;
             }
 
-            // Check idle time
-            if let Ok(idle_time) = IdleDetector::get_idle_time().await {
+            // Check idle time; nothing new starts between sleep and wake
+            if asleep {
+                // Wait for DidWake below
+            } else if let Ok(idle_time) = IdleDetector::get_idle_time().await {
                 let current_session = current_session_id.read().await.clone();
 
                 if idle_time.as_secs() < 60 {
                     // User is active
                     if current_session.is_none() {
                         // Start new session
-                        if let Ok(session_id) = Self::create_session_internal(&db).await {
+                        if let Ok(session_id) = Self::create_session_internal(&db, &device_id).await {
                             *current_session_id.write().await = Some(session_id);
                             println!("Started new session");
                         }
                     }
-                } else if idle_time.as_secs() > config.idle_timeout_minutes as u64 * 60 {
-                    // User is idle beyond threshold
+                } else if idle_time.as_secs() > config.min_idle_timeout_minutes() as u64 * 60 {
+                    // User may be idle beyond the threshold for this kind of session
                     if let Some(session_id) = current_session {
-                        // End current session
-                        if let Ok(_) = Self::end_session_internal(&db, &session_id).await {
-                            *current_session_id.write().await = None;
-                            println!("Ended session due to idle timeout");
+                        let session_type = Self::classify_session_internal(&db, &session_id)
+                            .await
+                            .ok()
+                            .filter(|t| *t != SessionType::Unknown);
+                        let timeout_minutes = config.idle_timeout_for(session_type);
+
+                        if idle_time.as_secs() > timeout_minutes as u64 * 60 {
+                            // End current session
+                            if let Ok(_) = Self::end_session_internal(&db, &session_id).await {
+                                *current_session_id.write().await = None;
+                                println!("Ended session due to idle timeout");
+                            }
                         }
                     }
                 }
             }
 
-            // Sleep for 30 seconds before checking again
-            tokio::time::sleep(Duration::from_secs(30)).await;
+            // Sleep for 30 seconds before checking again, unless power state changes
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(30)) => {}
+                Some(event) = power_rx.recv() => match event {
+                    PowerEvent::WillSleep => {
+                        asleep = true;
+                        if config.auto_end_on_sleep {
+                            let session_id = current_session_id.read().await.clone();
+                            if let Some(session_id) = session_id {
+                                if Self::end_session_internal(&db, &session_id).await.is_ok() {
+                                    *current_session_id.write().await = None;
+                                    println!("Ended session before system sleep");
+                                }
+                            }
+                        }
+                    }
+                    PowerEvent::DidWake => asleep = false,
+                    PowerEvent::BatteryLow => {}
+                },
+            }
         }
     }
 
@@ -341,6 +894,12 @@ impl SessionManager {
         Ok(())
     }
 
+    /// The authoritative session id for anything being recorded right now
+    ///
+    /// Every recorder (screen, OS activity, keyboard, input, clipboard) should
+    /// store its data under this id so cross-modal queries join on one session.
+    /// Recorders must not mint their own ids: ask here first, then pass the id
+    /// to the recorder's start method.
     pub async fn get_or_create_session(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let current = self.current_session_id.read().await;
         if let Some(session_id) = current.as_ref() {
@@ -349,18 +908,17 @@ impl SessionManager {
         drop(current);
 
         // Create new session
-        let session_id = Self::create_session_internal(&self.db).await?;
+        let session_id = Self::create_session_internal(&self.db, &self.device_id).await?;
         *self.current_session_id.write().await = Some(session_id.clone());
 
         Ok(session_id)
     }
 
-    async fn create_session_internal(db: &Arc<Database>) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    async fn create_session_internal(db: &Arc<Database>, device_id: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let session_id = Uuid::new_v4().to_string();
         let start_timestamp = chrono::Utc::now().timestamp_millis();
-        let device_id = Self::get_device_id();
 
-        db.create_session(&session_id, start_timestamp, &device_id).await?;
+        db.create_session(&session_id, start_timestamp, device_id).await?;
 
         Ok(session_id)
     }
@@ -377,6 +935,11 @@ impl SessionManager {
     async fn end_session_internal(db: &Arc<Database>, session_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let end_timestamp = chrono::Utc::now().timestamp_millis();
         db.end_session(session_id, end_timestamp).await?;
+
+        // Classify once at the end so reports can read the stored value
+        let session_type = Self::classify_session_internal(db, session_id).await?;
+        db.set_session_type(session_id, &session_type.to_string()).await?;
+
         Ok(())
     }
 
@@ -395,7 +958,7 @@ impl SessionManager {
             id: session.id,
             start_timestamp: session.start_timestamp,
             end_timestamp: session.end_timestamp,
-            session_type: None, // Will be calculated on demand
+            session_type: session.session_type,
             device_id: session.device_id,
         })
     }
@@ -406,24 +969,44 @@ impl SessionManager {
         end: i64,
     ) -> Result<Vec<Session>, Box<dyn std::error::Error + Send + Sync>> {
         let db_sessions = self.db.get_sessions_in_range(start, end).await?;
+        Ok(db_sessions.into_iter().map(Self::to_session).collect())
+    }
 
-        let sessions = db_sessions
-            .into_iter()
-            .map(|s| Session {
-                id: s.id,
-                start_timestamp: s.start_timestamp,
-                end_timestamp: s.end_timestamp,
-                session_type: None,
-                device_id: s.device_id,
-            })
-            .collect();
+    pub async fn get_sessions_in_range_page(
+        &self,
+        start: i64,
+        end: i64,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<Session>, Box<dyn std::error::Error + Send + Sync>> {
+        let db_sessions = self.db.get_sessions_in_range_page(start, end, limit, offset).await?;
+        Ok(db_sessions.into_iter().map(Self::to_session).collect())
+    }
 
-        Ok(sessions)
+    fn to_session(s: crate::core::database::Session) -> Session {
+        Session {
+            id: s.id,
+            start_timestamp: s.start_timestamp,
+            end_timestamp: s.end_timestamp,
+            session_type: s.session_type,
+            device_id: s.device_id,
+        }
     }
 
     pub async fn classify_session_type(&self, session_id: &str) -> Result<SessionType, Box<dyn std::error::Error + Send + Sync>> {
+        Self::classify_session_internal(&self.db, session_id).await
+    }
+
+    /// Classify a session and store the result
+    pub async fn classify_and_store_session_type(&self, session_id: &str) -> Result<SessionType, Box<dyn std::error::Error + Send + Sync>> {
+        let session_type = self.classify_session_type(session_id).await?;
+        self.db.set_session_type(session_id, &session_type.to_string()).await?;
+        Ok(session_type)
+    }
+
+    async fn classify_session_internal(db: &Arc<Database>, session_id: &str) -> Result<SessionType, Box<dyn std::error::Error + Send + Sync>> {
         // Get app usage for this session from the app_usage table
-        let apps = self.get_app_usage_for_session(session_id).await?;
+        let apps = Self::get_app_usage_internal(db, session_id).await?;
 
         if apps.is_empty() {
             return Ok(SessionType::Unknown);
@@ -448,9 +1031,206 @@ impl SessionManager {
         Ok(session_type)
     }
 
+    /// Recompute and store the session type for every session in a range
+    ///
+    /// `on_progress` is called with (completed, total) after each session.
+    pub async fn reclassify_sessions_in_range<F>(
+        &self,
+        start: i64,
+        end: i64,
+        on_progress: F,
+    ) -> Result<u32, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: Fn(u32, u32),
+    {
+        let sessions = self.db.get_sessions_in_range(start, end).await?;
+        let total = sessions.len() as u32;
+
+        for (index, session) in sessions.iter().enumerate() {
+            self.classify_and_store_session_type(&session.id).await?;
+
+            on_progress(index as u32 + 1, total);
+        }
+
+        Ok(total)
+    }
+
+    /// Fold several sessions into one
+    ///
+    /// The active session survives if it is among `ids`, so recorders keep
+    /// writing to a live id; otherwise the earliest one does. Every child row is
+    /// moved to the survivor, which then spans from the first start to the last
+    /// end (or stays open if any merged session was). Recording files stay where
+    /// they are; only their rows move.
+    pub async fn merge_sessions(&self, mut ids: Vec<String>) -> Result<Session, Box<dyn std::error::Error + Send + Sync>> {
+        ids.sort();
+        ids.dedup();
+
+        let mut sessions = Vec::new();
+        for id in &ids {
+            sessions.push(self.db.get_session(id).await?);
+        }
+
+        if sessions.len() < 2 {
+            return Err("At least two distinct sessions are needed to merge".into());
+        }
+
+        let current = self.current_session_id.read().await.clone();
+        let target = sessions
+            .iter()
+            .find(|s| current.as_deref() == Some(s.id.as_str()))
+            .or_else(|| sessions.iter().min_by_key(|s| s.start_timestamp))
+            .map(|s| s.id.clone())
+            .ok_or("No sessions to merge")?;
+
+        let start_timestamp = sessions.iter().map(|s| s.start_timestamp).min().unwrap_or_default();
+        let end_timestamp = if sessions.iter().any(|s| s.end_timestamp.is_none()) {
+            None
+        } else {
+            sessions.iter().filter_map(|s| s.end_timestamp).max()
+        };
+
+        let mut tx = self.db.pool().begin().await?;
+
+        for source in sessions.iter().filter(|s| s.id != target) {
+            for (table, _) in SESSION_CHILD_TABLES {
+                sqlx::query(&format!("UPDATE {} SET session_id = ? WHERE session_id = ?", table))
+                    .bind(&target)
+                    .bind(&source.id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            sqlx::query(
+                "UPDATE sessions SET
+                    frame_count = COALESCE(frame_count, 0) + (SELECT COALESCE(frame_count, 0) FROM sessions WHERE id = ?),
+                    segment_count = COALESCE(segment_count, 0) + (SELECT COALESCE(segment_count, 0) FROM sessions WHERE id = ?),
+                    total_size_bytes = COALESCE(total_size_bytes, 0) + (SELECT COALESCE(total_size_bytes, 0) FROM sessions WHERE id = ?)
+                 WHERE id = ?"
+            )
+            .bind(&source.id)
+            .bind(&source.id)
+            .bind(&source.id)
+            .bind(&target)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("DELETE FROM sessions WHERE id = ?")
+                .bind(&source.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("UPDATE sessions SET start_timestamp = ?, end_timestamp = ? WHERE id = ?")
+            .bind(start_timestamp)
+            .bind(end_timestamp)
+            .bind(&target)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        if end_timestamp.is_some() {
+            self.classify_and_store_session_type(&target).await?;
+        }
+
+        self.get_session_by_id(&target).await
+    }
+
+    /// Cut an ended session in two at `at_timestamp`
+    ///
+    /// Child rows placed at or after the split move to a new session that runs
+    /// to the original end, and the original now ends at the split point. Rows
+    /// spanning the split (app usage, video segments) go with their start.
+    pub async fn split_session(&self, id: &str, at_timestamp: i64) -> Result<Session, Box<dyn std::error::Error + Send + Sync>> {
+        let session = self.db.get_session(id).await?;
+        let end_timestamp = session
+            .end_timestamp
+            .ok_or("Cannot split a session that is still in progress")?;
+
+        if at_timestamp <= session.start_timestamp || at_timestamp >= end_timestamp {
+            return Err(format!(
+                "Split point {} is outside the session ({} - {})",
+                at_timestamp, session.start_timestamp, end_timestamp
+            )
+            .into());
+        }
+
+        let new_id = Uuid::new_v4().to_string();
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            "INSERT INTO sessions (id, start_timestamp, end_timestamp, device_id, created_at)
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(&new_id)
+        .bind(at_timestamp)
+        .bind(end_timestamp)
+        .bind(&session.device_id)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await?;
+
+        for (table, time_column) in SESSION_CHILD_TABLES {
+            sqlx::query(&format!(
+                "UPDATE {} SET session_id = ? WHERE session_id = ? AND {} >= ?",
+                table, time_column
+            ))
+            .bind(&new_id)
+            .bind(id)
+            .bind(at_timestamp)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("UPDATE sessions SET end_timestamp = ? WHERE id = ?")
+            .bind(at_timestamp)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        self.classify_and_store_session_type(id).await?;
+        self.classify_and_store_session_type(&new_id).await?;
+
+        self.get_session_by_id(&new_id).await
+    }
+
     pub async fn calculate_session_metrics(&self, session_id: &str) -> Result<SessionMetrics, Box<dyn std::error::Error + Send + Sync>> {
+        let model = self.config.productivity_model.model();
+        Self::metrics_for_session(&self.db, &self.current_session_id, &self.accumulator, session_id, model).await
+    }
+
+    /// Metrics, classification and recorded-data counts for one session
+    ///
+    /// Uses the stored classification; sessions that haven't been classified
+    /// yet (such as the one still recording) are classified without storing.
+    pub async fn get_session_summary(&self, session_id: &str) -> Result<SessionSummary, Box<dyn std::error::Error + Send + Sync>> {
         let session = self.get_session_by_id(session_id).await?;
-        let apps = self.get_app_usage_for_session(session_id).await?;
+        let metrics = self.calculate_session_metrics(session_id).await?;
+        let session_type = match session.session_type.as_deref() {
+            Some(stored) => SessionType::from_string(stored),
+            None => self.classify_session_type(session_id).await?,
+        };
+        let coverage = self.db.get_session_coverage(session_id).await?;
+
+        Ok(SessionSummary {
+            session,
+            metrics,
+            session_type,
+            has_screen_recording: coverage.screen_segments > 0,
+            has_input_recording: coverage.keyboard_events > 0 || coverage.mouse_events > 0,
+            has_clipboard_events: coverage.clipboard_events > 0,
+            has_ocr_text: coverage.ocr_results > 0,
+            keyboard_event_count: coverage.keyboard_events,
+            command_count: coverage.commands,
+        })
+    }
+
+    async fn calculate_metrics_internal(db: &Arc<Database>, session_id: &str, model: &dyn ProductivityModel) -> Result<SessionMetrics, Box<dyn std::error::Error + Send + Sync>> {
+        let session = db.get_session(session_id).await?;
+        let apps = Self::get_app_usage_internal(db, session_id).await?;
 
         let total_duration = if let Some(end) = session.end_timestamp {
             end - session.start_timestamp
@@ -476,7 +1256,7 @@ impl SessionManager {
             .map(|a| a.app_name.clone())
             .unwrap_or_else(|| "None".to_string());
 
-        let productivity_score = calculate_productivity_score(&apps);
+        let productivity_score = model.score(&apps);
 
         Ok(SessionMetrics {
             total_duration_ms: total_duration as u64,
@@ -489,7 +1269,69 @@ impl SessionManager {
         })
     }
 
-    async fn get_app_usage_for_session(&self, session_id: &str) -> Result<Vec<AppUsageInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    /// Focus time between `start` and `end`, bucketed by local hour and split by work hours
+    pub async fn get_productivity_report(
+        &self,
+        start: i64,
+        end: i64,
+        config: &Config,
+    ) -> Result<ProductivityReport, Box<dyn std::error::Error + Send + Sync>> {
+        #[derive(sqlx::FromRow)]
+        struct FocusSpanRow {
+            start_timestamp: i64,
+            end_timestamp: i64,
+        }
+
+        let spans = sqlx::query_as::<_, FocusSpanRow>(
+            "SELECT start_timestamp, end_timestamp
+             FROM app_focus_spans
+             WHERE end_timestamp > ? AND start_timestamp < ?
+             ORDER BY start_timestamp"
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut hourly: Vec<HourlyActivity> = (0..24)
+            .map(|hour| HourlyActivity { hour, work_hours_ms: 0, off_hours_ms: 0 })
+            .collect();
+
+        for span in spans {
+            let mut t = span.start_timestamp.max(start);
+            let span_end = span.end_timestamp.min(end);
+
+            // Walk the span one local hour at a time
+            while t < span_end {
+                let Some(local) = Local.timestamp_millis_opt(t).single() else {
+                    break;
+                };
+                let ms_into_hour = (local.minute() * 60 + local.second()) as i64 * 1000
+                    + local.timestamp_subsec_millis() as i64;
+                let chunk_end = (t - ms_into_hour + 3_600_000).min(span_end);
+                let chunk = (chunk_end - t) as u64;
+
+                let bucket = &mut hourly[local.hour() as usize];
+                if config.is_work_time(&local) {
+                    bucket.work_hours_ms += chunk;
+                } else {
+                    bucket.off_hours_ms += chunk;
+                }
+
+                t = chunk_end;
+            }
+        }
+
+        Ok(ProductivityReport {
+            start_timestamp: start,
+            end_timestamp: end,
+            work_hours_focus_ms: hourly.iter().map(|h| h.work_hours_ms).sum(),
+            off_hours_focus_ms: hourly.iter().map(|h| h.off_hours_ms).sum(),
+            hourly,
+        })
+    }
+
+    async fn get_app_usage_internal(db: &Arc<Database>, session_id: &str) -> Result<Vec<AppUsageInfo>, Box<dyn std::error::Error + Send + Sync>> {
         // Query the app_usage table
         #[derive(sqlx::FromRow)]
         struct AppUsageRow {
@@ -505,7 +1347,7 @@ impl SessionManager {
              ORDER BY total_focus DESC"
         )
         .bind(session_id)
-        .fetch_all(self.db.pool())
+        .fetch_all(db.pool())
         .await?;
 
         let apps = results
@@ -518,13 +1360,353 @@ impl SessionManager {
 
         Ok(apps)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_productivity_models_disagree_on_entertainment() {
+        let apps = vec![
+            AppUsageInfo { app_name: "Spotify".to_string(), focus_duration_ms: 50 * 60_000 },
+            AppUsageInfo { app_name: "Visual Studio Code".to_string(), focus_duration_ms: 10 * 60_000 },
+        ];
+
+        let focus = ProductivityModelKind::FocusWeighted.model().score(&apps);
+        let category = ProductivityModelKind::CategoryWeighted.model().score(&apps);
+
+        // Long, unbroken focus looks productive regardless of what the app is
+        assert!((focus - 1.0 / 1.2).abs() < 1e-4);
+        // Only the ten minutes in the editor count
+        assert!((category - 1.0 / 6.0).abs() < 1e-4);
+        assert!(category < focus);
+    }
+
+    #[test]
+    fn test_accumulator_counts_focus_switches_and_away_time() {
+        let mut acc = MetricsAccumulator::new("live".to_string(), 0);
+
+        acc.record_focus_change("Editor".to_string(), 0);
+        // Regaining focus in the same app is not a switch
+        acc.record_focus_change("Editor".to_string(), 30_000);
+        acc.record_focus_change("Browser".to_string(), 120_000);
+
+        acc.record_input(0);
+        acc.record_input(50_000);
+        // Two minutes without input counts as away
+        acc.record_input(170_000);
+
+        let metrics = acc.snapshot(180_000, &FocusWeighted);
+
+        assert_eq!(metrics.total_duration_ms, 180_000);
+        assert_eq!(metrics.app_switches, 1);
+        assert_eq!(metrics.unique_apps, 2);
+        assert_eq!(metrics.most_used_app, "Editor");
+        assert_eq!(metrics.active_duration_ms, 60_000);
+        assert_eq!(metrics.idle_duration_ms, 120_000);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_for_current_session_come_from_reported_activity() {
+        let db = test_db().await;
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let session_id = manager.get_or_create_session().await.unwrap();
+        let start = db.get_session(&session_id).await.unwrap().start_timestamp;
+
+        let activity = manager.activity_sender();
+        activity.send(ActivitySignal::FocusChange { app_name: "Editor".to_string(), timestamp: start }).unwrap();
+        activity.send(ActivitySignal::FocusChange { app_name: "Terminal".to_string(), timestamp: start + 1 }).unwrap();
+        activity.send(ActivitySignal::Input { timestamp: start + 1 }).unwrap();
+
+        // Nothing was written to app_usage, so these can only come from the accumulator
+        let mut metrics = manager.calculate_session_metrics(&session_id).await.unwrap();
+        for _ in 0..100 {
+            if metrics.app_switches == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            metrics = manager.calculate_session_metrics(&session_id).await.unwrap();
+        }
+
+        assert_eq!(metrics.app_switches, 1);
+        assert_eq!(metrics.unique_apps, 2);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_for_missing_session_are_an_error() {
+        let db = test_db().await;
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+
+        assert!(manager.calculate_session_metrics("missing").await.is_err());
+    }
+
+    #[test]
+    fn test_hid_idle_time_reads_nanoseconds() {
+        assert_eq!(hid_idle_time(2_500_000_000), Duration::from_millis(2_500));
+        assert_eq!(hid_idle_time(0), Duration::ZERO);
+        // A bogus negative reading means no idle time, not a huge one
+        assert_eq!(hid_idle_time(-1), Duration::ZERO);
+    }
+
+    async fn test_db() -> Arc<Database> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db
+    }
+
+    async fn add_clipboard_event(db: &Database, session_id: &str, timestamp: i64) {
+        sqlx::query(
+            "INSERT INTO clipboard_events (id, session_id, timestamp, content_type, size_bytes)
+             VALUES (?, ?, ?, 'text', 1)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(timestamp)
+        .execute(db.pool())
+        .await
+        .unwrap();
+    }
+
+    async fn clipboard_sessions(db: &Database) -> Vec<(String, i64)> {
+        sqlx::query_as("SELECT session_id, timestamp FROM clipboard_events ORDER BY timestamp")
+            .fetch_all(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_merge_reparents_rows_and_extends_end() {
+        let db = test_db().await;
+        db.create_session("first", 1_000, "test-device").await.unwrap();
+        db.end_session("first", 2_000).await.unwrap();
+        db.create_session("second", 5_000, "test-device").await.unwrap();
+        db.end_session("second", 9_000).await.unwrap();
+        add_clipboard_event(&db, "first", 1_500).await;
+        add_clipboard_event(&db, "second", 6_000).await;
+
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let merged = manager
+            .merge_sessions(vec!["second".to_string(), "first".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(merged.id, "first");
+        assert_eq!(merged.start_timestamp, 1_000);
+        assert_eq!(merged.end_timestamp, Some(9_000));
+        assert!(db.get_session("second").await.is_err());
+        assert_eq!(
+            clipboard_sessions(&db).await,
+            vec![("first".to_string(), 1_500), ("first".to_string(), 6_000)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_split_moves_later_rows_to_new_session() {
+        let db = test_db().await;
+        db.create_session("original", 0, "test-device").await.unwrap();
+        db.end_session("original", 10_000).await.unwrap();
+        add_clipboard_event(&db, "original", 1_000).await;
+        add_clipboard_event(&db, "original", 6_000).await;
+
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        assert!(manager.split_session("original", 12_000).await.is_err());
+
+        let tail = manager.split_session("original", 5_000).await.unwrap();
+
+        assert_eq!(tail.start_timestamp, 5_000);
+        assert_eq!(tail.end_timestamp, Some(10_000));
+        assert_eq!(db.get_session("original").await.unwrap().end_timestamp, Some(5_000));
+        assert_eq!(
+            clipboard_sessions(&db).await,
+            vec![("original".to_string(), 1_000), (tail.id.clone(), 6_000)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sessions_share_persisted_device_id() {
+        let db = test_db().await;
+
+        let mut config = Config::default();
+        let (device_id, changed) = config.ensure_device_id(|| Some("platform-id".to_string()));
+        assert!(changed);
+
+        // A later platform id doesn't replace the stored one
+        assert_eq!(config.ensure_device_id(|| Some("other-id".to_string())), (device_id.clone(), false));
+
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), device_id).await.unwrap();
+        let first = manager.get_or_create_session().await.unwrap();
+        manager.end_current_session().await.unwrap();
+        let second = manager.get_or_create_session().await.unwrap();
+        assert_ne!(first, second);
+
+        for id in [&first, &second] {
+            let session = manager.get_session_by_id(id).await.unwrap();
+            assert_eq!(Some(session.device_id), config.device_id.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_will_sleep_ends_current_session() {
+        let db = test_db().await;
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let session_id = manager.get_or_create_session().await.unwrap();
+        manager.start_monitoring().await.unwrap();
+        manager.power_monitor.trigger_event(PowerEvent::WillSleep);
+
+        for _ in 0..50 {
+            if manager.get_current_session().await.unwrap().is_none() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert!(manager.get_current_session().await.unwrap().is_none());
+        assert!(db.get_session(&session_id).await.unwrap().end_timestamp.is_some());
+
+        manager.stop_monitoring().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_matches_recorded_data() {
+        let db = test_db().await;
+        db.create_session("populated", 0, "test-device").await.unwrap();
+        db.end_session("populated", 600_000).await.unwrap();
+
+        sqlx::query(
+            "INSERT INTO app_usage (id, session_id, app_name, bundle_id, process_id, start_timestamp, end_timestamp, focus_duration_ms, background_duration_ms)
+             VALUES (?, 'populated', 'Visual Studio Code', '', 1, 0, 600000, 600000, 0)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+        for i in 0..3i64 {
+            sqlx::query(
+                "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id)
+                 VALUES (?, 'populated', ?, 'key_down', 65, '{}', 'Visual Studio Code', '', 1)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(i * 1_000)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO commands (id, session_id, timestamp, shortcut, command_type, app_name, description)
+             VALUES (?, 'populated', 1000, 'Cmd+S', 'save', 'Visual Studio Code', 'Save')"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+        add_clipboard_event(&db, "populated", 2_000).await;
+
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let summary = manager.get_session_summary("populated").await.unwrap();
+        let coverage = db.get_session_coverage("populated").await.unwrap();
+
+        assert_eq!(summary.session.id, "populated");
+        assert_eq!(summary.session_type, SessionType::Development);
+        assert_eq!(summary.metrics.total_duration_ms, 600_000);
+        assert_eq!(summary.has_screen_recording, coverage.screen_segments > 0);
+        assert!(!summary.has_screen_recording);
+        assert!(summary.has_input_recording);
+        assert!(summary.has_clipboard_events);
+        assert!(!summary.has_ocr_text);
+        assert_eq!(summary.keyboard_event_count, 3);
+        assert_eq!(summary.command_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_uses_stored_session_type() {
+        let db = test_db().await;
+        db.create_session("classified", 0, "test-device").await.unwrap();
+        db.end_session("classified", 600_000).await.unwrap();
+
+        // The recorded apps would classify as development
+        sqlx::query(
+            "INSERT INTO app_usage (id, session_id, app_name, bundle_id, process_id, start_timestamp, end_timestamp, focus_duration_ms, background_duration_ms)
+             VALUES (?, 'classified', 'Visual Studio Code', '', 1, 0, 600000, 600000, 0)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+        db.set_session_type("classified", SessionType::Research.to_string()).await.unwrap();
+
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let summary = manager.get_session_summary("classified").await.unwrap();
+
+        assert_eq!(summary.session_type, SessionType::Research);
+    }
+
+    async fn add_app_usage(db: &Database, session_id: &str, app_name: &str, focus_duration_ms: i64) {
+        sqlx::query(
+            "INSERT INTO app_usage (id, session_id, app_name, bundle_id, process_id, start_timestamp, end_timestamp, focus_duration_ms, background_duration_ms)
+             VALUES (?, ?, ?, '', 1, 0, ?, ?, 0)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(app_name)
+        .bind(focus_duration_ms)
+        .bind(focus_duration_ms)
+        .execute(db.pool())
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reclassify_rewrites_stale_types_in_range() {
+        let db = test_db().await;
+        for (id, start) in [("coding", 1_000), ("idle", 2_000), ("outside", 10_000)] {
+            db.create_session(id, start, "test-device").await.unwrap();
+            db.end_session(id, start + 500).await.unwrap();
+            db.set_session_type(id, SessionType::Entertainment.to_string()).await.unwrap();
+        }
+        add_app_usage(&db, "coding", "Visual Studio Code", 600_000).await;
+        add_app_usage(&db, "outside", "Visual Studio Code", 600_000).await;
+
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let progress = std::sync::Mutex::new(Vec::new());
+        let total = manager
+            .reclassify_sessions_in_range(0, 5_000, |done, total| progress.lock().unwrap().push((done, total)))
+            .await
+            .unwrap();
+
+        assert_eq!(total, 2);
+        assert_eq!(*progress.lock().unwrap(), vec![(1, 2), (2, 2)]);
+
+        let coding = db.get_session("coding").await.unwrap();
+        assert_eq!(coding.session_type.as_deref(), Some("development"));
+        let idle = db.get_session("idle").await.unwrap();
+        assert_eq!(idle.session_type.as_deref(), Some("unknown"));
+        // Sessions outside the range keep whatever was stored
+        let outside = db.get_session("outside").await.unwrap();
+        assert_eq!(outside.session_type.as_deref(), Some("entertainment"));
+    }
 
-    fn get_device_id() -> String {
-        // Simple device ID based on hostname
-        // In production, this should be a persistent UUID stored in config
-        hostname::get()
-            .ok()
-            .and_then(|h| h.into_string().ok())
-            .unwrap_or_else(|| "unknown".to_string())
+    #[tokio::test]
+    async fn test_ending_a_session_stores_its_type() {
+        let db = test_db().await;
+        let manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string()).await.unwrap();
+        let session_id = manager.get_or_create_session().await.unwrap();
+        add_app_usage(&db, &session_id, "Visual Studio Code", 600_000).await;
+
+        manager.end_current_session().await.unwrap();
+        assert!(manager.get_current_session().await.unwrap().is_none());
+        let stored = db.get_session(&session_id).await.unwrap();
+        assert_eq!(stored.session_type.as_deref(), Some("development"));
+
+        // Usage added afterwards would classify differently; listing must not reclassify
+        add_app_usage(&db, &session_id, "Netflix", 6_000_000).await;
+        let sessions = manager.get_sessions_in_range(0, i64::MAX).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_type.as_deref(), Some("development"));
     }
 }
@@ -1,11 +1,13 @@
 // Frame storage system - saves captured frames to disk and tracks in database
 
 use crate::core::database::Database;
+use crate::core::session_manager::SESSION_CHILD_TABLES;
 use crate::core::video_encoder::VideoSegment;
 use crate::models::capture::{PixelFormat, RawFrame};
 use image::{ImageBuffer, Rgba};
 use sqlx::Row;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
@@ -30,10 +32,18 @@ pub enum StorageError {
 
 pub type StorageResult<T> = Result<T, StorageError>;
 
+/// `usage_bytes` before the recordings directory has been measured
+const USAGE_UNKNOWN: u64 = u64::MAX;
+
 /// Recording storage manager
 pub struct RecordingStorage {
     base_path: PathBuf,
     db: Arc<Database>,
+    /// Most bytes recordings may take on disk; 0 means no limit
+    max_storage_bytes: AtomicU64,
+    /// Bytes recordings take on disk, kept up to date as files are written
+    /// and deleted so the quota check doesn't walk the tree
+    usage_bytes: AtomicU64,
 }
 
 impl RecordingStorage {
@@ -42,13 +52,133 @@ impl RecordingStorage {
         // Ensure base path exists
         std::fs::create_dir_all(&base_path)?;
 
-        Ok(Self { base_path, db })
+        Ok(Self {
+            base_path,
+            db,
+            max_storage_bytes: AtomicU64::new(0),
+            usage_bytes: AtomicU64::new(USAGE_UNKNOWN),
+        })
+    }
+
+    /// Cap the space recordings may use; None removes the cap
+    pub fn set_max_storage_bytes(&self, max_bytes: Option<u64>) {
+        self.max_storage_bytes.store(max_bytes.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    pub fn max_storage_bytes(&self) -> Option<u64> {
+        match self.max_storage_bytes.load(Ordering::Relaxed) {
+            0 => None,
+            max => Some(max),
+        }
+    }
+
+    /// Total bytes of every recording file: frames, segments, base layers and anything else
+    ///
+    /// Measures the directory tree, and corrects the tracked usage with the result.
+    pub fn get_disk_usage(&self) -> StorageResult<u64> {
+        let usage = directory_size(&self.base_path)?;
+        self.usage_bytes.store(usage, Ordering::Relaxed);
+        Ok(usage)
+    }
+
+    /// Tracked disk usage, measuring the tree only the first time
+    fn tracked_usage(&self) -> StorageResult<u64> {
+        match self.usage_bytes.load(Ordering::Relaxed) {
+            USAGE_UNKNOWN => self.get_disk_usage(),
+            usage => Ok(usage),
+        }
+    }
+
+    /// Adjust the tracked usage by bytes written (positive) or deleted (negative)
+    fn adjust_usage(&self, delta: i64) {
+        let _ = self.usage_bytes.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |usage| {
+            (usage != USAGE_UNKNOWN).then(|| usage.saturating_add_signed(delta))
+        });
+    }
+
+    /// Delete the oldest finished sessions until recordings fit the quota
+    ///
+    /// Sessions still being recorded are never evicted. Returns the ids of the
+    /// sessions that were deleted, oldest first.
+    pub async fn enforce_quota(&self) -> StorageResult<Vec<Uuid>> {
+        self.make_room(0).await
+    }
+
+    /// Delete the oldest finished sessions until `incoming` more bytes fit the quota
+    ///
+    /// Fails without deleting anything if they wouldn't fit even with every
+    /// finished session gone. Returns the ids of the deleted sessions, oldest first.
+    pub async fn reserve_space(&self, incoming: u64) -> StorageResult<Vec<Uuid>> {
+        if let Some(max_bytes) = self.max_storage_bytes() {
+            let active_bytes = self.active_sessions_size().await?;
+            if active_bytes.saturating_add(incoming) > max_bytes {
+                return Err(StorageError::Other(format!(
+                    "{} bytes don't fit the {} byte storage quota",
+                    incoming, max_bytes
+                )));
+            }
+        }
+
+        self.make_room(incoming).await
+    }
+
+    /// Bytes used by everything that can't be evicted: sessions still recording and loose files
+    async fn active_sessions_size(&self) -> StorageResult<u64> {
+        let finished: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM sessions WHERE recording_path IS NOT NULL AND end_timestamp IS NOT NULL"
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut evictable = 0u64;
+        for id in finished {
+            if let Ok(session_id) = Uuid::parse_str(&id) {
+                evictable += directory_size(&self.get_session_path(&session_id))?;
+            }
+        }
+
+        Ok(self.tracked_usage()?.saturating_sub(evictable))
+    }
+
+    async fn make_room(&self, incoming: u64) -> StorageResult<Vec<Uuid>> {
+        let Some(max_bytes) = self.max_storage_bytes() else {
+            return Ok(Vec::new());
+        };
+
+        let mut usage = self.tracked_usage()?.saturating_add(incoming);
+        if usage <= max_bytes {
+            return Ok(Vec::new());
+        }
+
+        let candidates: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM sessions
+             WHERE recording_path IS NOT NULL AND end_timestamp IS NOT NULL
+             ORDER BY start_timestamp ASC"
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut evicted = Vec::new();
+        for id in candidates {
+            if usage <= max_bytes {
+                break;
+            }
+            let Ok(session_id) = Uuid::parse_str(&id) else {
+                continue;
+            };
+
+            usage = usage.saturating_sub(self.delete_session_files(session_id).await?);
+            evicted.push(session_id);
+        }
+
+        Ok(evicted)
     }
 
     /// Create a new recording session
     pub async fn create_session(&self, _display_id: u32) -> StorageResult<Uuid> {
         let session_id = Uuid::new_v4();
-        let start_timestamp = chrono::Utc::now().timestamp();
+        // Milliseconds, matching sessions created by the SessionManager
+        let start_timestamp = chrono::Utc::now().timestamp_millis();
 
         // Create session directory
         let session_path = self.get_session_path(&session_id);
@@ -64,7 +194,7 @@ impl RecordingStorage {
         .bind("local") // device_id - using "local" for now
         .bind(start_timestamp)
         .bind(session_path.to_string_lossy().to_string())
-        .bind(start_timestamp * 1000) // created_at in milliseconds
+        .bind(start_timestamp) // created_at in milliseconds
         .execute(self.db.pool())
         .await?;
 
@@ -74,6 +204,28 @@ impl RecordingStorage {
         Ok(session_id)
     }
 
+    /// Prepare storage for an existing session, such as one created by the SessionManager
+    pub async fn attach_session(&self, session_id: Uuid) -> StorageResult<()> {
+        let session_path = self.get_session_path(&session_id);
+        std::fs::create_dir_all(session_path.join("frames"))?;
+        std::fs::create_dir_all(session_path.join("segments"))?;
+
+        let result = sqlx::query("UPDATE sessions SET recording_path = ? WHERE id = ?")
+            .bind(session_path.to_string_lossy().to_string())
+            .bind(session_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::SessionNotFound(session_id));
+        }
+
+        println!("Attached recording to session: {}", session_id);
+        println!("Recording path: {}", session_path.display());
+
+        Ok(())
+    }
+
     /// Save a frame to disk and database
     pub async fn save_frame(&self, session_id: Uuid, frame: &RawFrame) -> StorageResult<PathBuf> {
         let frame_id = Uuid::new_v4();
@@ -85,6 +237,7 @@ impl RecordingStorage {
 
         // Convert RawFrame to PNG
         self.save_frame_as_png(frame, &frame_path)?;
+        self.adjust_usage(std::fs::metadata(&frame_path)?.len() as i64);
 
         // Insert frame record into database
         sqlx::query(
@@ -105,7 +258,7 @@ impl RecordingStorage {
 
     /// End a recording session
     pub async fn end_session(&self, session_id: Uuid) -> StorageResult<()> {
-        let end_timestamp = chrono::Utc::now().timestamp();
+        let end_timestamp = chrono::Utc::now().timestamp_millis();
 
         // Get session info
         let row = sqlx::query("SELECT start_timestamp FROM sessions WHERE id = ?")
@@ -115,8 +268,30 @@ impl RecordingStorage {
             .ok_or(StorageError::SessionNotFound(session_id))?;
 
         let start_timestamp: i64 = row.get("start_timestamp");
-        let duration = end_timestamp - start_timestamp;
+        let duration = (end_timestamp - start_timestamp) / 1000;
+
+        sqlx::query("UPDATE sessions SET end_timestamp = ? WHERE id = ?")
+            .bind(end_timestamp)
+            .bind(session_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        let (frame_count, total_size) = self.update_session_stats_inner(session_id).await?;
+
+        println!("Ended recording session: {}", session_id);
+        println!("  Duration: {}s", duration);
+        println!("  Frames: {}", frame_count);
+        println!("  Size: {} bytes", total_size);
+
+        Ok(())
+    }
 
+    /// Refresh a session's frame count and size without ending it
+    pub async fn update_session_stats(&self, session_id: Uuid) -> StorageResult<()> {
+        self.update_session_stats_inner(session_id).await.map(|_| ())
+    }
+
+    async fn update_session_stats_inner(&self, session_id: Uuid) -> StorageResult<(i64, u64)> {
         // Count frames
         let frame_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM frames WHERE session_id = ?")
             .bind(session_id.to_string())
@@ -126,25 +301,18 @@ impl RecordingStorage {
         // Calculate total size
         let total_size = self.calculate_session_size(&session_id).await?;
 
-        // Update session
         sqlx::query(
             "UPDATE sessions
-             SET end_timestamp = ?, frame_count = ?, total_size_bytes = ?
+             SET frame_count = ?, total_size_bytes = ?
              WHERE id = ?",
         )
-        .bind(end_timestamp)
         .bind(frame_count)
         .bind(total_size as i64)
         .bind(session_id.to_string())
         .execute(self.db.pool())
         .await?;
 
-        println!("Ended recording session: {}", session_id);
-        println!("  Duration: {}s", duration);
-        println!("  Frames: {}", frame_count);
-        println!("  Size: {} bytes", total_size);
-
-        Ok(())
+        Ok((frame_count, total_size))
     }
 
     /// Get all frame paths for a session
@@ -192,31 +360,49 @@ impl RecordingStorage {
 
     /// Delete a recording session
     pub async fn delete_session(&self, session_id: Uuid) -> StorageResult<()> {
-        // Delete frames from database
-        sqlx::query("DELETE FROM frames WHERE session_id = ?")
-            .bind(session_id.to_string())
-            .execute(self.db.pool())
-            .await?;
+        self.delete_session_files(session_id).await.map(|_| ())
+    }
+
+    /// Delete a recording session, returning how many bytes its files took
+    async fn delete_session_files(&self, session_id: Uuid) -> StorageResult<u64> {
+        let mut tx = self.db.pool().begin().await?;
+
+        // Recorder tables have no cascade, so every per-session table is cleared first
+        for (table, _) in SESSION_CHILD_TABLES {
+            sqlx::query(&format!("DELETE FROM {} WHERE session_id = ?", table))
+                .bind(session_id.to_string())
+                .execute(&mut *tx)
+                .await?;
+        }
 
         // Delete session from database
         sqlx::query("DELETE FROM sessions WHERE id = ?")
             .bind(session_id.to_string())
-            .execute(self.db.pool())
+            .execute(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         // Delete session directory
         let session_path = self.get_session_path(&session_id);
+        let session_size = directory_size(&session_path)?;
         if session_path.exists() {
             std::fs::remove_dir_all(&session_path)?;
         }
+        self.adjust_usage(-(session_size as i64));
 
         println!("Deleted recording session: {}", session_id);
 
-        Ok(())
+        Ok(session_size)
+    }
+
+    /// Directory every session's recordings live under
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
     }
 
     /// Get the path for a session
-    fn get_session_path(&self, session_id: &Uuid) -> PathBuf {
+    pub fn get_session_path(&self, session_id: &Uuid) -> PathBuf {
         self.base_path.join(session_id.to_string())
     }
 
@@ -270,6 +456,39 @@ impl RecordingStorage {
         &self,
         session_id: &Uuid,
         segment: &VideoSegment,
+    ) -> StorageResult<()> {
+        let mut tx = self.db.pool().begin().await?;
+        Self::insert_segment(&mut tx, session_id, segment).await?;
+        tx.commit().await?;
+        self.record_written(segment.file_size_bytes);
+
+        // Each segment grows the recordings, so make room as they land
+        match self.enforce_quota().await {
+            Ok(evicted) if !evicted.is_empty() => {
+                println!("Storage quota exceeded, deleted {} old session(s)", evicted.len());
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to enforce storage quota: {}", e),
+        }
+
+        Ok(())
+    }
+
+    /// Count bytes written under the recordings directory by another component
+    pub fn record_written(&self, bytes: u64) {
+        self.adjust_usage(bytes as i64);
+    }
+
+    /// Count bytes deleted from the recordings directory by another component
+    pub fn record_removed(&self, bytes: u64) {
+        self.adjust_usage(-(bytes as i64));
+    }
+
+    /// Record a segment row and bump the session's segment count
+    pub(crate) async fn insert_segment(
+        conn: &mut sqlx::SqliteConnection,
+        session_id: &Uuid,
+        segment: &VideoSegment,
     ) -> StorageResult<()> {
         let segment_id = Uuid::new_v4();
 
@@ -285,13 +504,13 @@ impl RecordingStorage {
         .bind(segment.frame_count as i64)
         .bind(segment.file_size_bytes as i64)
         .bind(segment.duration_ms as i64)
-        .execute(self.db.pool())
+        .execute(&mut *conn)
         .await?;
 
         // Update session segment count
         sqlx::query("UPDATE sessions SET segment_count = segment_count + 1 WHERE id = ?")
             .bind(session_id.to_string())
-            .execute(self.db.pool())
+            .execute(&mut *conn)
             .await?;
 
         Ok(())
@@ -305,8 +524,10 @@ impl RecordingStorage {
     ) -> StorageResult<()> {
         let base_layer_path = self.get_session_path(session_id).join("base_layer.png");
 
-        // Save the frame as PNG
+        // Save the frame as PNG, replacing any earlier base layer
+        let replaced = std::fs::metadata(&base_layer_path).map(|m| m.len()).unwrap_or(0);
         self.save_frame_as_png(frame, &base_layer_path)?;
+        self.adjust_usage(std::fs::metadata(&base_layer_path)?.len() as i64 - replaced as i64);
 
         // Update session with base layer path
         sqlx::query("UPDATE sessions SET base_layer_path = ? WHERE id = ?")
@@ -318,6 +539,42 @@ impl RecordingStorage {
         Ok(())
     }
 
+    /// Record the size frames were captured at, before `capture_scale`
+    pub async fn save_source_dimensions(&self, session_id: &Uuid, width: u32, height: u32) -> StorageResult<()> {
+        sqlx::query("UPDATE sessions SET source_width = ?, source_height = ? WHERE id = ?")
+            .bind(width as i64)
+            .bind(height as i64)
+            .bind(session_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Size frames were captured at, if the session recorded any
+    pub async fn get_source_dimensions(&self, session_id: &Uuid) -> StorageResult<Option<(u32, u32)>> {
+        let row: Option<(Option<i64>, Option<i64>)> =
+            sqlx::query_as("SELECT source_width, source_height FROM sessions WHERE id = ?")
+                .bind(session_id.to_string())
+                .fetch_optional(self.db.pool())
+                .await?;
+
+        Ok(match row {
+            Some((Some(width), Some(height))) => Some((width as u32, height as u32)),
+            _ => None,
+        })
+    }
+
+    /// Load the session's base layer, if one has been saved
+    pub async fn load_base_layer(&self, session_id: &Uuid) -> StorageResult<Option<RawFrame>> {
+        let base_layer_path = self.get_session_path(session_id).join("base_layer.png");
+        if !base_layer_path.exists() {
+            return Ok(None);
+        }
+
+        self.load_frame(base_layer_path).await.map(Some)
+    }
+
     /// Get the path for a video segment
     pub fn get_segment_path(&self, session_id: &Uuid, segment_num: usize) -> PathBuf {
         self.get_session_path(session_id)
@@ -356,6 +613,26 @@ impl RecordingStorage {
     }
 }
 
+/// Sum of the sizes of every file under `path`; a missing path is empty
+fn directory_size(path: &Path) -> StorageResult<u64> {
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let mut total = 0u64;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            total += directory_size(&entry.path())?;
+        } else {
+            total += metadata.len();
+        }
+    }
+
+    Ok(total)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -419,4 +696,174 @@ mod tests {
         // Cleanup
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
+
+    #[tokio::test]
+    async fn test_attach_existing_session() {
+        let db = Arc::new(Database::init().await.expect("Failed to init database"));
+
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_attach");
+        let storage = RecordingStorage::new(temp_dir.clone(), db.clone())
+            .await
+            .expect("Failed to create storage");
+
+        // A session created elsewhere, as the SessionManager does
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), chrono::Utc::now().timestamp_millis(), "test-device")
+            .await
+            .expect("Failed to create session");
+
+        storage
+            .attach_session(session_id)
+            .await
+            .expect("Failed to attach session");
+        assert!(temp_dir.join(session_id.to_string()).join("segments").exists());
+
+        // Unknown sessions are rejected rather than silently created
+        let missing = storage.attach_session(Uuid::new_v4()).await;
+        assert!(matches!(missing, Err(StorageError::SessionNotFound(_))));
+
+        db.delete_session(&session_id.to_string()).await.ok();
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    async fn memory_db() -> Arc<Database> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+        db
+    }
+
+    /// Attach storage to a new session and give it a segment file of `segment_bytes`
+    async fn recorded_session(
+        storage: &RecordingStorage,
+        db: &Database,
+        start_timestamp: i64,
+        finished: bool,
+        segment_bytes: usize,
+    ) -> Uuid {
+        let session_id = Uuid::new_v4();
+        db.create_session(&session_id.to_string(), start_timestamp, "test-device").await.unwrap();
+        if finished {
+            db.end_session(&session_id.to_string(), start_timestamp + 60_000).await.unwrap();
+        }
+        storage.attach_session(session_id).await.unwrap();
+        std::fs::write(storage.get_segment_path(&session_id, 0), vec![0u8; segment_bytes]).unwrap();
+        session_id
+    }
+
+    #[tokio::test]
+    async fn test_disk_usage_sums_file_sizes() {
+        let db = memory_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_usage");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let storage = RecordingStorage::new(temp_dir.clone(), db.clone()).await.unwrap();
+
+        assert_eq!(storage.get_disk_usage().unwrap(), 0);
+
+        let session_id = recorded_session(&storage, &db, 1_000, true, 1_000).await;
+        let session_path = temp_dir.join(session_id.to_string());
+        std::fs::write(session_path.join("base_layer.png"), vec![0u8; 200]).unwrap();
+        std::fs::create_dir_all(session_path.join("audio")).unwrap();
+        std::fs::write(session_path.join("audio").join("mic.wav"), vec![0u8; 30]).unwrap();
+
+        assert_eq!(storage.get_disk_usage().unwrap(), 1_230);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_quota_evicts_oldest_session_first() {
+        let db = memory_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_quota");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let storage = RecordingStorage::new(temp_dir.clone(), db.clone()).await.unwrap();
+
+        // Still recording, so never evicted even though it is the oldest
+        let active = recorded_session(&storage, &db, 0, false, 1_000).await;
+        let oldest = recorded_session(&storage, &db, 1_000, true, 1_000).await;
+        let middle = recorded_session(&storage, &db, 2_000, true, 1_000).await;
+        let newest = recorded_session(&storage, &db, 3_000, true, 1_000).await;
+
+        // Input recorded in the evicted session goes with it
+        sqlx::query(
+            "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id)
+             VALUES (?, ?, 1500, 'key_down', 65, '{}', 'Editor', '', 1)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(oldest.to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        // No quota, nothing evicted
+        assert!(storage.enforce_quota().await.unwrap().is_empty());
+
+        storage.set_max_storage_bytes(Some(3_500));
+        let evicted = storage.enforce_quota().await.unwrap();
+
+        assert_eq!(evicted, vec![oldest]);
+        assert!(!temp_dir.join(oldest.to_string()).exists());
+        assert!(db.get_session(&oldest.to_string()).await.is_err());
+        for kept in [active, middle, newest] {
+            assert!(temp_dir.join(kept.to_string()).exists());
+            assert!(db.get_session(&kept.to_string()).await.is_ok());
+        }
+        assert_eq!(storage.get_disk_usage().unwrap(), 3_000);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_quota_follows_saved_segments_without_rescanning() {
+        let db = memory_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_quota_tracking");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let storage = RecordingStorage::new(temp_dir.clone(), db.clone()).await.unwrap();
+
+        let oldest = recorded_session(&storage, &db, 1_000, true, 1_000).await;
+        let newest = recorded_session(&storage, &db, 2_000, false, 1_000).await;
+        storage.set_max_storage_bytes(Some(2_500));
+        assert!(storage.enforce_quota().await.unwrap().is_empty());
+
+        // The segment is counted from its recorded size as it's saved
+        let segment = VideoSegment {
+            path: storage.get_segment_path(&newest, 1),
+            start_timestamp: 2_000,
+            end_timestamp: 3_000,
+            frame_count: 10,
+            duration_ms: 1_000,
+            file_size_bytes: 1_000,
+        };
+        storage.save_segment(&newest, &segment).await.unwrap();
+
+        assert!(!temp_dir.join(oldest.to_string()).exists());
+        assert!(db.get_session(&oldest.to_string()).await.is_err());
+
+        // Only the session still recording is left, so more can't be made to fit
+        assert!(storage.reserve_space(2_000).await.is_err());
+        assert!(temp_dir.join(newest.to_string()).exists());
+        assert!(storage.reserve_space(500).await.unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_source_dimensions_are_stored_with_the_session() {
+        let db = memory_db().await;
+        let temp_dir = std::env::temp_dir().join("observer_test_recordings_source_dimensions");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let storage = RecordingStorage::new(temp_dir.clone(), db.clone()).await.unwrap();
+
+        let session_id = recorded_session(&storage, &db, 1_000, false, 0).await;
+        assert_eq!(storage.get_source_dimensions(&session_id).await.unwrap(), None);
+
+        storage.save_source_dimensions(&session_id, 2560, 1440).await.unwrap();
+        assert_eq!(storage.get_source_dimensions(&session_id).await.unwrap(), Some((2560, 1440)));
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
 }
@@ -0,0 +1,326 @@
+// Timeline assembly - sessions in a time range with their apps and activity
+
+use crate::core::config::Config;
+use crate::core::database::{placeholders, Database};
+use crate::core::os_activity::AppUsage;
+use crate::core::session_manager::input_intensity;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Sessions returned per page when the caller doesn't ask for a size
+pub const DEFAULT_TIMELINE_LIMIT: u32 = 100;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineData {
+    pub sessions: Vec<TimelineSession>,
+    /// Duration of every session in the range, not just this page
+    pub total_duration: u64,
+    /// Number of sessions in the range, for paging
+    pub total_count: u32,
+    /// Whether sessions remain past this page
+    pub has_more: bool,
+    /// Offset of the next page, when this one doesn't reach the end of the range
+    pub next_offset: Option<u32>,
+    pub date_range: DateRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineSession {
+    pub id: String,
+    pub start_timestamp: i64,
+    pub end_timestamp: Option<i64>,
+    pub session_type: Option<String>,
+    pub applications: Vec<AppUsageSegment>,
+    pub activity_intensity: f32,
+    pub has_screen_recording: bool,
+    pub has_input_recording: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUsageSegment {
+    pub app_name: String,
+    pub bundle_id: String,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub focus_duration: i64,
+    pub color: String,
+}
+
+/// Load one page of the sessions starting in [start, end], newest first
+///
+/// App usage, recording presence and input density for the page are fetched
+/// with one grouped query each rather than per session.
+pub async fn load_timeline(
+    db: &Database,
+    config: &Config,
+    start: i64,
+    end: i64,
+    limit: u32,
+    offset: u32,
+) -> Result<TimelineData> {
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let (total_count, total_duration): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COALESCE(SUM(COALESCE(end_timestamp, ?) - start_timestamp), 0)
+         FROM sessions
+         WHERE start_timestamp >= ? AND start_timestamp <= ?"
+    )
+    .bind(now)
+    .bind(start)
+    .bind(end)
+    .fetch_one(db.pool())
+    .await?;
+
+    let sessions = db.get_sessions_in_range_page(start, end, limit, offset).await?;
+    let ids: Vec<String> = sessions.iter().map(|s| s.id.clone()).collect();
+
+    let mut apps_by_session = app_usage_by_session(db, &ids).await?;
+    let screen_segments = count_by_session(db, "video_segments", &ids).await?;
+    let input_events = input_events_by_session(db, &ids, now).await?;
+
+    let timeline_sessions = sessions
+        .into_iter()
+        .map(|session| {
+            let apps = apps_by_session.remove(&session.id).unwrap_or_default();
+            let (events, deliberate_events) = input_events.get(&session.id).copied().unwrap_or((0, 0));
+            let session_end = session.end_timestamp.unwrap_or(now);
+
+            TimelineSession {
+                applications: app_segments(config, apps, now),
+                activity_intensity: input_intensity(deliberate_events, session.start_timestamp, session_end),
+                has_screen_recording: screen_segments.get(&session.id).copied().unwrap_or(0) > 0,
+                has_input_recording: events > 0,
+                id: session.id,
+                start_timestamp: session.start_timestamp,
+                end_timestamp: session.end_timestamp,
+                session_type: session.session_type,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let end_of_page = offset + timeline_sessions.len() as u32;
+    let has_more = end_of_page < total_count as u32;
+
+    Ok(TimelineData {
+        has_more,
+        next_offset: has_more.then_some(end_of_page),
+        sessions: timeline_sessions,
+        total_duration: total_duration.max(0) as u64,
+        total_count: total_count as u32,
+        date_range: DateRange { start, end },
+    })
+}
+
+/// Convert a session's app usage to segments, numbering apps by first use for colors
+fn app_segments(config: &Config, apps: Vec<AppUsage>, now: i64) -> Vec<AppUsageSegment> {
+    let mut app_order: HashMap<String, usize> = HashMap::new();
+
+    apps.into_iter()
+        .map(|app| {
+            let next = app_order.len();
+            let order = *app_order.entry(app.app_name.clone()).or_insert(next);
+            AppUsageSegment {
+                color: config.app_color(&app.app_name, order),
+                app_name: app.app_name,
+                bundle_id: app.bundle_id,
+                start_timestamp: app.start_timestamp,
+                end_timestamp: app.end_timestamp.unwrap_or(now),
+                focus_duration: app.focus_duration_ms,
+            }
+        })
+        .collect()
+}
+
+async fn app_usage_by_session(db: &Database, ids: &[String]) -> Result<HashMap<String, Vec<AppUsage>>> {
+    let mut by_session: HashMap<String, Vec<AppUsage>> = HashMap::new();
+    if ids.is_empty() {
+        return Ok(by_session);
+    }
+
+    let sql = format!(
+        "SELECT id, session_id, app_name, bundle_id, process_id,
+                start_timestamp, end_timestamp, focus_duration_ms, background_duration_ms
+         FROM app_usage
+         WHERE session_id IN ({})
+         ORDER BY start_timestamp ASC",
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query_as::<_, AppUsage>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+
+    for app in query.fetch_all(db.pool()).await? {
+        by_session.entry(app.session_id.clone()).or_default().push(app);
+    }
+
+    Ok(by_session)
+}
+
+async fn count_by_session(db: &Database, table: &str, ids: &[String]) -> Result<HashMap<String, i64>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let sql = format!(
+        "SELECT session_id, COUNT(*) FROM {} WHERE session_id IN ({}) GROUP BY session_id",
+        table,
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query_as::<_, (String, i64)>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+
+    Ok(query.fetch_all(db.pool()).await?.into_iter().collect())
+}
+
+/// Keyboard plus mouse events inside each session's time range
+async fn input_events_by_session(db: &Database, ids: &[String], now: i64) -> Result<HashMap<String, (i64, i64)>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // Counts all events, and those other than mouse moves for intensity
+    let sql = format!(
+        "SELECT e.session_id, COUNT(*), SUM(e.deliberate)
+         FROM (
+             SELECT session_id, timestamp, 1 AS deliberate FROM keyboard_events WHERE session_id IN ({0})
+             UNION ALL
+             SELECT session_id, timestamp, event_type != 'move' FROM mouse_events WHERE session_id IN ({0})
+         ) e
+         JOIN sessions s ON s.id = e.session_id
+         WHERE e.timestamp BETWEEN s.start_timestamp AND COALESCE(s.end_timestamp, ?)
+         GROUP BY e.session_id",
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query_as::<_, (String, i64, i64)>(&sql);
+    for id in ids.iter().chain(ids) {
+        query = query.bind(id);
+    }
+    query = query.bind(now);
+
+    Ok(query
+        .fetch_all(db.pool())
+        .await?
+        .into_iter()
+        .map(|(id, events, deliberate)| (id, (events, deliberate)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use uuid::Uuid;
+
+    async fn test_db() -> Database {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database { pool };
+        db.run_migrations().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_timeline_pages_and_counts() {
+        let db = test_db().await;
+
+        // 25 one-minute sessions, a minute apart
+        for i in 0..25i64 {
+            let id = format!("session-{:02}", i);
+            db.create_session(&id, i * 120_000, "test-device").await.unwrap();
+            db.end_session(&id, i * 120_000 + 60_000).await.unwrap();
+        }
+
+        sqlx::query(
+            "INSERT INTO app_usage (id, session_id, app_name, bundle_id, process_id, start_timestamp, end_timestamp, focus_duration_ms, background_duration_ms)
+             VALUES (?, 'session-03', 'Editor', '', 1, 360000, 400000, 40000, 0)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        for i in 0..60i64 {
+            sqlx::query(
+                "INSERT INTO keyboard_events (id, session_id, timestamp, event_type, key_code, modifiers, app_name, window_title, process_id)
+                 VALUES (?, 'session-02', ?, 'key_down', 65, '{}', 'Editor', '', 1)"
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(240_000 + i * 1_000)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+
+        // A minute of pointer movement sampled every 50ms, alongside the typing and on its own
+        for (session_id, start) in [("session-02", 240_000i64), ("session-04", 480_000)] {
+            for i in 0..1_200i64 {
+                sqlx::query(
+                    "INSERT INTO mouse_events (id, session_id, timestamp, event_type, position_x, position_y, app_name, window_title, process_id)
+                     VALUES (?, ?, ?, 'move', 0, 0, 'Editor', '', 1)"
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(session_id)
+                .bind(start + i * 50)
+                .execute(db.pool())
+                .await
+                .unwrap();
+            }
+        }
+
+        let config = Config::default();
+        let page = load_timeline(&db, &config, 0, i64::MAX, 10, 20).await.unwrap();
+
+        assert_eq!(page.total_count, 25);
+        assert_eq!(page.total_duration, 25 * 60_000);
+        assert_eq!(page.next_offset, None);
+        let ids: Vec<&str> = page.sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["session-04", "session-03", "session-02", "session-01", "session-00"]);
+
+        // Batched lookups land on the right sessions
+        let session = |id: &str| page.sessions.iter().find(|s| s.id == id).unwrap();
+        assert_eq!(session("session-03").applications.len(), 1);
+        assert!(session("session-02").applications.is_empty());
+        assert!(session("session-02").has_input_recording);
+        assert!((session("session-02").activity_intensity - 0.5).abs() < 1e-4);
+        assert!(!session("session-03").has_input_recording);
+        assert!(!session("session-04").has_screen_recording);
+
+        // Mouse moves show input was recorded but don't count as activity
+        assert!(session("session-04").has_input_recording);
+        assert_eq!(session("session-04").activity_intensity, 0.0);
+
+        let first = load_timeline(&db, &config, 0, i64::MAX, 10, 0).await.unwrap();
+        assert_eq!(first.sessions.len(), 10);
+        assert_eq!(first.sessions[0].id, "session-24");
+        assert_eq!(first.next_offset, Some(10));
+
+        // The frontend reads these names
+        let json = serde_json::to_value(&first).unwrap();
+        assert_eq!(json["totalCount"], 25);
+        assert_eq!(json["hasMore"], true);
+        assert_eq!(json["nextOffset"], 10);
+        assert!(json["sessions"][0]["activityIntensity"].is_number());
+
+        let past_end = load_timeline(&db, &config, 0, i64::MAX, 10, 30).await.unwrap();
+        assert!(past_end.sessions.is_empty());
+        assert_eq!(past_end.total_count, 25);
+        assert_eq!(past_end.next_offset, None);
+    }
+}
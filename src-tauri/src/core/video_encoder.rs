@@ -1,5 +1,7 @@
 use crate::models::capture::RawFrame;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::sync::mpsc::Receiver;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -12,6 +14,8 @@ pub enum VideoEncoderError {
     Io(#[from] std::io::Error),
     #[error("Invalid codec: {0}")]
     InvalidCodec(String),
+    #[error("Invalid quality: {0}")]
+    InvalidQuality(String),
     #[error("Hardware acceleration not available")]
     HardwareAccelerationNotAvailable,
     #[error("Encoding failed: {0}")]
@@ -49,13 +53,45 @@ impl VideoCodec {
             VideoCodec::H264 => "libx264",
         }
     }
+
+    /// Hardware encoders worth trying on `platform`, most preferred first
+    pub fn hardware_encoder_candidates(&self, platform: &str) -> &'static [&'static str] {
+        match self {
+            VideoCodec::H264 => match platform {
+                "macos" => &["h264_videotoolbox"],
+                "windows" => &["h264_nvenc", "h264_qsv", "h264_amf"],
+                "linux" => &["h264_vaapi", "h264_nvenc", "h264_qsv"],
+                _ => &[],
+            },
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Hardware encoders the linked FFmpeg build offers for a codec
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardwareSupport {
+    /// Hardware encoders found, in preference order for this platform
+    pub available: Vec<String>,
+    /// Encoder used when hardware acceleration is enabled, if any
+    pub preferred: Option<String>,
+}
+
+impl HardwareSupport {
+    pub fn is_available(&self) -> bool {
+        self.preferred.is_some()
+    }
+}
+
+/// Highest CRF x264 accepts
+pub const MAX_CRF: u8 = 51;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum CompressionQuality {
-    High,   // CRF 18-23
-    Medium, // CRF 23-28 (default)
-    Low,    // CRF 28-35
+    High,   // CRF 20
+    Medium, // CRF 25 (default)
+    Low,    // CRF 30
+    /// Explicit CRF (0-51, lower = better) and frames between keyframes
+    Custom { crf: u8, keyframe_interval: u32 },
 }
 
 impl CompressionQuality {
@@ -64,8 +100,34 @@ impl CompressionQuality {
             CompressionQuality::High => 20,
             CompressionQuality::Medium => 25,
             CompressionQuality::Low => 30,
+            CompressionQuality::Custom { crf, .. } => *crf as u32,
         }
     }
+
+    /// Frames between keyframes; presets use one every two seconds
+    pub fn keyframe_interval(&self, fps: u32) -> u32 {
+        match self {
+            CompressionQuality::Custom { keyframe_interval, .. } => *keyframe_interval,
+            _ => fps * 2,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let CompressionQuality::Custom { crf, keyframe_interval } = *self {
+            if crf > MAX_CRF {
+                return Err(VideoEncoderError::InvalidQuality(format!(
+                    "crf must be between 0 and {}, got {}",
+                    MAX_CRF, crf
+                )));
+            }
+            if keyframe_interval == 0 {
+                return Err(VideoEncoderError::InvalidQuality(
+                    "keyframe_interval must be greater than 0".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,8 +144,10 @@ pub struct VideoSegment {
 pub struct VideoEncoder {
     codec: VideoCodec,
     quality: CompressionQuality,
-    hardware_acceleration: bool,
-    platform: String,
+    /// Encoder tried first for each segment
+    codec_name: String,
+    /// Set when hardware encoding was requested but software is in use
+    hardware_fallback: Arc<AtomicBool>,
 }
 
 impl VideoEncoder {
@@ -92,24 +156,71 @@ impl VideoEncoder {
         quality: CompressionQuality,
         hardware_acceleration: bool,
     ) -> Result<Self> {
-        let platform = if cfg!(target_os = "macos") {
-            "macos"
-        } else if cfg!(target_os = "windows") {
-            "windows"
-        } else if cfg!(target_os = "linux") {
-            "linux"
+        quality.validate()?;
+
+        let platform = current_platform();
+
+        let preferred = if hardware_acceleration {
+            Self::detect_hardware_support(codec).preferred
         } else {
-            "unknown"
+            None
         };
 
+        let hardware_fallback = hardware_acceleration && preferred.is_none();
+        if hardware_fallback {
+            eprintln!(
+                "Warning: No hardware {:?} encoder available on {}, using {}",
+                codec,
+                platform,
+                codec.software_fallback_name()
+            );
+        }
+
+        let codec_name = preferred.unwrap_or_else(|| codec.software_fallback_name().to_string());
+
         Ok(Self {
             codec,
             quality,
-            hardware_acceleration,
-            platform: platform.to_string(),
+            codec_name,
+            hardware_fallback: Arc::new(AtomicBool::new(hardware_fallback)),
         })
     }
 
+    /// Probe the linked FFmpeg for hardware encoders usable on this platform
+    ///
+    /// This only checks which encoders were compiled in; a listed encoder can
+    /// still fail to open without the matching GPU or driver, which
+    /// `encode_frames` handles by falling back to software.
+    pub fn detect_hardware_support(codec: VideoCodec) -> HardwareSupport {
+        let encoders = crate::core::ffmpeg_wrapper::available_encoders();
+
+        let available: Vec<String> = codec
+            .hardware_encoder_candidates(current_platform())
+            .iter()
+            .filter(|name| encoders.iter().any(|e| e == *name))
+            .map(|name| name.to_string())
+            .collect();
+
+        HardwareSupport {
+            preferred: available.first().cloned(),
+            available,
+        }
+    }
+
+    /// Name of the FFmpeg encoder segments are currently written with
+    pub fn active_encoder(&self) -> String {
+        if self.hardware_fallback() {
+            self.codec.software_fallback_name().to_string()
+        } else {
+            self.codec_name.clone()
+        }
+    }
+
+    /// Whether hardware encoding was requested but software is being used
+    pub fn hardware_fallback(&self) -> bool {
+        self.hardware_fallback.load(Ordering::Relaxed)
+    }
+
     /// Encode a batch of frames into a video file
     pub async fn encode_frames(
         &self,
@@ -130,8 +241,8 @@ impl VideoEncoder {
         // Encode frames in a blocking task since FFmpeg is synchronous
         let codec = self.codec;
         let quality = self.quality;
-        let hardware_acceleration = self.hardware_acceleration;
-        let platform = self.platform.clone();
+        let codec_name = self.active_encoder();
+        let hardware_fallback = self.hardware_fallback.clone();
         let output_path_clone = output_path.clone();
 
         tokio::task::spawn_blocking(move || {
@@ -141,8 +252,8 @@ impl VideoEncoder {
                 fps,
                 codec,
                 quality,
-                hardware_acceleration,
-                &platform,
+                &codec_name,
+                &hardware_fallback,
             )
         })
         .await
@@ -186,14 +297,15 @@ impl VideoEncoder {
         fps: u32,
         codec: VideoCodec,
         quality: CompressionQuality,
-        hardware_acceleration: bool,
-        platform: &str,
+        codec_name: &str,
+        hardware_fallback: &AtomicBool,
     ) -> Result<()> {
         use crate::core::ffmpeg_wrapper::FFmpegEncoder;
 
         println!("VideoEncoder: Encoding {} frames to {:?}", frames.len(), output_path);
         println!("  Codec: {:?}, Quality: {:?}, FPS: {}", codec, quality, fps);
-        println!("  Hardware acceleration: {}, Platform: {}", hardware_acceleration, platform);
+        let hardware_acceleration = codec_name != codec.software_fallback_name();
+        println!("  Hardware acceleration: {}, Platform: {}", hardware_acceleration, current_platform());
 
         // Create output directory if it doesn't exist
         if let Some(parent) = output_path.parent() {
@@ -208,17 +320,12 @@ impl VideoEncoder {
         let width = first_frame.width;
         let height = first_frame.height;
         let crf = quality.to_crf();
+        let keyframe_interval = quality.keyframe_interval(fps);
 
         // Try hardware acceleration first, fallback to software if it fails
-        let codec_name = if hardware_acceleration {
-            codec.to_ffmpeg_codec_name(true, platform)
-        } else {
-            codec.software_fallback_name().to_string()
-        };
-
         println!("  Attempting codec: {}", codec_name);
 
-        let mut encoder = match FFmpegEncoder::new(output_path, width, height, fps, &codec_name, crf) {
+        let mut encoder = match FFmpegEncoder::new(output_path, width, height, fps, codec_name, crf, keyframe_interval) {
             Ok(enc) => {
                 println!("  ✓ Successfully initialized {} encoder", codec_name);
                 enc
@@ -227,8 +334,11 @@ impl VideoEncoder {
                 println!("  ✗ Hardware acceleration failed: {}", e);
                 println!("  → Falling back to software encoder");
 
+                // Later segments go straight to software
+                hardware_fallback.store(true, Ordering::Relaxed);
+
                 let software_codec = codec.software_fallback_name();
-                FFmpegEncoder::new(output_path, width, height, fps, software_codec, crf)
+                FFmpegEncoder::new(output_path, width, height, fps, software_codec, crf, keyframe_interval)
                     .map_err(|e| VideoEncoderError::FFmpeg(format!(
                         "Software fallback also failed: {}", e
                     )))?
@@ -254,6 +364,18 @@ impl VideoEncoder {
     }
 }
 
+fn current_platform() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else {
+        "unknown"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -393,11 +515,56 @@ mod tests {
         assert_eq!(codec.software_fallback_name(), "libx264");
     }
 
+    #[test]
+    fn test_detect_hardware_support_is_deterministic() {
+        let first = VideoEncoder::detect_hardware_support(VideoCodec::H264);
+        let second = VideoEncoder::detect_hardware_support(VideoCodec::H264);
+        assert_eq!(first, second);
+
+        // Only known hardware encoders are reported, and never the software one
+        let candidates = VideoCodec::H264.hardware_encoder_candidates(current_platform());
+        assert!(first.available.iter().all(|name| candidates.contains(&name.as_str())));
+        assert_eq!(first.preferred.as_ref(), first.available.first());
+
+        let encoder = VideoEncoder::new(VideoCodec::H264, CompressionQuality::Medium, true).unwrap();
+        if first.is_available() {
+            assert_eq!(Some(encoder.active_encoder()), first.preferred);
+        } else {
+            assert_eq!(encoder.active_encoder(), "libx264");
+            assert!(encoder.hardware_fallback());
+        }
+
+        let software = VideoEncoder::new(VideoCodec::H264, CompressionQuality::Medium, false).unwrap();
+        assert_eq!(software.active_encoder(), "libx264");
+        assert!(!software.hardware_fallback());
+    }
+
     #[test]
     fn test_quality_crf() {
         assert_eq!(CompressionQuality::High.to_crf(), 20);
         assert_eq!(CompressionQuality::Medium.to_crf(), 25);
         assert_eq!(CompressionQuality::Low.to_crf(), 30);
+        assert_eq!(CompressionQuality::Medium.keyframe_interval(10), 20);
+
+        let custom = CompressionQuality::Custom { crf: 18, keyframe_interval: 300 };
+        assert_eq!(custom.to_crf(), 18);
+        assert_eq!(custom.keyframe_interval(10), 300);
+        assert!(VideoEncoder::new(VideoCodec::H264, custom, false).is_ok());
+    }
+
+    #[test]
+    fn test_custom_quality_out_of_range_rejected() {
+        let too_high = CompressionQuality::Custom { crf: 52, keyframe_interval: 20 };
+        assert!(matches!(
+            VideoEncoder::new(VideoCodec::H264, too_high, false),
+            Err(VideoEncoderError::InvalidQuality(_))
+        ));
+
+        let no_keyframes = CompressionQuality::Custom { crf: 23, keyframe_interval: 0 };
+        assert!(matches!(
+            no_keyframes.validate(),
+            Err(VideoEncoderError::InvalidQuality(_))
+        ));
     }
 
 }
@@ -2,67 +2,44 @@ pub mod core;
 pub mod models;
 pub mod platform;
 
+use core::auto_record::{AutoRecordAction, AutoRecordConfig, AutoRecordEvent, AutoRecordPolicy};
+use core::bundle::{BundleManifest, SessionBundler};
 use core::command_analyzer::{Command, CommandAnalyzer, CommandStats};
-use core::consent::{ConsentManager, Feature};
+use core::consent::{ConsentAuditEntry, ConsentManager, Feature};
 use core::config::Config;
-use core::database::Database;
+use core::database::{Database, SessionCoverage};
+use core::health::{HealthSource, ObserverStatus};
 use core::input_recorder::InputRecorder;
 use core::input_storage::{InputTimeline, TimeRange};
 use core::keyboard_recorder::KeyboardRecorder;
 use core::os_activity::{AppUsageStats, OsActivityRecorder};
-use core::playback_engine::{PlaybackEngine, PlaybackInfo, SeekInfo};
-use core::screen_recorder::{RecordingStatus, ScreenRecorder};
-use core::search_engine::{SearchEngine, SearchFilters, SearchQuery, SearchResults};
-use core::session_manager::{Session, SessionConfig, SessionManager, SessionMetrics};
+use core::clipboard_monitor::{ClipboardEvent, ClipboardMonitor};
+use core::model_manager::{ModelInfo, ModelManager};
+use core::ocr_engine::{OcrConfig, OcrEngine};
+use core::ocr_processor::{OcrProcessor, OcrProcessorConfig};
+use core::ocr_storage::OcrStorage;
+use core::playback_engine::{ExportSummary, PlaybackEngine, PlaybackInfo, SeekInfo};
+use core::retention::{CleanupReport, RetentionManager, CLEANUP_INTERVAL};
+use core::screen_recorder::{RecordingConfig, RecordingEvent, RecordingStatus, ScreenRecorder};
+use core::search_engine::{OpenFocus, SearchEngine, SearchFilters, SearchMode, SearchQuery, SearchResults};
+use core::self_test::SelfTestReport;
+use core::session_manager::{ProductivityReport, Session, SessionConfig, SessionManager, SessionMetrics, SessionSummary};
 use core::storage::RecordingStorage;
-use models::activity::AppInfo;
+use core::timeline::{TimelineData, DEFAULT_TIMELINE_LIMIT};
+use models::activity::{AppEvent, AppInfo};
 use models::capture::Display;
 use models::input::{KeyboardEvent, KeyboardStats, MouseEvent};
 use chrono;
 use platform::get_platform;
-use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{Manager, State};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, State};
 use uuid::Uuid;
 
-// Timeline data structures
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct TimelineData {
-    pub sessions: Vec<TimelineSession>,
-    pub total_duration: u64,
-    pub date_range: DateRange,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct DateRange {
-    pub start: i64,
-    pub end: i64,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct TimelineSession {
-    pub id: String,
-    pub start_timestamp: i64,
-    pub end_timestamp: Option<i64>,
-    pub session_type: Option<String>,
-    pub applications: Vec<AppUsageSegment>,
-    pub activity_intensity: f32,
-    pub has_screen_recording: bool,
-    pub has_input_recording: bool,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct AppUsageSegment {
-    pub app_name: String,
-    pub bundle_id: String,
-    pub start_timestamp: i64,
-    pub end_timestamp: i64,
-    pub focus_duration: i64,
-    pub color: String,
-}
-
 // Application state
 pub struct AppState {
     pub db: Arc<Database>,
@@ -70,11 +47,98 @@ pub struct AppState {
     pub config: Mutex<Config>,
     pub screen_recorder: Option<ScreenRecorder>,
     pub os_activity_recorder: Option<Arc<OsActivityRecorder>>,
+    pub clipboard_monitor: Option<Arc<ClipboardMonitor>>,
     pub session_manager: Option<Arc<SessionManager>>,
     pub keyboard_recorder: Option<Arc<KeyboardRecorder>>,
     pub input_recorder: Option<Arc<InputRecorder>>,
     pub search_engine: Arc<SearchEngine>,
     pub playback_engine: Option<Arc<PlaybackEngine>>,
+    pub model_manager: Arc<ModelManager>,
+    pub recording_storage: Arc<RecordingStorage>,
+    pub retention_manager: Arc<RetentionManager>,
+    pub ocr_engine: Option<Arc<tokio::sync::RwLock<OcrEngine>>>,
+    pub ocr_processor: Option<Arc<OcrProcessor>>,
+    pub started_at: Instant,
+    /// Last subsystem that failed to initialize, and why
+    pub last_init_error: Option<String>,
+    /// Set once shutdown has started so it only runs once
+    pub shutting_down: AtomicBool,
+    /// Set while the running screen recording is one auto-record started;
+    /// starting or stopping a recording by hand clears it
+    pub auto_recording: AtomicBool,
+}
+
+/// Longest any one subsystem may take to stop before shutdown moves on
+const SHUTDOWN_STEP_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl AppState {
+    /// Stop every recorder, flush buffered input and end the current session
+    ///
+    /// Called on quit. Later calls return immediately, and each step gives up
+    /// after SHUTDOWN_STEP_TIMEOUT so a stuck subsystem can't hold the app open.
+    pub async fn shutdown(&self) {
+        if self.shutting_down.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // Screen recording first: stopping waits for the last frames to encode
+        if let Some(recorder) = &self.screen_recorder {
+            shutdown_step("screen recorder", recorder.stop_recording()).await;
+        }
+        if let Some(recorder) = &self.input_recorder {
+            shutdown_step("input recorder", recorder.stop_recording()).await;
+        }
+        if let Some(recorder) = &self.keyboard_recorder {
+            shutdown_step("keyboard recorder", recorder.stop_recording()).await;
+        }
+        if let Some(recorder) = &self.os_activity_recorder {
+            shutdown_step("OS activity recorder", recorder.stop_recording()).await;
+        }
+        if let Some(monitor) = &self.clipboard_monitor {
+            shutdown_step("clipboard monitor", monitor.stop_recording()).await;
+        }
+        if let Some(processor) = &self.ocr_processor {
+            shutdown_step("OCR processor", processor.stop()).await;
+        }
+
+        // Last, so the session's end covers everything the recorders flushed
+        if let Some(manager) = &self.session_manager {
+            shutdown_step("session monitoring", manager.stop_monitoring()).await;
+            shutdown_step("current session", manager.end_current_session()).await;
+        }
+    }
+}
+
+async fn shutdown_step<E: std::fmt::Display>(name: &str, step: impl Future<Output = Result<(), E>>) {
+    match tokio::time::timeout(SHUTDOWN_STEP_TIMEOUT, step).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("Shutdown: failed to stop {}: {}", name, e),
+        Err(_) => eprintln!("Shutdown: timed out stopping {}", name),
+    }
+}
+
+impl HealthSource for AppState {
+    fn started_at(&self) -> Instant {
+        self.started_at
+    }
+
+    fn subsystems(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("database", true),
+            ("screen_recorder", self.screen_recorder.is_some()),
+            ("os_activity_recorder", self.os_activity_recorder.is_some()),
+            ("clipboard_monitor", self.clipboard_monitor.is_some()),
+            ("session_manager", self.session_manager.is_some()),
+            ("keyboard_recorder", self.keyboard_recorder.is_some()),
+            ("input_recorder", self.input_recorder.is_some()),
+            ("playback_engine", self.playback_engine.is_some()),
+            ("ocr_engine", self.ocr_engine.is_some()),
+        ]
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.last_init_error.clone()
+    }
 }
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -123,6 +187,21 @@ async fn revoke_consent(feature: String, state: State<'_, AppState>) -> Result<(
         .map_err(|e| format!("Failed to revoke consent: {}", e))
 }
 
+#[tauri::command]
+async fn get_consent_history(
+    feature: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ConsentAuditEntry>, String> {
+    let feature = Feature::from_string(&feature)
+        .map_err(|e| format!("Invalid feature: {}", e))?;
+
+    state
+        .consent_manager
+        .get_consent_history(feature)
+        .await
+        .map_err(|e| format!("Failed to get consent history: {}", e))
+}
+
 #[tauri::command]
 async fn get_all_consents(state: State<'_, AppState>) -> Result<HashMap<String, bool>, String> {
     let consents = state
@@ -153,11 +232,22 @@ fn get_config(state: State<'_, AppState>) -> Result<Config, String> {
 
 #[tauri::command]
 fn update_config(config: Config, state: State<'_, AppState>) -> Result<(), String> {
+    let config_path = Config::get_config_path()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+    apply_config(&state, config, &config_path)
+}
+
+/// Validate `config`, then make it current and save it to `config_path`
+fn apply_config(state: &AppState, config: Config, config_path: &Path) -> Result<(), String> {
     // Validate config
     config
         .validate()
         .map_err(|e| format!("Invalid configuration: {}", e))?;
 
+    config
+        .prepare_data_directory()
+        .map_err(|e| format!("Invalid configuration: {}", e))?;
+
     // Update in-memory config
     let mut current_config = state
         .config
@@ -168,9 +258,11 @@ fn update_config(config: Config, state: State<'_, AppState>) -> Result<(), Strin
 
     // Save to disk
     config
-        .save()
+        .save_to(config_path)
         .map_err(|e| format!("Failed to save config: {}", e))?;
 
+    state.recording_storage.set_max_storage_bytes(config.max_storage_bytes);
+
     Ok(())
 }
 
@@ -191,6 +283,30 @@ fn reset_config(state: State<'_, AppState>) -> Result<Config, String> {
 }
 
 // Screen recording commands
+/// Pick the session a recorder writes to
+///
+/// An explicit id must refer to an existing session. Without one, the
+/// SessionManager's current session is used (created if needed), so every
+/// recorder shares one id and cross-modal queries join.
+async fn resolve_session_id(state: &AppState, session_id: Option<String>) -> Result<String, String> {
+    if let Some(id) = session_id {
+        state
+            .db
+            .get_session(&id)
+            .await
+            .map_err(|e| format!("Unknown session {}: {}", id, e))?;
+        return Ok(id);
+    }
+
+    let manager = state.session_manager.as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    manager
+        .get_or_create_session()
+        .await
+        .map_err(|e| format!("Failed to get current session: {}", e))
+}
+
 #[tauri::command]
 async fn get_available_displays(state: State<'_, AppState>) -> Result<Vec<Display>, String> {
     let recorder = state.screen_recorder.as_ref()
@@ -205,15 +321,23 @@ async fn get_available_displays(state: State<'_, AppState>) -> Result<Vec<Displa
 #[tauri::command]
 async fn start_screen_recording(
     display_id: u32,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let recorder = state.screen_recorder.as_ref()
         .ok_or("Screen recorder not initialized")?;
 
+    let session_id = resolve_session_id(&state, session_id).await?;
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
     recorder
-        .start_recording(display_id)
+        .start_recording_for_session(display_id, uuid)
         .await
-        .map_err(|e| format!("Failed to start recording: {}", e))
+        .map_err(|e| format!("Failed to start recording: {}", e))?;
+    state.auto_recording.store(false, Ordering::SeqCst);
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -221,12 +345,187 @@ async fn stop_screen_recording(state: State<'_, AppState>) -> Result<(), String>
     let recorder = state.screen_recorder.as_ref()
         .ok_or("Screen recorder not initialized")?;
 
+    // Whatever auto-record started, the user has taken over
+    state.auto_recording.store(false, Ordering::SeqCst);
+
     recorder
         .stop_recording()
         .await
         .map_err(|e| format!("Failed to stop recording: {}", e))
 }
 
+/// Emit every message of a broadcast channel to the frontend, under the name `event_name` picks
+///
+/// Messages missed while the forwarder lagged behind are skipped rather than
+/// ending the forwarding.
+fn spawn_forwarder<T>(
+    app_handle: tauri::AppHandle,
+    mut rx: tokio::sync::broadcast::Receiver<T>,
+    event_name: fn(&T) -> &'static str,
+) where
+    T: Clone + serde::Serialize + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(message) => {
+                    let _ = app_handle.emit(event_name(&message), message);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Start and stop screen recording as configured trigger apps gain and lose focus
+///
+/// Focus changes arrive from the OS activity recorder, so this only reacts
+/// while OS monitoring is running. Only recordings auto-record started are
+/// stopped; once the user starts or stops one by hand, it is left alone.
+fn spawn_auto_record(app_handle: tauri::AppHandle, mut focus_rx: tokio::sync::broadcast::Receiver<AppEvent>) {
+    tauri::async_runtime::spawn(async move {
+        let mut policy = AutoRecordPolicy::new();
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                event = focus_rx.recv() => {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    };
+                    let Some(state) = app_handle.try_state::<AppState>() else { continue };
+                    let config = auto_record_config(&state);
+                    policy.on_focus(&config, &event.app_info.name, event.timestamp);
+                }
+                _ = interval.tick() => {
+                    let Some(state) = app_handle.try_state::<AppState>() else { continue };
+                    let config = auto_record_config(&state);
+                    let now = chrono::Utc::now().timestamp_millis();
+
+                    if policy.is_active() && !state.auto_recording.load(Ordering::SeqCst) {
+                        policy.stopped();
+                    }
+
+                    match policy.on_tick(&config, now) {
+                        Some(AutoRecordAction::Start { app_name }) => {
+                            match auto_start_recording(&state).await {
+                                Ok(session_id) => {
+                                    state.auto_recording.store(true, Ordering::SeqCst);
+                                    policy.started();
+                                    let _ = app_handle.emit("auto-recording-started", AutoRecordEvent {
+                                        session_id,
+                                        app_name: Some(app_name),
+                                    });
+                                }
+                                Err(e) => {
+                                    eprintln!("Auto-record did not start: {}", e);
+                                    policy.stopped();
+                                }
+                            }
+                        }
+                        Some(AutoRecordAction::Stop) => {
+                            policy.stopped();
+                            if !state.auto_recording.swap(false, Ordering::SeqCst) {
+                                continue;
+                            }
+                            let Some(recorder) = state.screen_recorder.as_ref() else { continue };
+                            let session_id = recorder
+                                .get_status()
+                                .await
+                                .ok()
+                                .and_then(|status| status.session_id)
+                                .map(|id| id.to_string())
+                                .unwrap_or_default();
+                            if let Err(e) = recorder.stop_recording().await {
+                                eprintln!("Auto-record failed to stop recording: {}", e);
+                                continue;
+                            }
+                            let _ = app_handle.emit("auto-recording-stopped", AutoRecordEvent {
+                                session_id,
+                                app_name: None,
+                            });
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn auto_record_config(state: &AppState) -> AutoRecordConfig {
+    state
+        .config
+        .lock()
+        .map(|config| config.auto_record.clone())
+        .unwrap_or_default()
+}
+
+/// Start recording the primary display, unless a recording is already running
+async fn auto_start_recording(state: &AppState) -> Result<String, String> {
+    let recorder = state.screen_recorder.as_ref()
+        .ok_or("Screen recorder not initialized")?;
+
+    if recorder.is_recording().await {
+        return Err("A recording is already running".to_string());
+    }
+
+    let has_consent = state
+        .consent_manager
+        .is_consent_granted(Feature::ScreenRecording)
+        .await
+        .map_err(|e| format!("Consent check failed: {}", e))?;
+    if !has_consent {
+        return Err("ScreenRecording consent not granted".to_string());
+    }
+
+    let displays = recorder
+        .get_available_displays()
+        .await
+        .map_err(|e| format!("Failed to get displays: {}", e))?;
+    let display = displays
+        .iter()
+        .find(|d| d.is_primary)
+        .or_else(|| displays.first())
+        .ok_or("No displays available")?;
+
+    let session_id = resolve_session_id(state, None).await?;
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    recorder
+        .start_recording_for_session(display.id, uuid)
+        .await
+        .map_err(|e| format!("Failed to start recording: {}", e))?;
+
+    Ok(session_id)
+}
+
+#[tauri::command]
+async fn get_recording_config(state: State<'_, AppState>) -> Result<RecordingConfig, String> {
+    let recorder = state.screen_recorder.as_ref()
+        .ok_or("Screen recorder not initialized")?;
+
+    Ok(recorder.config())
+}
+
+#[tauri::command]
+async fn update_recording_config(
+    config: RecordingConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let recorder = state.screen_recorder.as_ref()
+        .ok_or("Screen recorder not initialized")?;
+
+    recorder
+        .update_config(config)
+        .await
+        .map_err(|e| format!("Failed to update recording config: {}", e))
+}
+
 #[tauri::command]
 async fn get_recording_status(state: State<'_, AppState>) -> Result<RecordingStatus, String> {
     let recorder = state.screen_recorder.as_ref()
@@ -241,12 +540,14 @@ async fn get_recording_status(state: State<'_, AppState>) -> Result<RecordingSta
 // OS monitoring commands
 #[tauri::command]
 async fn start_os_monitoring(
-    session_id: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let recorder = state.os_activity_recorder.as_ref()
         .ok_or("OS activity recorder not initialized")?;
 
+    let session_id = resolve_session_id(&state, session_id).await?;
+
     recorder
         .start_recording(session_id)
         .await
@@ -300,6 +601,48 @@ async fn get_current_application(state: State<'_, AppState>) -> Result<Option<Ap
         .map_err(|e| format!("Failed to get current app: {}", e))
 }
 
+// Clipboard monitoring commands
+#[tauri::command]
+async fn start_clipboard_monitoring(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let monitor = state.clipboard_monitor.as_ref()
+        .ok_or("Clipboard monitor not initialized")?;
+
+    let session_id = resolve_session_id(&state, session_id).await?;
+
+    monitor
+        .start_recording(session_id)
+        .await
+        .map_err(|e| format!("Failed to start clipboard monitoring: {}", e))
+}
+
+#[tauri::command]
+async fn stop_clipboard_monitoring(state: State<'_, AppState>) -> Result<(), String> {
+    let monitor = state.clipboard_monitor.as_ref()
+        .ok_or("Clipboard monitor not initialized")?;
+
+    monitor
+        .stop_recording()
+        .await
+        .map_err(|e| format!("Failed to stop clipboard monitoring: {}", e))
+}
+
+#[tauri::command]
+async fn get_clipboard_events(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ClipboardEvent>, String> {
+    let monitor = state.clipboard_monitor.as_ref()
+        .ok_or("Clipboard monitor not initialized")?;
+
+    monitor
+        .get_clipboard_events(&session_id)
+        .await
+        .map_err(|e| format!("Failed to get clipboard events: {}", e))
+}
+
 // Session management commands
 #[tauri::command]
 async fn get_current_session(state: State<'_, AppState>) -> Result<Option<Session>, String> {
@@ -316,15 +659,21 @@ async fn get_current_session(state: State<'_, AppState>) -> Result<Option<Sessio
 async fn get_session_history(
     start: i64,
     end: i64,
+    limit: Option<u32>,
+    offset: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<Vec<Session>, String> {
+) -> Result<RangePage<Session>, String> {
     let manager = state.session_manager.as_ref()
         .ok_or("Session manager not initialized")?;
 
-    manager
-        .get_sessions_in_range(start, end)
+    let limit = effective_limit(limit);
+    let offset = offset.unwrap_or(0);
+    let sessions = manager
+        .get_sessions_in_range_page(start, end, limit + 1, offset)
         .await
-        .map_err(|e| format!("Failed to get session history: {}", e))
+        .map_err(|e| format!("Failed to get session history: {}", e))?;
+
+    Ok(RangePage::new(sessions, limit, offset))
 }
 
 #[tauri::command]
@@ -341,6 +690,20 @@ async fn get_session_metrics(
         .map_err(|e| format!("Failed to get session metrics: {}", e))
 }
 
+#[tauri::command]
+async fn get_session_summary(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<SessionSummary, String> {
+    let manager = state.session_manager.as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    manager
+        .get_session_summary(&session_id)
+        .await
+        .map_err(|e| format!("Failed to get session summary: {}", e))
+}
+
 #[tauri::command]
 async fn classify_session(
     session_id: String,
@@ -350,13 +713,60 @@ async fn classify_session(
         .ok_or("Session manager not initialized")?;
 
     let session_type = manager
-        .classify_session_type(&session_id)
+        .classify_and_store_session_type(&session_id)
         .await
         .map_err(|e| format!("Failed to classify session: {}", e))?;
 
     Ok(session_type.to_string().to_string())
 }
 
+#[tauri::command]
+async fn get_productivity_report(
+    start: i64,
+    end: i64,
+    state: State<'_, AppState>,
+) -> Result<ProductivityReport, String> {
+    let manager = state.session_manager.as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .clone();
+
+    manager
+        .get_productivity_report(start, end, &config)
+        .await
+        .map_err(|e| format!("Failed to get productivity report: {}", e))
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ReclassifyProgress {
+    completed: u32,
+    total: u32,
+}
+
+#[tauri::command]
+async fn reclassify_all_sessions(
+    start: i64,
+    end: i64,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<u32, String> {
+    let manager = state
+        .session_manager
+        .as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    manager
+        .reclassify_sessions_in_range(start, end, |completed, total| {
+            let _ = app.emit("session-reclassify-progress", ReclassifyProgress { completed, total });
+        })
+        .await
+        .map_err(|e| format!("Failed to reclassify sessions: {}", e))
+}
+
 #[tauri::command]
 async fn end_current_session(state: State<'_, AppState>) -> Result<(), String> {
     let manager = state.session_manager.as_ref()
@@ -368,6 +778,35 @@ async fn end_current_session(state: State<'_, AppState>) -> Result<(), String> {
         .map_err(|e| format!("Failed to end session: {}", e))
 }
 
+#[tauri::command]
+async fn merge_sessions(
+    session_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Session, String> {
+    let manager = state.session_manager.as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    manager
+        .merge_sessions(session_ids)
+        .await
+        .map_err(|e| format!("Failed to merge sessions: {}", e))
+}
+
+#[tauri::command]
+async fn split_session(
+    session_id: String,
+    at_timestamp: i64,
+    state: State<'_, AppState>,
+) -> Result<Session, String> {
+    let manager = state.session_manager.as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    manager
+        .split_session(&session_id, at_timestamp)
+        .await
+        .map_err(|e| format!("Failed to split session: {}", e))
+}
+
 #[tauri::command]
 async fn start_session_monitoring(state: State<'_, AppState>) -> Result<(), String> {
     let manager = state.session_manager.as_ref()
@@ -393,12 +832,21 @@ async fn stop_session_monitoring(state: State<'_, AppState>) -> Result<(), Strin
 // Keyboard recording commands
 #[tauri::command]
 async fn start_keyboard_recording(
-    session_id: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let recorder = state.keyboard_recorder.as_ref()
         .ok_or("Keyboard recorder not initialized")?;
 
+    let session_id = resolve_session_id(&state, session_id).await?;
+
+    let redact_sensitive_input = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .redact_sensitive_input;
+    recorder.set_redact_sensitive_input(redact_sensitive_input);
+
     recorder
         .start_recording(session_id)
         .await
@@ -441,7 +889,7 @@ async fn is_keyboard_recording(state: State<'_, AppState>) -> Result<bool, Strin
 // Input recording commands
 #[tauri::command]
 async fn start_input_recording(
-    session_id: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let recorder = state
@@ -449,6 +897,25 @@ async fn start_input_recording(
         .as_ref()
         .ok_or("Input recorder not initialized")?;
 
+    let session_id = resolve_session_id(&state, session_id).await?;
+
+    let (custom_shortcuts, redact_sensitive_input, mouse_sample_interval_ms, input_flush_interval_secs) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("Failed to lock config: {}", e))?;
+        (
+            config.custom_shortcuts.clone(),
+            config.redact_sensitive_input,
+            config.mouse_sample_interval_ms,
+            config.input_flush_interval_secs,
+        )
+    };
+    recorder.set_custom_shortcuts(custom_shortcuts).await;
+    recorder.set_redact_sensitive_input(redact_sensitive_input);
+    recorder.set_mouse_sample_interval(mouse_sample_interval_ms);
+    recorder.set_flush_interval(std::time::Duration::from_secs(input_flush_interval_secs));
+
     recorder
         .start_recording(session_id)
         .await
@@ -478,6 +945,32 @@ async fn is_input_recording(state: State<'_, AppState>) -> Result<bool, String>
     Ok(recorder.is_recording().await)
 }
 
+#[tauri::command]
+async fn get_mouse_heatmap(
+    session_id: String,
+    grid_width: u32,
+    grid_height: u32,
+    state: State<'_, AppState>,
+) -> Result<Vec<Vec<u32>>, String> {
+    let recorder = state
+        .input_recorder
+        .as_ref()
+        .ok_or("Input recorder not initialized")?;
+
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+    let display_size = state
+        .recording_storage
+        .get_source_dimensions(&uuid)
+        .await
+        .map_err(|e| format!("Failed to get source dimensions: {}", e))?;
+
+    recorder
+        .get_mouse_heatmap(session_id, display_size, grid_width, grid_height)
+        .await
+        .map_err(|e| format!("Failed to get mouse heatmap: {}", e))
+}
+
 #[tauri::command]
 async fn cleanup_old_input_events(
     retention_days: u32,
@@ -494,6 +987,43 @@ async fn cleanup_old_input_events(
         .map_err(|e| format!("Failed to cleanup old events: {}", e))
 }
 
+/// Apply every data type's retention window now instead of waiting for the schedule
+#[tauri::command]
+async fn run_cleanup_now(state: State<'_, AppState>) -> Result<CleanupReport, String> {
+    run_retention(&state).await
+}
+
+async fn run_retention(state: &AppState) -> Result<CleanupReport, String> {
+    let retention_days = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .retention_days
+        .clone();
+
+    state
+        .retention_manager
+        .run_cleanup(&retention_days, chrono::Utc::now().timestamp_millis())
+        .await
+        .map_err(|e| format!("Failed to run cleanup: {}", e))
+}
+
+/// Run the retention cleanup every CLEANUP_INTERVAL, starting one interval after launch
+fn spawn_retention(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let start = tokio::time::Instant::now() + CLEANUP_INTERVAL;
+        let mut interval = tokio::time::interval_at(start, CLEANUP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            let Some(state) = app_handle.try_state::<AppState>() else { continue };
+            if let Err(e) = run_retention(&state).await {
+                eprintln!("Scheduled cleanup failed: {}", e);
+            }
+        }
+    });
+}
+
 // Command analyzer commands
 #[tauri::command]
 async fn get_command_stats(
@@ -526,27 +1056,102 @@ async fn get_most_used_shortcuts(
         .collect())
 }
 
-// Search engine commands
+// OCR commands
 #[tauri::command]
-async fn search_text(
-    query: String,
-    filters: SearchFilters,
-    limit: u32,
-    offset: u32,
-    state: State<'_, AppState>,
-) -> Result<SearchResults, String> {
-    state
-        .search_engine
-        .search(SearchQuery {
-            query,
-            filters,
-            limit,
-            offset,
-        })
-        .await
-        .map_err(|e| format!("Search failed: {}", e))
-}
-
+fn get_ocr_languages(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+
+    Ok(config.ocr_languages.clone())
+}
+
+#[tauri::command]
+async fn set_ocr_languages(languages: Vec<String>, state: State<'_, AppState>) -> Result<(), String> {
+    if languages.is_empty() {
+        return Err("OCR languages cannot be empty".to_string());
+    }
+
+    // Reload the engine first so a missing language pack leaves the config untouched
+    if let Some(engine) = &state.ocr_engine {
+        engine
+            .write()
+            .await
+            .set_languages(languages.clone())
+            .map_err(|e| format!("Failed to load OCR languages: {}", e))?;
+    }
+
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+
+    config.ocr_languages = languages;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn reprocess_ocr(session_id: String, state: State<'_, AppState>) -> Result<u64, String> {
+    let processor = state
+        .ocr_processor
+        .as_ref()
+        .ok_or("OCR processor not initialized")?;
+
+    let session_id = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    processor
+        .reprocess_session(session_id)
+        .await
+        .map_err(|e| format!("Failed to reprocess OCR: {}", e))
+}
+
+// Search engine commands
+
+/// The app in focus in the session being recorded, for search to attribute new text to
+async fn open_focus(state: &AppState) -> Option<OpenFocus> {
+    let (session_id, span) = state.os_activity_recorder.as_ref()?.open_focus_span().await?;
+
+    Some(OpenFocus {
+        session_id: Uuid::parse_str(&session_id).ok()?,
+        app_name: span.app_name,
+        start_timestamp: span.start_time,
+    })
+}
+
+#[tauri::command]
+async fn search_text(
+    query: String,
+    mode: Option<SearchMode>,
+    mut filters: SearchFilters,
+    limit: u32,
+    offset: u32,
+    highlight: Option<bool>,
+    snippet_chars: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<SearchResults, String> {
+    filters.open_focus = open_focus(&state).await;
+
+    state
+        .search_engine
+        .search(SearchQuery {
+            query,
+            mode: mode.unwrap_or_default(),
+            filters,
+            limit,
+            offset,
+            highlight: highlight.unwrap_or(false),
+            snippet_chars: snippet_chars.unwrap_or(50),
+        })
+        .await
+        .map_err(|e| format!("Search failed: {}", e))
+}
+
 #[tauri::command]
 async fn search_suggestions(
     partial: String,
@@ -572,12 +1177,16 @@ async fn search_in_session(
         .search_engine
         .search(SearchQuery {
             query,
+            mode: SearchMode::Substring,
             filters: SearchFilters {
                 session_ids: Some(vec![session_uuid]),
+                open_focus: open_focus(&state).await,
                 ..Default::default()
             },
             limit: 50,
             offset: 0,
+            highlight: false,
+            snippet_chars: 50,
         })
         .await
         .map_err(|e| format!("Search failed: {}", e))
@@ -588,141 +1197,70 @@ async fn search_in_session(
 async fn get_timeline_data(
     start_timestamp: i64,
     end_timestamp: i64,
+    limit: Option<u32>,
+    offset: Option<u32>,
     state: State<'_, AppState>,
 ) -> Result<TimelineData, String> {
-    let manager = state
-        .session_manager
-        .as_ref()
-        .ok_or("Session manager not initialized")?;
-
-    // Get sessions in range
-    let sessions = manager
-        .get_sessions_in_range(start_timestamp, end_timestamp)
-        .await
-        .map_err(|e| format!("Failed to get sessions: {}", e))?;
-
-    let mut timeline_sessions = Vec::new();
-
-    for session in &sessions {
-        // Get app usage for this session
-        let apps = get_app_usage_for_session(&state.db, &session.id).await?;
-
-        // Convert to AppUsageSegments with colors
-        let app_segments: Vec<AppUsageSegment> = apps
-            .into_iter()
-            .map(|app| AppUsageSegment {
-                app_name: app.app_name.clone(),
-                bundle_id: app.bundle_id.clone(),
-                start_timestamp: app.start_timestamp,
-                end_timestamp: app.end_timestamp.unwrap_or(chrono::Utc::now().timestamp_millis()),
-                focus_duration: app.focus_duration_ms,
-                color: app_color(&app.app_name),
-            })
-            .collect();
-
-        // Calculate activity intensity
-        let activity_intensity = calculate_activity_intensity(&app_segments);
-
-        // Check for recordings
-        let has_screen_recording = check_has_screen_recording(&state.db, &session.id).await?;
-        let has_input_recording = check_has_input_recording(&state.db, &session.id).await?;
-
-        timeline_sessions.push(TimelineSession {
-            id: session.id.clone(),
-            start_timestamp: session.start_timestamp,
-            end_timestamp: session.end_timestamp,
-            session_type: session.session_type.clone(),
-            applications: app_segments,
-            activity_intensity,
-            has_screen_recording,
-            has_input_recording,
-        });
-    }
-
-    // Calculate total duration
-    let total_duration: u64 = timeline_sessions
-        .iter()
-        .map(|s| {
-            let end = s.end_timestamp.unwrap_or(chrono::Utc::now().timestamp_millis());
-            (end - s.start_timestamp) as u64
-        })
-        .sum();
-
-    Ok(TimelineData {
-        sessions: timeline_sessions,
-        total_duration,
-        date_range: DateRange {
-            start: start_timestamp,
-            end: end_timestamp,
-        },
-    })
-}
-
-// Helper functions for timeline
-async fn get_app_usage_for_session(
-    db: &Arc<Database>,
-    session_id: &str,
-) -> Result<Vec<core::os_activity::AppUsage>, String> {
-    sqlx::query_as::<_, core::os_activity::AppUsage>(
-        r#"
-        SELECT id, session_id, app_name, bundle_id, process_id,
-               start_timestamp, end_timestamp, focus_duration_ms, background_duration_ms
-        FROM app_usage
-        WHERE session_id = ?
-        ORDER BY start_timestamp ASC
-        "#
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .clone();
+
+    core::timeline::load_timeline(
+        &state.db,
+        &config,
+        start_timestamp,
+        end_timestamp,
+        limit.unwrap_or(DEFAULT_TIMELINE_LIMIT),
+        offset.unwrap_or(0),
     )
-    .bind(session_id)
-    .fetch_all(&db.pool)
     .await
-    .map_err(|e| format!("Failed to get app usage: {}", e))
+    .map_err(|e| format!("Failed to load timeline: {}", e))
 }
 
-async fn check_has_screen_recording(db: &Arc<Database>, session_id: &str) -> Result<bool, String> {
-    let count: i64 = sqlx::query_scalar(
-        r#"
-        SELECT COUNT(*) FROM screen_recordings WHERE session_id = ?
-        "#,
-    )
-    .bind(session_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| format!("Failed to check screen recording: {}", e))?;
+/// Set or clear (with `None`) the timeline color for an app
+#[tauri::command]
+fn set_app_color(app_name: String, color: Option<String>, state: State<'_, AppState>) -> Result<(), String> {
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
 
-    Ok(count > 0)
-}
+    let mut updated = config.clone();
+    match color {
+        Some(color) => updated.color_overrides.insert(app_name, color),
+        None => updated.color_overrides.remove(&app_name),
+    };
 
-async fn check_has_input_recording(db: &Arc<Database>, session_id: &str) -> Result<bool, String> {
-    let count: i64 = sqlx::query_scalar(
-        r#"
-        SELECT COUNT(*) FROM keyboard_events WHERE session_id = ? LIMIT 1
-        "#,
-    )
-    .bind(session_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| format!("Failed to check input recording: {}", e))?;
+    updated
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+    *config = updated;
 
-    Ok(count > 0)
+    Ok(())
 }
 
-fn app_color(app_name: &str) -> String {
-    let mut hasher = DefaultHasher::new();
-    app_name.hash(&mut hasher);
-    let hash = hasher.finish();
-
-    let hue = (hash % 360) as f32;
-    let saturation = 70.0;
-    let lightness = 60.0;
+#[tauri::command]
+async fn get_session_coverage(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<SessionCoverage, String> {
+    state
+        .db
+        .get_session_coverage(&session_id)
+        .await
+        .map_err(|e| format!("Failed to get session coverage: {}", e))
+}
 
-    format!("hsl({}, {}%, {}%)", hue, saturation, lightness)
+#[tauri::command]
+fn get_observer_status(state: State<'_, AppState>) -> ObserverStatus {
+    core::health::collect_status(state.inner())
 }
 
-fn calculate_activity_intensity(apps: &[AppUsageSegment]) -> f32 {
-    // Calculate based on number of app switches
-    let app_switches = apps.len() as f32;
-    let normalized = (app_switches / 20.0).min(1.0); // Cap at 20 switches
-    normalized
+#[tauri::command]
+async fn run_self_test(state: State<'_, AppState>) -> Result<SelfTestReport, String> {
+    Ok(core::self_test::run_self_test(&state.db, state.screen_recorder.as_ref()).await)
 }
 
 // Input event DTOs for overlay
@@ -784,14 +1322,61 @@ struct MouseEventRow {
     button: Option<String>,
 }
 
+// Range query pagination
+/// Rows returned by a range query when no limit is given
+const DEFAULT_RANGE_LIMIT: u32 = 1000;
+/// Hard cap on rows returned by a single range query
+const MAX_RANGE_LIMIT: u32 = 10_000;
+
+/// One page of a range query; `TimelineData` pages with the same
+/// `hasMore`/`nextOffset` fields
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RangePage<T> {
+    items: Vec<T>,
+    has_more: bool,
+    /// Offset to pass for the next page, when there is one
+    next_offset: Option<u32>,
+}
+
+impl<T> RangePage<T> {
+    /// Build a page from rows fetched with one extra look-ahead row
+    fn new(mut rows: Vec<T>, limit: u32, offset: u32) -> Self {
+        let has_more = rows.len() > limit as usize;
+        rows.truncate(limit as usize);
+
+        Self {
+            next_offset: has_more.then(|| offset + limit),
+            items: rows,
+            has_more,
+        }
+    }
+
+    fn map<U>(self, f: impl FnMut(T) -> U) -> RangePage<U> {
+        RangePage {
+            items: self.items.into_iter().map(f).collect(),
+            has_more: self.has_more,
+            next_offset: self.next_offset,
+        }
+    }
+}
+
+fn effective_limit(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_RANGE_LIMIT).clamp(1, MAX_RANGE_LIMIT)
+}
+
 // Input event range queries for playback overlay
 #[tauri::command]
 async fn get_keyboard_events_in_range(
     session_id: String,
     start_time: i64,
     end_time: i64,
+    limit: Option<u32>,
+    offset: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<Vec<KeyboardEventDto>, String> {
+) -> Result<RangePage<KeyboardEventDto>, String> {
+    let limit = effective_limit(limit);
+    let offset = offset.unwrap_or(0);
     let rows = sqlx::query_as::<_, KeyboardEventRow>(
         r#"
         SELECT id, timestamp, event_type, key_char, key_code,
@@ -801,18 +1386,20 @@ async fn get_keyboard_events_in_range(
         WHERE session_id = ?
           AND timestamp >= ?
           AND timestamp <= ?
-        ORDER BY timestamp ASC
-        LIMIT 100
+        ORDER BY timestamp ASC, id ASC
+        LIMIT ? OFFSET ?
         "#
     )
     .bind(&session_id)
     .bind(start_time)
     .bind(end_time)
+    .bind(limit as i64 + 1)
+    .bind(offset as i64)
     .fetch_all(&state.db.pool)
     .await
     .map_err(|e| format!("Failed to get keyboard events: {}", e))?;
 
-    let events = rows.into_iter().map(|row| KeyboardEventDto {
+    Ok(RangePage::new(rows, limit, offset).map(|row| KeyboardEventDto {
         id: row.id,
         timestamp: row.timestamp,
         event_type: row.event_type,
@@ -825,9 +1412,7 @@ async fn get_keyboard_events_in_range(
             meta: row.modifiers_meta,
         },
         app_name: row.app_name,
-    }).collect();
-
-    Ok(events)
+    }))
 }
 
 #[tauri::command]
@@ -835,8 +1420,12 @@ async fn get_mouse_events_in_range(
     session_id: String,
     start_time: i64,
     end_time: i64,
+    limit: Option<u32>,
+    offset: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<Vec<MouseEventDto>, String> {
+) -> Result<RangePage<MouseEventDto>, String> {
+    let limit = effective_limit(limit);
+    let offset = offset.unwrap_or(0);
     let rows = sqlx::query_as::<_, MouseEventRow>(
         r#"
         SELECT id, timestamp, event_type, position_x, position_y, button
@@ -844,18 +1433,20 @@ async fn get_mouse_events_in_range(
         WHERE session_id = ?
           AND timestamp >= ?
           AND timestamp <= ?
-        ORDER BY timestamp ASC
-        LIMIT 100
+        ORDER BY timestamp ASC, id ASC
+        LIMIT ? OFFSET ?
         "#
     )
     .bind(&session_id)
     .bind(start_time)
     .bind(end_time)
+    .bind(limit as i64 + 1)
+    .bind(offset as i64)
     .fetch_all(&state.db.pool)
     .await
     .map_err(|e| format!("Failed to get mouse events: {}", e))?;
 
-    let events = rows.into_iter().map(|row| MouseEventDto {
+    Ok(RangePage::new(rows, limit, offset).map(|row| MouseEventDto {
         id: row.id,
         timestamp: row.timestamp,
         event_type: row.event_type,
@@ -864,9 +1455,7 @@ async fn get_mouse_events_in_range(
             y: row.position_y,
         },
         button: row.button,
-    }).collect();
-
-    Ok(events)
+    }))
 }
 
 // Playback commands
@@ -909,6 +1498,46 @@ async fn seek_to_timestamp(
         .map_err(|e| format!("Failed to seek: {}", e))
 }
 
+#[tauri::command]
+async fn next_change(
+    session_id: String,
+    after_ts: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<i64>, String> {
+    let engine = state
+        .playback_engine
+        .as_ref()
+        .ok_or("Playback engine not initialized")?;
+
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    engine
+        .next_change(uuid, after_ts)
+        .await
+        .map_err(|e| format!("Failed to find next change: {}", e))
+}
+
+#[tauri::command]
+async fn prev_change(
+    session_id: String,
+    before_ts: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<i64>, String> {
+    let engine = state
+        .playback_engine
+        .as_ref()
+        .ok_or("Playback engine not initialized")?;
+
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    engine
+        .prev_change(uuid, before_ts)
+        .await
+        .map_err(|e| format!("Failed to find previous change: {}", e))
+}
+
 #[tauri::command]
 async fn get_frame_at_timestamp(
     session_id: String,
@@ -929,16 +1558,284 @@ async fn get_frame_at_timestamp(
         .map_err(|e| format!("Failed to get frame: {}", e))
 }
 
+#[tauri::command]
+async fn reconstruct_frame(
+    session_id: String,
+    timestamp: i64,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let engine = state
+        .playback_engine
+        .as_ref()
+        .ok_or("Playback engine not initialized")?;
+
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    engine
+        .reconstruct_frame(uuid, timestamp)
+        .await
+        .map_err(|e| format!("Failed to reconstruct frame: {}", e))
+}
+
+// Unified inspector: everything recorded around one instant
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct SnapshotData {
+    session_id: String,
+    timestamp: i64,
+    frame: Option<SeekInfo>,
+    active_app: Option<String>,
+    recent_keystrokes: Option<String>,
+    mouse_position: Option<PositionDto>,
+    screen_text: Option<String>,
+}
+
+/// How far back to look for keystrokes leading up to the snapshot
+const SNAPSHOT_KEYSTROKE_WINDOW_MS: i64 = 5_000;
+/// How far back a mouse position or OCR result still counts as current
+const SNAPSHOT_STALE_AFTER_MS: i64 = 60_000;
+
+#[tauri::command]
+async fn get_snapshot_at(
+    session_id: String,
+    timestamp: i64,
+    state: State<'_, AppState>,
+) -> Result<SnapshotData, String> {
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+    let pool = &state.db.pool;
+
+    // Each subsystem is optional; missing tables or data just leave the field empty
+    let frame = match state.playback_engine.as_ref() {
+        Some(engine) => engine.seek_to_timestamp(uuid, timestamp).await.ok(),
+        None => None,
+    };
+
+    let active_app: Option<String> = sqlx::query_scalar(
+        "SELECT app_name FROM app_focus_spans
+         WHERE session_id = ? AND start_timestamp <= ? AND end_timestamp > ?
+         ORDER BY start_timestamp DESC
+         LIMIT 1"
+    )
+    .bind(&session_id)
+    .bind(timestamp)
+    .bind(timestamp)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    let keys: Vec<String> = sqlx::query_scalar(
+        "SELECT key_char FROM keyboard_events
+         WHERE session_id = ? AND timestamp > ? AND timestamp <= ? AND key_char IS NOT NULL
+         ORDER BY timestamp ASC"
+    )
+    .bind(&session_id)
+    .bind(timestamp - SNAPSHOT_KEYSTROKE_WINDOW_MS)
+    .bind(timestamp)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+    let recent_keystrokes = if keys.is_empty() { None } else { Some(keys.concat()) };
+
+    let mouse_position = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT position_x, position_y FROM mouse_events
+         WHERE session_id = ? AND timestamp > ? AND timestamp <= ?
+         ORDER BY timestamp DESC
+         LIMIT 1"
+    )
+    .bind(&session_id)
+    .bind(timestamp - SNAPSHOT_STALE_AFTER_MS)
+    .bind(timestamp)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+    .map(|(x, y)| PositionDto { x, y });
+
+    let screen_text: Option<String> = sqlx::query_scalar(
+        "SELECT text FROM ocr_results
+         WHERE session_id = ? AND timestamp > ? AND timestamp <= ?
+         ORDER BY timestamp DESC
+         LIMIT 1"
+    )
+    .bind(&session_id)
+    .bind(timestamp - SNAPSHOT_STALE_AFTER_MS)
+    .bind(timestamp)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    Ok(SnapshotData {
+        session_id,
+        timestamp,
+        frame,
+        active_app,
+        recent_keystrokes,
+        mouse_position,
+        screen_text,
+    })
+}
+
+#[tauri::command]
+async fn extract_frames(
+    session_id: String,
+    start: i64,
+    end: i64,
+    interval_ms: u64,
+    output_dir: String,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let engine = state
+        .playback_engine
+        .as_ref()
+        .ok_or("Playback engine not initialized")?;
+
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    let paths = engine
+        .extract_frames(uuid, start, end, interval_ms, PathBuf::from(output_dir), move |progress| {
+            let _ = app.emit("frame-extraction-progress", progress);
+        })
+        .await
+        .map_err(|e| format!("Failed to extract frames: {}", e))?;
+
+    Ok(paths
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+#[tauri::command]
+async fn export_session(
+    session_id: String,
+    output_path: String,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ExportSummary, String> {
+    let engine = state
+        .playback_engine
+        .as_ref()
+        .ok_or("Playback engine not initialized")?;
+
+    let uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    tauri::async_runtime::spawn(async move {
+        while let Some(progress) = progress_rx.recv().await {
+            let _ = app.emit("export-progress", progress);
+        }
+    });
+
+    engine
+        .export_session(uuid, PathBuf::from(output_path), progress_tx)
+        .await
+        .map_err(|e| format!("Failed to export session: {}", e))
+}
+
+/// Write everything recorded for a session to one zip, for backup or moving machines
+#[tauri::command]
+async fn export_session_bundle(
+    session_id: String,
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<BundleManifest, String> {
+    let session_manager = state
+        .session_manager
+        .as_ref()
+        .ok_or("Session manager not initialized")?;
+
+    let bundler = SessionBundler::new(state.db.clone(), state.recording_storage.clone())
+        .await
+        .map_err(|e| format!("Failed to prepare bundle: {}", e))?;
+
+    bundler
+        .export(session_manager, &session_id, &PathBuf::from(output_path))
+        .await
+        .map_err(|e| format!("Failed to export session bundle: {}", e))
+}
+
+/// Read a bundle back in as a new session, returning the new session's id
+#[tauri::command]
+async fn import_session_bundle(path: String, state: State<'_, AppState>) -> Result<String, String> {
+    let bundler = SessionBundler::new(state.db.clone(), state.recording_storage.clone())
+        .await
+        .map_err(|e| format!("Failed to prepare bundle: {}", e))?;
+
+    bundler
+        .import(&PathBuf::from(path))
+        .await
+        .map_err(|e| format!("Failed to import session bundle: {}", e))
+}
+
+// Model management commands
+#[tauri::command]
+async fn get_available_models(state: State<'_, AppState>) -> Result<Vec<ModelInfo>, String> {
+    Ok(state.model_manager.list_models())
+}
+
+#[tauri::command]
+async fn download_model(
+    name: String,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let path = state
+        .model_manager
+        .download_model(&name, move |progress| {
+            let _ = app.emit("model-download-progress", progress);
+        })
+        .await
+        .map_err(|e| format!("Failed to download model: {}", e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Bytes used by recording files on disk
+#[tauri::command]
+fn get_disk_usage(state: State<'_, AppState>) -> Result<u64, String> {
+    state
+        .recording_storage
+        .get_disk_usage()
+        .map_err(|e| format!("Failed to get disk usage: {}", e))
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .setup(|app| {
-            // Initialize database, consent manager, config, and screen recorder
+            // Initialize config, database, consent manager, and screen recorder
             tauri::async_runtime::block_on(async {
+                let started_at = Instant::now();
+                let mut last_init_error = None;
+
+                let mut config = Config::load()
+                    .expect("Failed to load configuration");
+                let platform = get_platform();
+
+                // The configured data directory, if any, replaces the platform default everywhere.
+                // If it is unavailable (an unplugged drive, say) this run uses the defaults; the
+                // setting is left alone so it applies again once the directory is back.
+                let platform_data_dir = platform.get_data_directory()
+                    .expect("Failed to get data directory");
+                let mut paths = config.clone();
+                if let Err(e) = config.prepare_data_directory() {
+                    eprintln!("Warning: {}; using the default data directory", e);
+                    last_init_error = Some(format!("data directory: {}", e));
+                    paths.data_directory = None;
+                }
+                let data_dir = paths.data_dir(platform_data_dir.clone());
+
+                let default_db_path = Database::get_db_path()
+                    .expect("Failed to get database path");
                 let db = Arc::new(
-                    Database::init()
+                    Database::init_at(&paths.database_path(default_db_path))
                         .await
                         .expect("Failed to initialize database")
                 );
@@ -949,29 +1846,49 @@ pub fn run() {
                         .expect("Failed to initialize consent manager")
                 );
 
-                let config = Config::load()
-                    .expect("Failed to load configuration");
+                // Pin the device id on first run so sessions keep one id for this machine
+                let (device_id, device_id_added) = config.ensure_device_id(|| platform.get_device_id().ok());
+                if device_id_added {
+                    if let Err(e) = config.save() {
+                        eprintln!("Warning: Failed to save device id: {}", e);
+                    }
+                }
 
                 // Initialize recording storage
-                let platform = get_platform();
-                let data_dir = platform.get_data_directory()
-                    .expect("Failed to get data directory");
-                let recordings_path = data_dir.join("recordings");
-
+                let recordings_path = paths.recordings_path(platform_data_dir);
                 let storage = Arc::new(
                     RecordingStorage::new(recordings_path, db.clone())
                         .await
                         .expect("Failed to initialize recording storage")
                 );
+                let retention_manager = Arc::new(RetentionManager::new(db.clone(), storage.clone()));
+                storage.set_max_storage_bytes(config.max_storage_bytes);
+                match storage.enforce_quota().await {
+                    Ok(evicted) if !evicted.is_empty() => {
+                        println!("Storage quota exceeded, deleted {} old session(s)", evicted.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Warning: Failed to enforce storage quota: {}", e),
+                }
 
                 // Try to initialize screen recorder (may fail on some platforms)
                 let screen_recorder = match ScreenRecorder::new(consent_manager.clone(), storage.clone()).await {
                     Ok(recorder) => {
                         println!("Screen recorder initialized successfully");
+
+                        // Forward recorder events to the frontend
+                        spawn_forwarder(app.handle().clone(), recorder.subscribe_events(), |event| match event {
+                            RecordingEvent::SuspendedIdle { .. } => "recording-suspended-idle",
+                            RecordingEvent::ResumedFromIdle { .. } => "recording-resumed-idle",
+                            RecordingEvent::CaptureRecovered { .. } => "recording-capture-recovered",
+                        });
+                        spawn_forwarder(app.handle().clone(), recorder.subscribe_progress(), |_| "recording-progress");
+
                         Some(recorder)
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to initialize screen recorder: {}", e);
+                        last_init_error = Some(format!("screen recorder: {}", e));
                         eprintln!("Screen recording features will be unavailable");
                         None
                     }
@@ -981,23 +1898,44 @@ pub fn run() {
                 let os_activity_recorder = match OsActivityRecorder::new(consent_manager.clone(), db.clone()).await {
                     Ok(recorder) => {
                         println!("OS activity recorder initialized successfully");
+                        spawn_auto_record(app.handle().clone(), recorder.subscribe_focus());
                         Some(Arc::new(recorder))
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to initialize OS activity recorder: {}", e);
+                        last_init_error = Some(format!("OS activity recorder: {}", e));
                         eprintln!("OS activity monitoring features will be unavailable");
                         None
                     }
                 };
 
+                // Initialize clipboard monitor
+                let clipboard_monitor = match ClipboardMonitor::new(consent_manager.clone(), db.clone()).await {
+                    Ok(monitor) => {
+                        println!("Clipboard monitor initialized successfully");
+                        Some(Arc::new(monitor))
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Failed to initialize clipboard monitor: {}", e);
+                        last_init_error = Some(format!("clipboard monitor: {}", e));
+                        eprintln!("Clipboard monitoring features will be unavailable");
+                        None
+                    }
+                };
+
                 // Initialize session manager
-                let session_manager = match SessionManager::new(db.clone(), SessionConfig::default()).await {
+                let session_manager = match SessionManager::new(db.clone(), SessionConfig::default(), device_id).await {
                     Ok(manager) => {
                         println!("Session manager initialized successfully");
+
+                        // Forward live metrics to the frontend
+                        spawn_forwarder(app.handle().clone(), manager.subscribe_metrics(), |_| "session-metrics-updated");
+
                         Some(Arc::new(manager))
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to initialize session manager: {}", e);
+                        last_init_error = Some(format!("session manager: {}", e));
                         eprintln!("Session management features will be unavailable");
                         None
                     }
@@ -1011,6 +1949,7 @@ pub fn run() {
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to initialize keyboard recorder: {}", e);
+                        last_init_error = Some(format!("keyboard recorder: {}", e));
                         eprintln!("Keyboard recording features will be unavailable");
                         None
                     }
@@ -1024,11 +1963,22 @@ pub fn run() {
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to initialize input recorder: {}", e);
+                        last_init_error = Some(format!("input recorder: {}", e));
                         eprintln!("Input recording features will be unavailable");
                         None
                     }
                 };
 
+                // Feed recorder activity into the session manager's live metrics
+                if let Some(manager) = &session_manager {
+                    if let Some(recorder) = &os_activity_recorder {
+                        recorder.set_activity_sink(manager.activity_sender()).await;
+                    }
+                    if let Some(recorder) = &input_recorder {
+                        recorder.set_activity_sink(manager.activity_sender()).await;
+                    }
+                }
+
                 // Initialize search engine
                 let search_engine = Arc::new(SearchEngine::new(db.clone()));
                 println!("Search engine initialized successfully");
@@ -1037,20 +1987,66 @@ pub fn run() {
                 let playback_engine = Arc::new(PlaybackEngine::new(storage.clone(), db.clone()));
                 println!("Playback engine initialized successfully");
 
+                let model_manager = Arc::new(ModelManager::new(data_dir.join("models"), &config.model_manifest));
+
+                // Try to initialize OCR engine with the configured language packs
+                let ocr_config = OcrConfig {
+                    languages: config.ocr_languages.clone(),
+                    confidence_threshold: config.ocr_confidence_threshold,
+                    ..OcrConfig::for_screenshots()
+                };
+                let ocr_engine = match OcrEngine::new(ocr_config) {
+                    Ok(engine) => {
+                        println!("OCR engine initialized with languages: {}", engine.active_languages().join(", "));
+                        Some(Arc::new(tokio::sync::RwLock::new(engine)))
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Failed to initialize OCR engine: {}", e);
+                        last_init_error = Some(format!("OCR engine: {}", e));
+                        eprintln!("OCR features will be unavailable");
+                        None
+                    }
+                };
+
+                let ocr_processor = ocr_engine.as_ref().map(|engine| {
+                    let processor_config = OcrProcessorConfig {
+                        enabled: config.ocr_enabled,
+                        interval_seconds: config.ocr_interval_seconds,
+                        ..OcrProcessorConfig::default()
+                    };
+                    Arc::new(OcrProcessor::new(
+                        engine.clone(),
+                        Arc::new(OcrStorage::new(db.clone())),
+                        processor_config,
+                    ))
+                });
+
                 app.manage(AppState {
                     db,
                     consent_manager,
                     config: Mutex::new(config),
                     screen_recorder,
                     os_activity_recorder,
+                    clipboard_monitor,
                     session_manager,
                     keyboard_recorder,
                     input_recorder,
                     search_engine,
                     playback_engine: Some(playback_engine),
+                    model_manager,
+                    recording_storage: storage,
+                    retention_manager,
+                    ocr_engine,
+                    ocr_processor,
+                    started_at,
+                    last_init_error,
+                    shutting_down: AtomicBool::new(false),
+                    auto_recording: AtomicBool::new(false),
                 });
             });
 
+            spawn_retention(app.handle().clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1059,6 +2055,7 @@ pub fn run() {
             request_consent,
             revoke_consent,
             get_all_consents,
+            get_consent_history,
             get_config,
             update_config,
             reset_config,
@@ -1066,16 +2063,26 @@ pub fn run() {
             start_screen_recording,
             stop_screen_recording,
             get_recording_status,
+            get_recording_config,
+            update_recording_config,
             start_os_monitoring,
             stop_os_monitoring,
             get_app_usage_stats,
             get_running_applications,
             get_current_application,
+            start_clipboard_monitoring,
+            stop_clipboard_monitoring,
+            get_clipboard_events,
             get_current_session,
             get_session_history,
             get_session_metrics,
+            get_session_summary,
             classify_session,
+            reclassify_all_sessions,
+            get_productivity_report,
             end_current_session,
+            merge_sessions,
+            split_session,
             start_session_monitoring,
             stop_session_monitoring,
             start_keyboard_recording,
@@ -1086,18 +2093,207 @@ pub fn run() {
             stop_input_recording,
             is_input_recording,
             cleanup_old_input_events,
+            run_cleanup_now,
+            get_mouse_heatmap,
             get_command_stats,
             get_most_used_shortcuts,
             search_text,
             search_suggestions,
             search_in_session,
             get_timeline_data,
+            get_session_coverage,
+            run_self_test,
+            get_observer_status,
             get_keyboard_events_in_range,
             get_mouse_events_in_range,
             get_playback_info,
             seek_to_timestamp,
-            get_frame_at_timestamp
+            next_change,
+            prev_change,
+            get_frame_at_timestamp,
+            reconstruct_frame,
+            extract_frames,
+            export_session,
+            export_session_bundle,
+            import_session_bundle,
+            get_snapshot_at,
+            get_available_models,
+            download_model,
+            get_ocr_languages,
+            set_ocr_languages,
+            reprocess_ocr,
+            set_app_color,
+            get_disk_usage
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Every way of quitting ends here, after the windows are gone
+            if let tauri::RunEvent::Exit = event {
+                if let Some(state) = app.try_state::<AppState>() {
+                    tauri::async_runtime::block_on(state.shutdown());
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::input::{AppContext, KeyEventType, ModifierState};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_state() -> AppState {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Arc::new(Database { pool });
+        db.run_migrations().await.unwrap();
+
+        let consent_manager = Arc::new(ConsentManager::new(db.clone()).await.unwrap());
+        let session_manager = SessionManager::new(db.clone(), SessionConfig::default(), "test-device".to_string())
+            .await
+            .unwrap();
+        let input_recorder = InputRecorder::new(consent_manager.clone(), db.clone()).await.unwrap();
+
+        let recording_storage = Arc::new(
+            RecordingStorage::new(std::env::temp_dir().join("observer_test_shutdown_recordings"), db.clone())
+                .await
+                .unwrap(),
+        );
+
+        AppState {
+            search_engine: Arc::new(SearchEngine::new(db.clone())),
+            model_manager: Arc::new(ModelManager::new(std::env::temp_dir().join("models"), &HashMap::new())),
+            retention_manager: Arc::new(RetentionManager::new(db.clone(), recording_storage.clone())),
+            recording_storage,
+            db,
+            consent_manager,
+            config: Mutex::new(Config::default()),
+            screen_recorder: None,
+            os_activity_recorder: None,
+            clipboard_monitor: None,
+            session_manager: Some(Arc::new(session_manager)),
+            keyboard_recorder: None,
+            input_recorder: Some(Arc::new(input_recorder)),
+            playback_engine: None,
+            ocr_engine: None,
+            ocr_processor: None,
+            started_at: Instant::now(),
+            last_init_error: None,
+            shutting_down: AtomicBool::new(false),
+            auto_recording: AtomicBool::new(false),
+        }
+    }
+
+    fn key_event(timestamp: i64, key_char: char) -> KeyboardEvent {
+        KeyboardEvent {
+            timestamp,
+            event_type: KeyEventType::KeyDown,
+            key_code: key_char as u32,
+            key_char: Some(key_char),
+            modifiers: ModifierState::new(),
+            app_context: AppContext::new("Editor".to_string(), "notes.txt".to_string(), 42),
+            ui_element: None,
+            is_sensitive: false,
+        }
+    }
+
+    async fn keyboard_event_count(db: &Database, session_id: &str) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM keyboard_events WHERE session_id = ?")
+            .bind(session_id)
+            .fetch_one(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_flushes_pending_keyboard_events() {
+        let state = test_state().await;
+        let session_manager = state.session_manager.clone().unwrap();
+        let session_id = session_manager.get_or_create_session().await.unwrap();
+
+        // Fewer events than the buffer holds, so nothing has been written yet
+        let storage = state.input_recorder.as_ref().unwrap().storage();
+        let now = chrono::Utc::now().timestamp_millis();
+        for (i, c) in "hello".chars().enumerate() {
+            storage.store_keyboard_event(session_id.clone(), key_event(now + i as i64, c)).await.unwrap();
+        }
+
+        assert_eq!(keyboard_event_count(&state.db, &session_id).await, 0);
+
+        state.shutdown().await;
+        // Idempotent: a second quit does nothing
+        state.shutdown().await;
+
+        assert_eq!(keyboard_event_count(&state.db, &session_id).await, 5);
+        assert!(session_manager.get_current_session().await.unwrap().is_none());
+        let session = state.db.get_session(&session_id).await.unwrap();
+        assert!(session.end_timestamp.is_some());
+    }
+
+    #[test]
+    fn test_range_page_uses_the_timeline_paging_fields() {
+        let page = RangePage::new(vec![1, 2, 3], 2, 4);
+        assert_eq!(page.items, vec![1, 2]);
+        assert!(page.has_more);
+        assert_eq!(page.next_offset, Some(6));
+
+        // Same names as TimelineData, which the frontend pages through the same way
+        let json = serde_json::to_value(&page).unwrap();
+        assert_eq!(json["hasMore"], true);
+        assert_eq!(json["nextOffset"], 6);
+
+        let last = RangePage::new(vec![1], 2, 0);
+        assert!(!last.has_more);
+        assert_eq!(last.next_offset, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_round_trip() {
+        let state = test_state().await;
+        let dir = std::env::temp_dir().join(format!("observer_test_update_config_{}", Uuid::new_v4()));
+        let path = dir.join("settings.json");
+
+        let mut updated = state.config.lock().unwrap().clone();
+        updated.retention_days.insert("keyboard".to_string(), 7);
+        updated.max_storage_bytes = Some(1_000_000);
+        apply_config(&state, updated.clone(), &path).unwrap();
+
+        assert_eq!(*state.config.lock().unwrap(), updated);
+        assert_eq!(Config::load_from(&path).unwrap(), updated);
+        assert_eq!(state.recording_storage.max_storage_bytes(), Some(1_000_000));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_rejects_invalid_settings_before_saving() {
+        let state = test_state().await;
+        let dir = std::env::temp_dir().join(format!("observer_test_update_config_{}", Uuid::new_v4()));
+        let path = dir.join("settings.json");
+        let current = state.config.lock().unwrap().clone();
+
+        let mut zero_retention = current.clone();
+        zero_retention.retention_days.insert("mouse".to_string(), 0);
+
+        let mut too_long = current.clone();
+        too_long.retention_days.insert("screen".to_string(), 5000);
+
+        // Each value is fine on its own, but not together
+        let mut contradictory = current.clone();
+        contradictory.auto_record.trigger_apps = vec!["Figma".to_string()];
+        contradictory.auto_record.focus_debounce_secs = 60;
+        contradictory.auto_record.stop_after_secs = 5;
+
+        for invalid in [zero_retention, too_long, contradictory] {
+            let err = apply_config(&state, invalid, &path).unwrap_err();
+            assert!(err.starts_with("Invalid configuration"), "{}", err);
+        }
+
+        assert_eq!(*state.config.lock().unwrap(), current);
+        assert!(!path.exists());
+    }
 }
@@ -10,6 +10,9 @@ pub struct Display {
     pub width: u32,
     pub height: u32,
     pub is_primary: bool,
+    /// Position of the display's top-left corner in the virtual desktop
+    pub x_offset: i32,
+    pub y_offset: i32,
 }
 
 /// A captured frame from the screen
@@ -49,6 +52,9 @@ pub enum CaptureError {
 
     #[error("Not currently capturing")]
     NotCapturing,
+
+    #[error("Invalid recording config: {0}")]
+    InvalidConfig(String),
 }
 
 pub type CaptureResult<T> = Result<T, CaptureError>;
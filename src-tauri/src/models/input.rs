@@ -34,7 +34,7 @@ impl KeyEventType {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ModifierState {
     pub shift: bool,
     pub ctrl: bool,
@@ -74,7 +74,7 @@ impl ModifierState {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct KeyboardShortcut {
     pub modifiers: ModifierState,
     pub key: String,
@@ -225,6 +225,10 @@ pub struct KeyboardStats {
     pub most_used_keys: Vec<(char, u32)>,
     pub shortcut_usage: Vec<(String, u32)>, // (shortcut like "Cmd+C", count)
     pub typing_speed_wpm: Option<f32>,      // Words per minute if detectable
+    /// Words (5 characters) per minute of active typing; 0 with too few keystrokes
+    pub words_per_minute: f32,
+    /// Best words per minute over any 60 second window
+    pub peak_wpm: f32,
 }
 
 // ==============================================================================
@@ -2,7 +2,19 @@
 
 use crate::models::capture::{CaptureError, CaptureResult, Display, PixelFormat, RawFrame};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "wayland")]
+use std::time::Duration;
+
+/// How long to wait for the PipeWire stream to deliver a frame
+#[cfg(feature = "wayland")]
+const WAYLAND_FRAME_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The portal session for this run, so the permission dialog is shown only once
+///
+/// Cleared when the stream fails so the next capture opens a fresh session.
+#[cfg(feature = "wayland")]
+static WAYLAND_STREAM: tokio::sync::Mutex<Option<Arc<WaylandStream>>> = tokio::sync::Mutex::const_new(None);
 
 /// Display server type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,126 +93,37 @@ impl LinuxScreenCapture {
     }
 
     /// Get displays on X11
+    ///
+    /// The RandR layout is read once and reused until the X server reports a
+    /// screen or CRTC change, so per-frame lookups don't round-trip to the server.
     async fn get_displays_x11() -> CaptureResult<Vec<Display>> {
-        unsafe {
-            // Open X11 display
-            let display = x11::xlib::XOpenDisplay(std::ptr::null());
-            if display.is_null() {
-                return Err(CaptureError::CaptureFailed(
-                    "Failed to open X11 display".to_string()
-                ));
-            }
-
-            let screen = x11::xlib::XDefaultScreen(display);
-            let root = x11::xlib::XRootWindow(display, screen);
-
-            // Get screen dimensions
-            let mut root_return = 0;
-            let mut x = 0;
-            let mut y = 0;
-            let mut width = 0u32;
-            let mut height = 0u32;
-            let mut border = 0u32;
-            let mut depth = 0u32;
-
-            x11::xlib::XGetGeometry(
-                display,
-                root,
-                &mut root_return,
-                &mut x,
-                &mut y,
-                &mut width,
-                &mut height,
-                &mut border,
-                &mut depth,
-            );
-
-            // Try to get RandR extension info for multi-monitor
-            let mut displays = Vec::new();
-
-            // Check if XRandR extension is available
-            let mut event_base = 0;
-            let mut error_base = 0;
-            let has_randr = x11::xrandr::XRRQueryExtension(display, &mut event_base, &mut error_base) != 0;
-
-            if has_randr {
-                // Use XRandR to get screen resources
-                let screen_resources = x11::xrandr::XRRGetScreenResources(display, root);
-                if !screen_resources.is_null() {
-                    let noutput = (*screen_resources).noutput;
-
-                    for i in 0..noutput {
-                        let output = *(*screen_resources).outputs.add(i as usize);
-                        let output_info = x11::xrandr::XRRGetOutputInfo(display, screen_resources, output);
-
-                        if !output_info.is_null() && (*output_info).connection == x11::xrandr::RR_Connected as u16 {
-                            if (*output_info).crtc != 0 {
-                                let crtc_info = x11::xrandr::XRRGetCrtcInfo(display, screen_resources, (*output_info).crtc);
-
-                                if !crtc_info.is_null() {
-                                    let name = if !(*output_info).name.is_null() {
-                                        let name_slice = std::slice::from_raw_parts(
-                                            (*output_info).name as *const u8,
-                                            (*output_info).nameLen as usize,
-                                        );
-                                        String::from_utf8_lossy(name_slice).to_string()
-                                    } else {
-                                        format!("Display {}", i)
-                                    };
-
-                                    let is_primary = (*crtc_info).x == 0 && (*crtc_info).y == 0;
-
-                                    displays.push(Display {
-                                        id: i as u32,
-                                        name: format!("{} ({}x{})", name, (*crtc_info).width, (*crtc_info).height),
-                                        width: (*crtc_info).width as u32,
-                                        height: (*crtc_info).height as u32,
-                                        is_primary,
-                                    });
-
-                                    x11::xrandr::XRRFreeCrtcInfo(crtc_info);
-                                }
-                            }
-
-                            x11::xrandr::XRRFreeOutputInfo(output_info);
-                        }
-                    }
-
-                    x11::xrandr::XRRFreeScreenResources(screen_resources);
-                }
-            }
-
-            // If no displays found via RandR, fall back to default screen
-            if displays.is_empty() {
-                displays.push(Display {
-                    id: 0,
-                    name: format!("Default Display ({}x{})", width, height),
-                    width,
-                    height,
-                    is_primary: true,
-                });
-            }
-
-            x11::xlib::XCloseDisplay(display);
-
-            Ok(displays)
+        let mut layout = X11_LAYOUT.lock().unwrap();
+        if layout.is_none() {
+            *layout = Some(X11Layout::open()?);
         }
+        Ok(layout.as_mut().map(X11Layout::displays).unwrap_or_default())
     }
 
     /// Get displays on Wayland
     async fn get_displays_wayland() -> CaptureResult<Vec<Display>> {
-        // On Wayland, we can't enumerate displays without user permission
-        // We'll return a generic "Primary Display" entry
-        // The actual dimensions will be determined when capturing
+        // Wayland only reveals geometry through a portal session, so until the
+        // user has granted one, report a placeholder primary display
+        #[cfg(feature = "wayland")]
+        let (width, height) = match WAYLAND_STREAM.lock().await.as_ref() {
+            Some(stream) => stream.size(),
+            None => (1920, 1080),
+        };
+        #[cfg(not(feature = "wayland"))]
+        let (width, height) = (1920, 1080);
 
-        // Note: Wayland's security model doesn't allow querying display info
-        // without user permission via the portal
         Ok(vec![Display {
             id: 0,
-            name: "Primary Display (Wayland)".to_string(),
-            width: 1920, // Placeholder, will be updated on capture
-            height: 1080, // Placeholder, will be updated on capture
+            name: format!("Primary Display (Wayland, {}x{})", width, height),
+            width,
+            height,
             is_primary: true,
+            x_offset: 0,
+            y_offset: 0,
         }])
     }
 
@@ -219,8 +142,56 @@ impl LinuxScreenCapture {
         }
     }
 
-    /// Capture frame using X11
+    /// Capture a rectangle of a display, relative to the display's top-left
+    ///
+    /// The rectangle is clipped to the display bounds.
+    pub async fn capture_region(
+        display_id: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> CaptureResult<RawFrame> {
+        match DisplayServer::detect() {
+            DisplayServer::X11 => {
+                let target = Self::find_display_x11(display_id).await?;
+
+                if x >= target.width || y >= target.height {
+                    return Err(CaptureError::CaptureFailed(format!(
+                        "Region origin ({}, {}) is outside display {} ({}x{})",
+                        x, y, display_id, target.width, target.height
+                    )));
+                }
+                let width = width.min(target.width - x);
+                let height = height.min(target.height - y);
+
+                Self::capture_rect_x11(
+                    target.x_offset + x as i32,
+                    target.y_offset + y as i32,
+                    width,
+                    height,
+                )
+            }
+            _ => Err(CaptureError::NotSupported),
+        }
+    }
+
+    async fn find_display_x11(display_id: u32) -> CaptureResult<Display> {
+        Self::get_displays_x11()
+            .await?
+            .into_iter()
+            .find(|d| d.id == display_id)
+            .ok_or(CaptureError::DisplayNotFound(display_id))
+    }
+
+    /// Capture frame using X11, cropped to the requested monitor
     async fn capture_frame_x11(display_id: u32) -> CaptureResult<RawFrame> {
+        let target = Self::find_display_x11(display_id).await?;
+        Self::capture_rect_x11(target.x_offset, target.y_offset, target.width, target.height)
+    }
+
+    /// Grab a rectangle of the X11 root window, in virtual-desktop coordinates
+    fn capture_rect_x11(x: i32, y: i32, width: u32, height: u32) -> CaptureResult<RawFrame> {
         let timestamp = chrono::Utc::now().timestamp_millis();
 
         unsafe {
@@ -235,37 +206,12 @@ impl LinuxScreenCapture {
             let screen = x11::xlib::XDefaultScreen(display);
             let root = x11::xlib::XRootWindow(display, screen);
 
-            // Get screen dimensions
-            let mut root_return = 0;
-            let mut x = 0;
-            let mut y = 0;
-            let mut width = 0u32;
-            let mut height = 0u32;
-            let mut border = 0u32;
-            let mut depth = 0u32;
-
-            x11::xlib::XGetGeometry(
-                display,
-                root,
-                &mut root_return,
-                &mut x,
-                &mut y,
-                &mut width,
-                &mut height,
-                &mut border,
-                &mut depth,
-            );
-
-            // For multi-monitor, we should get the specific monitor's geometry
-            // For now, we capture the entire root window
-            // TODO: Support capturing specific monitors via RandR
-
-            // Capture the screen
+            // Capture the monitor's area of the root window
             let image = x11::xlib::XGetImage(
                 display,
                 root,
-                0,
-                0,
+                x,
+                y,
                 width,
                 height,
                 x11::xlib::XAllPlanes(),
@@ -338,30 +284,52 @@ impl LinuxScreenCapture {
         }
     }
 
-    /// Capture frame using Wayland (via XDG Desktop Portal)
+    /// Capture frame using Wayland (via XDG Desktop Portal and PipeWire)
+    ///
+    /// The first call opens a ScreenCast portal session, which shows the
+    /// system permission dialog; later calls reuse the running stream.
+    #[cfg(feature = "wayland")]
+    async fn capture_frame_wayland(display_id: u32) -> CaptureResult<RawFrame> {
+        // The portal is asked for a single monitor, which becomes display 0
+        if display_id != 0 {
+            return Err(CaptureError::DisplayNotFound(display_id));
+        }
+
+        let stream = {
+            let mut cached = WAYLAND_STREAM.lock().await;
+            match cached.as_ref() {
+                Some(stream) => stream.clone(),
+                None => {
+                    let stream = Arc::new(WaylandStream::open().await?);
+                    *cached = Some(stream.clone());
+                    stream
+                }
+            }
+        };
+
+        let deadline = tokio::time::Instant::now() + WAYLAND_FRAME_TIMEOUT;
+        loop {
+            match poll_stream(&stream) {
+                Some(Ok(frame)) => return Ok(frame),
+                Some(Err(e)) => {
+                    forget_stream(&mut *WAYLAND_STREAM.lock().await, &stream);
+                    return Err(e);
+                }
+                None => {}
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(CaptureError::CaptureFailed(
+                    "Timed out waiting for a frame from the PipeWire stream".to_string()
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Wayland capture was compiled out; build with the `wayland` feature
+    #[cfg(not(feature = "wayland"))]
     async fn capture_frame_wayland(_display_id: u32) -> CaptureResult<RawFrame> {
-        // Wayland screen capture requires using the XDG Desktop Portal
-        // This is a complex async operation that requires:
-        // 1. Creating a portal session
-        // 2. Requesting screen share permission (shows dialog to user)
-        // 3. Setting up PipeWire stream
-        // 4. Capturing frames from the stream
-
-        // For this implementation, we'll return an error with instructions
-        // A full implementation would use ashpd to interact with the portal
-
-        Err(CaptureError::CaptureFailed(
-            "Wayland screen capture requires XDG Desktop Portal integration. \
-            This is not yet fully implemented. Please use X11 for now, or use \
-            a tool like OBS Studio which has full Wayland PipeWire support.".to_string()
-        ))
-
-        // TODO: Full Wayland implementation would look like:
-        // 1. Use ashpd::desktop::screenshot::Screenshot or screencast
-        // 2. Request permission from user
-        // 3. Get PipeWire stream FD
-        // 4. Use pipewire crate to capture frames
-        // This requires significant additional dependencies and complexity
+        Err(CaptureError::NotSupported)
     }
 
     /// Start continuous capture from the specified display
@@ -410,6 +378,467 @@ impl LinuxScreenCapture {
     }
 }
 
+// ==============================================================================
+// X11 monitor layout
+// ==============================================================================
+
+/// Cached RandR layout, shared by every capture call in this process
+static X11_LAYOUT: Mutex<Option<X11Layout>> = Mutex::new(None);
+
+/// A dedicated X connection that listens for RandR changes to the root window
+struct X11Layout {
+    display: *mut x11::xlib::Display,
+    /// First RandR event code, or None when the extension is missing
+    randr_event_base: Option<i32>,
+    displays: Vec<Display>,
+}
+
+// SAFETY: the connection is only touched while holding the X11_LAYOUT lock
+unsafe impl Send for X11Layout {}
+
+impl X11Layout {
+    fn open() -> CaptureResult<Self> {
+        unsafe {
+            let display = x11::xlib::XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                return Err(CaptureError::CaptureFailed(
+                    "Failed to open X11 display".to_string()
+                ));
+            }
+
+            let mut event_base = 0;
+            let mut error_base = 0;
+            let randr_event_base = if x11::xrandr::XRRQueryExtension(display, &mut event_base, &mut error_base) != 0 {
+                let root = x11::xlib::XDefaultRootWindow(display);
+                x11::xrandr::XRRSelectInput(
+                    display,
+                    root,
+                    x11::xrandr::RRScreenChangeNotifyMask | x11::xrandr::RRCrtcChangeNotifyMask,
+                );
+                Some(event_base)
+            } else {
+                None
+            };
+
+            let displays = Self::query_displays(display, randr_event_base.is_some());
+            Ok(Self { display, randr_event_base, displays })
+        }
+    }
+
+    /// Current monitors, re-read only if a RandR change arrived since the last call
+    fn displays(&mut self) -> Vec<Display> {
+        let Some(event_base) = self.randr_event_base else {
+            return self.displays.clone();
+        };
+
+        let mut changed = false;
+        unsafe {
+            while x11::xlib::XPending(self.display) > 0 {
+                let mut event: x11::xlib::XEvent = std::mem::zeroed();
+                x11::xlib::XNextEvent(self.display, &mut event);
+
+                let randr_event = event.get_type() - event_base;
+                if randr_event == x11::xrandr::RRScreenChangeNotify
+                    || randr_event == x11::xrandr::RRNotify
+                {
+                    x11::xrandr::XRRUpdateConfiguration(&mut event);
+                    changed = true;
+                }
+            }
+
+            if changed {
+                self.displays = Self::query_displays(self.display, true);
+            }
+        }
+
+        self.displays.clone()
+    }
+
+    /// Enumerate connected monitors, falling back to the whole root window
+    unsafe fn query_displays(display: *mut x11::xlib::Display, has_randr: bool) -> Vec<Display> {
+        let screen = x11::xlib::XDefaultScreen(display);
+        let root = x11::xlib::XRootWindow(display, screen);
+
+        // Get screen dimensions
+        let mut root_return = 0;
+        let mut x = 0;
+        let mut y = 0;
+        let mut width = 0u32;
+        let mut height = 0u32;
+        let mut border = 0u32;
+        let mut depth = 0u32;
+
+        x11::xlib::XGetGeometry(
+            display,
+            root,
+            &mut root_return,
+            &mut x,
+            &mut y,
+            &mut width,
+            &mut height,
+            &mut border,
+            &mut depth,
+        );
+
+        let mut displays = Vec::new();
+
+        if has_randr {
+            // Use XRandR to get screen resources
+            let screen_resources = x11::xrandr::XRRGetScreenResources(display, root);
+            if !screen_resources.is_null() {
+                let noutput = (*screen_resources).noutput;
+
+                for i in 0..noutput {
+                    let output = *(*screen_resources).outputs.add(i as usize);
+                    let output_info = x11::xrandr::XRRGetOutputInfo(display, screen_resources, output);
+
+                    if !output_info.is_null() && (*output_info).connection == x11::xrandr::RR_Connected as u16 {
+                        if (*output_info).crtc != 0 {
+                            let crtc_info = x11::xrandr::XRRGetCrtcInfo(display, screen_resources, (*output_info).crtc);
+
+                            if !crtc_info.is_null() {
+                                let name = if !(*output_info).name.is_null() {
+                                    let name_slice = std::slice::from_raw_parts(
+                                        (*output_info).name as *const u8,
+                                        (*output_info).nameLen as usize,
+                                    );
+                                    String::from_utf8_lossy(name_slice).to_string()
+                                } else {
+                                    format!("Display {}", i)
+                                };
+
+                                let is_primary = (*crtc_info).x == 0 && (*crtc_info).y == 0;
+
+                                displays.push(Display {
+                                    id: i as u32,
+                                    name: format!("{} ({}x{})", name, (*crtc_info).width, (*crtc_info).height),
+                                    width: (*crtc_info).width as u32,
+                                    height: (*crtc_info).height as u32,
+                                    is_primary,
+                                    x_offset: (*crtc_info).x,
+                                    y_offset: (*crtc_info).y,
+                                });
+
+                                x11::xrandr::XRRFreeCrtcInfo(crtc_info);
+                            }
+                        }
+
+                        x11::xrandr::XRRFreeOutputInfo(output_info);
+                    }
+                }
+
+                x11::xrandr::XRRFreeScreenResources(screen_resources);
+            }
+        }
+
+        // If no displays found via RandR, fall back to default screen
+        if displays.is_empty() {
+            displays.push(Display {
+                id: 0,
+                name: format!("Default Display ({}x{})", width, height),
+                width,
+                height,
+                is_primary: true,
+                x_offset: 0,
+                y_offset: 0,
+            });
+        }
+
+        displays
+    }
+}
+
+// ==============================================================================
+// Wayland ScreenCast stream
+// ==============================================================================
+
+/// A running ScreenCast portal session feeding frames from PipeWire
+///
+/// PipeWire objects are not Send, so the stream lives on its own thread with
+/// a PipeWire main loop; the latest frame is shared through a mutex.
+#[cfg(feature = "wayland")]
+struct WaylandStream {
+    shared: Arc<Mutex<StreamShared>>,
+}
+
+#[cfg(feature = "wayland")]
+#[derive(Default)]
+struct StreamShared {
+    size: (u32, u32),
+    latest: Option<RawFrame>,
+    error: Option<String>,
+}
+
+#[cfg(feature = "wayland")]
+impl WaylandStream {
+    /// Ask the portal for a monitor and start reading its PipeWire stream
+    async fn open() -> CaptureResult<Self> {
+        use ashpd::desktop::screencast::{CursorMode, Screencast, SourceType};
+        use ashpd::desktop::PersistMode;
+        use ashpd::WindowIdentifier;
+
+        let proxy = Screencast::new().await.map_err(portal_error)?;
+        let session = proxy.create_session().await.map_err(portal_error)?;
+
+        proxy
+            .select_sources(
+                &session,
+                CursorMode::Embedded,
+                SourceType::Monitor.into(),
+                false,
+                None,
+                PersistMode::DoNot,
+            )
+            .await
+            .map_err(portal_error)?;
+
+        let streams = proxy
+            .start(&session, &WindowIdentifier::default())
+            .await
+            .map_err(portal_error)?
+            .response()
+            .map_err(portal_error)?;
+
+        let stream = streams.streams().first().ok_or_else(|| {
+            CaptureError::CaptureFailed("Portal returned no streams".to_string())
+        })?;
+        let node_id = stream.pipe_wire_node_id();
+        let size = stream
+            .size()
+            .map(|(w, h)| (w.max(0) as u32, h.max(0) as u32))
+            .unwrap_or((1920, 1080));
+
+        let fd = proxy.open_pipe_wire_remote(&session).await.map_err(portal_error)?;
+
+        let shared = Arc::new(Mutex::new(StreamShared { size, ..Default::default() }));
+        let thread_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("wayland-screencast".to_string())
+            .spawn(move || {
+                let result = run_pipewire_stream(fd, node_id, thread_shared.clone());
+                let mut shared = thread_shared.lock().unwrap();
+                if shared.error.is_none() {
+                    shared.error = Some(match result {
+                        Ok(()) => "PipeWire stream ended".to_string(),
+                        Err(e) => format!("PipeWire stream failed: {}", e),
+                    });
+                }
+            })
+            .map_err(|e| CaptureError::CaptureFailed(format!("Failed to start PipeWire thread: {}", e)))?;
+
+        Ok(Self { shared })
+    }
+
+    /// Stream size, updated once PipeWire negotiates the format
+    fn size(&self) -> (u32, u32) {
+        self.shared.lock().unwrap().size
+    }
+
+    fn latest_frame(&self) -> Option<RawFrame> {
+        self.shared.lock().unwrap().latest.clone()
+    }
+
+    fn error(&self) -> Option<String> {
+        self.shared.lock().unwrap().error.clone()
+    }
+}
+
+/// The stream's error if it has failed, otherwise its latest frame if any
+///
+/// The error wins so a stream that stopped after its first frames isn't read
+/// as a frozen screen forever.
+#[cfg(feature = "wayland")]
+fn poll_stream(stream: &WaylandStream) -> Option<CaptureResult<RawFrame>> {
+    if let Some(error) = stream.error() {
+        return Some(Err(CaptureError::CaptureFailed(error)));
+    }
+    stream.latest_frame().map(Ok)
+}
+
+/// Drop the cached session if it is still the one that failed
+///
+/// Another capture may already have replaced it with a fresh session.
+#[cfg(feature = "wayland")]
+fn forget_stream(cached: &mut Option<Arc<WaylandStream>>, failed: &Arc<WaylandStream>) {
+    if cached.as_ref().is_some_and(|stream| Arc::ptr_eq(stream, failed)) {
+        *cached = None;
+    }
+}
+
+/// Map portal failures, treating a dismissed dialog as a permission denial
+#[cfg(feature = "wayland")]
+fn portal_error(error: ashpd::Error) -> CaptureError {
+    use ashpd::desktop::ResponseError;
+    use ashpd::PortalError;
+
+    match error {
+        ashpd::Error::Response(ResponseError::Cancelled)
+        | ashpd::Error::Portal(PortalError::Cancelled(_))
+        | ashpd::Error::Portal(PortalError::NotAllowed(_)) => CaptureError::PermissionDenied(
+            "Screen sharing was not allowed in the desktop portal dialog".to_string()
+        ),
+        other => CaptureError::CaptureFailed(format!("Desktop portal error: {}", other)),
+    }
+}
+
+/// Run a PipeWire main loop that copies each frame of `node_id` into `shared`
+///
+/// Blocks until the stream errors or is disconnected.
+#[cfg(feature = "wayland")]
+fn run_pipewire_stream(
+    fd: std::os::fd::OwnedFd,
+    node_id: u32,
+    shared: Arc<Mutex<StreamShared>>,
+) -> Result<(), pipewire::Error> {
+    use pipewire as pw;
+    use pw::spa;
+    use spa::param::format::{FormatProperties, MediaSubtype, MediaType};
+    use spa::param::video::{VideoFormat, VideoInfoRaw};
+    use spa::pod::Pod;
+
+    pw::init();
+
+    let mainloop = pw::main_loop::MainLoop::new(None)?;
+    let context = pw::context::Context::new(&mainloop)?;
+    let core = context.connect_fd(fd, None)?;
+
+    let stream = pw::stream::Stream::new(
+        &core,
+        "zero-screencast",
+        pw::properties::properties! {
+            *pw::keys::MEDIA_TYPE => "Video",
+            *pw::keys::MEDIA_CATEGORY => "Capture",
+            *pw::keys::MEDIA_ROLE => "Screen",
+        },
+    )?;
+
+    let format_shared = shared.clone();
+    let state_shared = shared.clone();
+    let state_loop = mainloop.clone();
+    let _listener = stream
+        .add_local_listener_with_user_data(VideoInfoRaw::default())
+        .state_changed(move |_, _, old, new| {
+            // Closing the portal session or the compositor dropping the node
+            // ends the stream; stop the loop so the session can be reopened
+            let error = match new {
+                pw::stream::StreamState::Error(message) => message,
+                pw::stream::StreamState::Unconnected if old != pw::stream::StreamState::Unconnected => {
+                    "PipeWire stream disconnected".to_string()
+                }
+                _ => return,
+            };
+            state_shared.lock().unwrap().error = Some(error);
+            state_loop.quit();
+        })
+        .param_changed(move |_, format, id, param| {
+            let Some(param) = param else { return };
+            if id != spa::param::ParamType::Format.as_raw() {
+                return;
+            }
+            let Ok((media_type, media_subtype)) = spa::param::format_utils::parse_format(param) else {
+                return;
+            };
+            if media_type != MediaType::Video || media_subtype != MediaSubtype::Raw {
+                return;
+            }
+            if format.parse(param).is_ok() {
+                let size = format.size();
+                format_shared.lock().unwrap().size = (size.width, size.height);
+            }
+        })
+        .process(move |stream, format| {
+            let Some(mut buffer) = stream.dequeue_buffer() else { return };
+            let datas = buffer.datas_mut();
+            let Some(data) = datas.first_mut() else { return };
+
+            let size = format.size();
+            let (width, height) = (size.width, size.height);
+            let stride = data.chunk().stride() as usize;
+            let offset = data.chunk().offset() as usize;
+            let Some(bytes) = data.data() else { return };
+
+            let row_bytes = width as usize * 4;
+            if width == 0 || height == 0 || stride < row_bytes {
+                return;
+            }
+
+            // BGRx leaves the fourth byte undefined, so force it opaque
+            let opaque = format.format() == VideoFormat::BGRx;
+            let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+            for row in 0..height as usize {
+                let start = offset + row * stride;
+                let Some(line) = bytes.get(start..start + row_bytes) else { return };
+                pixels.extend_from_slice(line);
+            }
+            if opaque {
+                pixels.chunks_exact_mut(4).for_each(|px| px[3] = 255);
+            }
+
+            shared.lock().unwrap().latest = Some(RawFrame {
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                width,
+                height,
+                data: pixels,
+                format: PixelFormat::BGRA8,
+            });
+        })
+        .register()?;
+
+    // Accept 32-bit BGR layouts at any size the compositor offers
+    let format = spa::pod::object!(
+        spa::utils::SpaTypes::ObjectParamFormat,
+        spa::param::ParamType::EnumFormat,
+        spa::pod::property!(FormatProperties::MediaType, Id, MediaType::Video),
+        spa::pod::property!(FormatProperties::MediaSubtype, Id, MediaSubtype::Raw),
+        spa::pod::property!(
+            FormatProperties::VideoFormat,
+            Choice,
+            Enum,
+            Id,
+            VideoFormat::BGRA,
+            VideoFormat::BGRA,
+            VideoFormat::BGRx
+        ),
+        spa::pod::property!(
+            FormatProperties::VideoSize,
+            Choice,
+            Range,
+            Rectangle,
+            spa::utils::Rectangle { width: 1920, height: 1080 },
+            spa::utils::Rectangle { width: 1, height: 1 },
+            spa::utils::Rectangle { width: 8192, height: 8192 }
+        ),
+        spa::pod::property!(
+            FormatProperties::VideoFramerate,
+            Choice,
+            Range,
+            Fraction,
+            spa::utils::Fraction { num: 30, denom: 1 },
+            spa::utils::Fraction { num: 0, denom: 1 },
+            spa::utils::Fraction { num: 1000, denom: 1 }
+        ),
+    );
+    let values: Vec<u8> = spa::pod::serialize::PodSerializer::serialize(
+        std::io::Cursor::new(Vec::new()),
+        &spa::pod::Value::Object(format),
+    )
+    .map_err(|_| pw::Error::CreationFailed)?
+    .0
+    .into_inner();
+    let mut params = [Pod::from_bytes(&values).ok_or(pw::Error::CreationFailed)?];
+
+    stream.connect(
+        spa::utils::Direction::Input,
+        Some(node_id),
+        pw::stream::StreamFlags::AUTOCONNECT | pw::stream::StreamFlags::MAP_BUFFERS,
+        &mut params,
+    )?;
+
+    mainloop.run();
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -428,6 +857,59 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "wayland")]
+    fn idle_stream() -> Arc<WaylandStream> {
+        Arc::new(WaylandStream { shared: Arc::new(Mutex::new(StreamShared::default())) })
+    }
+
+    #[cfg(feature = "wayland")]
+    #[test]
+    fn test_failed_wayland_stream_is_forgotten() {
+        let failed = idle_stream();
+        let mut cached = Some(failed.clone());
+
+        forget_stream(&mut cached, &failed);
+
+        assert!(cached.is_none(), "A failed session should not be reused");
+    }
+
+    #[cfg(feature = "wayland")]
+    #[test]
+    fn test_replacement_wayland_stream_is_kept() {
+        let failed = idle_stream();
+        let replacement = idle_stream();
+        let mut cached = Some(replacement.clone());
+
+        forget_stream(&mut cached, &failed);
+
+        assert!(cached.is_some_and(|stream| Arc::ptr_eq(&stream, &replacement)));
+    }
+
+    #[cfg(feature = "wayland")]
+    #[test]
+    fn test_errored_stream_with_frame_is_reopened() {
+        let stream = idle_stream();
+        {
+            let mut shared = stream.shared.lock().unwrap();
+            shared.latest = Some(RawFrame {
+                timestamp: 0,
+                width: 1,
+                height: 1,
+                data: vec![0; 4],
+                format: PixelFormat::BGRA8,
+            });
+            shared.error = Some("PipeWire stream ended".to_string());
+        }
+        let mut cached = Some(stream.clone());
+
+        let polled = poll_stream(&stream);
+        assert!(matches!(polled, Some(Err(CaptureError::CaptureFailed(_)))));
+
+        // Forgetting it is what makes the next capture open a new session
+        forget_stream(&mut cached, &stream);
+        assert!(cached.is_none());
+    }
+
     #[tokio::test]
     async fn test_get_displays() {
         let displays = LinuxScreenCapture::get_displays().await;
@@ -450,6 +932,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_x11_layout_is_reused_between_lookups() {
+        if DisplayServer::detect() != DisplayServer::X11 {
+            println!("Skipping layout cache test - not running on X11");
+            return;
+        }
+
+        let first = LinuxScreenCapture::get_displays_x11().await.expect("Failed to read layout");
+        let second = LinuxScreenCapture::get_displays_x11().await.expect("Failed to read layout");
+
+        let geometry = |displays: &[Display]| {
+            displays
+                .iter()
+                .map(|d| (d.id, d.x_offset, d.y_offset, d.width, d.height))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(geometry(&first), geometry(&second));
+        assert!(X11_LAYOUT.lock().unwrap().is_some(), "Layout should stay cached");
+    }
+
     #[tokio::test]
     async fn test_capture_frame() {
         let displays = match LinuxScreenCapture::get_displays().await {
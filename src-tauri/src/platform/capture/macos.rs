@@ -85,6 +85,8 @@ impl MacOSScreenCapture {
                         width: bounds.size.width as u32,
                         height: bounds.size.height as u32,
                         is_primary: id == main_display_id,
+                        x_offset: bounds.origin.x as i32,
+                        y_offset: bounds.origin.y as i32,
                     })
                 })
                 .collect();
@@ -113,6 +113,8 @@ impl WindowsScreenCapture {
                             width,
                             height,
                             is_primary,
+                            x_offset: desc.DesktopCoordinates.left,
+                            y_offset: desc.DesktopCoordinates.top,
                         });
                     }
 
@@ -0,0 +1,386 @@
+// Clipboard change detection - reports type and size only, never the contents
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of data currently on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardContentType {
+    Text,
+    Image,
+    File,
+    Other,
+}
+
+impl ClipboardContentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClipboardContentType::Text => "text",
+            ClipboardContentType::Image => "image",
+            ClipboardContentType::File => "file",
+            ClipboardContentType::Other => "other",
+        }
+    }
+}
+
+/// Metadata describing the current clipboard contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardMetadata {
+    pub content_type: ClipboardContentType,
+    pub size_bytes: u64,
+}
+
+/// Token that changes whenever the clipboard contents change
+///
+/// Returns None when the clipboard cannot be queried.
+pub fn change_token() -> Option<u64> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::change_token()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::change_token()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::change_token()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Type and size of the current clipboard contents
+pub fn current_metadata() -> Option<ClipboardMetadata> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::current_metadata()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::current_metadata()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::current_metadata()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::{ClipboardContentType, ClipboardMetadata};
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+
+    pub fn change_token() -> Option<u64> {
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            if pasteboard == nil {
+                return None;
+            }
+            let count: i64 = msg_send![pasteboard, changeCount];
+            Some(count as u64)
+        }
+    }
+
+    pub fn current_metadata() -> Option<ClipboardMetadata> {
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            if pasteboard == nil {
+                return None;
+            }
+
+            let types: id = msg_send![pasteboard, types];
+            if types == nil {
+                return None;
+            }
+
+            // Pick the most specific representation on offer
+            let count: usize = msg_send![types, count];
+            let mut best: Option<(ClipboardContentType, id)> = None;
+            for i in 0..count {
+                let uti: id = msg_send![types, objectAtIndex: i];
+                let c_str: *const i8 = msg_send![uti, UTF8String];
+                if c_str.is_null() {
+                    continue;
+                }
+                let content_type = classify_uti(&CStr::from_ptr(c_str).to_string_lossy());
+
+                let better = match best {
+                    None => true,
+                    Some((current, _)) => rank(content_type) < rank(current),
+                };
+                if better {
+                    best = Some((content_type, uti));
+                }
+            }
+
+            let (content_type, uti) = best?;
+            let data: id = msg_send![pasteboard, dataForType: uti];
+            let size_bytes = if data == nil {
+                0
+            } else {
+                let length: usize = msg_send![data, length];
+                length as u64
+            };
+
+            Some(ClipboardMetadata { content_type, size_bytes })
+        }
+    }
+
+    fn classify_uti(uti: &str) -> ClipboardContentType {
+        match uti {
+            "public.file-url" => ClipboardContentType::File,
+            "public.png" | "public.tiff" | "public.jpeg" => ClipboardContentType::Image,
+            "public.utf8-plain-text" | "public.utf16-plain-text" | "public.rtf" | "public.html" => {
+                ClipboardContentType::Text
+            }
+            _ => ClipboardContentType::Other,
+        }
+    }
+
+    fn rank(content_type: ClipboardContentType) -> u8 {
+        match content_type {
+            ClipboardContentType::File => 0,
+            ClipboardContentType::Image => 1,
+            ClipboardContentType::Text => 2,
+            ClipboardContentType::Other => 3,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::{ClipboardContentType, ClipboardMetadata};
+    use windows::Win32::Foundation::{HGLOBAL, HWND};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, GetClipboardData, GetClipboardSequenceNumber, IsClipboardFormatAvailable,
+        OpenClipboard,
+    };
+    use windows::Win32::System::Memory::GlobalSize;
+
+    const CF_UNICODETEXT: u32 = 13;
+    const CF_DIB: u32 = 8;
+    const CF_HDROP: u32 = 15;
+
+    pub fn change_token() -> Option<u64> {
+        let sequence = unsafe { GetClipboardSequenceNumber() };
+        // Zero means the caller lacks access to the clipboard
+        (sequence != 0).then_some(sequence as u64)
+    }
+
+    pub fn current_metadata() -> Option<ClipboardMetadata> {
+        let (content_type, format) = [
+            (ClipboardContentType::File, CF_HDROP),
+            (ClipboardContentType::Image, CF_DIB),
+            (ClipboardContentType::Text, CF_UNICODETEXT),
+        ]
+        .into_iter()
+        .find(|(_, format)| unsafe { IsClipboardFormatAvailable(*format).is_ok() })
+        .unwrap_or((ClipboardContentType::Other, 0));
+
+        if format == 0 {
+            return Some(ClipboardMetadata { content_type, size_bytes: 0 });
+        }
+
+        unsafe {
+            OpenClipboard(HWND::default()).ok()?;
+            let size_bytes = GetClipboardData(format)
+                .map(|handle| GlobalSize(HGLOBAL(handle.0)) as u64)
+                .unwrap_or(0);
+            let _ = CloseClipboard();
+
+            Some(ClipboardMetadata { content_type, size_bytes })
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{ClipboardContentType, ClipboardMetadata};
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, OnceLock};
+
+    // XFixes event mask bits and event code for selection ownership changes
+    const SET_SELECTION_OWNER_NOTIFY_MASK: u64 = 1 << 0;
+    const SELECTION_WINDOW_DESTROY_NOTIFY_MASK: u64 = 1 << 1;
+    const SELECTION_CLIENT_CLOSE_NOTIFY_MASK: u64 = 1 << 2;
+    const XFIXES_SELECTION_NOTIFY: i32 = 0;
+
+    /// Change counter fed by a watcher thread, or None if no watcher could start
+    static CHANGES: OnceLock<Option<Arc<AtomicU64>>> = OnceLock::new();
+
+    // Neither X11 nor Wayland expose a change counter, so a background thread
+    // counts selection-change notifications instead of re-reading the payload.
+    pub fn change_token() -> Option<u64> {
+        CHANGES
+            .get_or_init(start_watcher)
+            .as_ref()
+            .map(|changes| changes.load(Ordering::SeqCst))
+    }
+
+    pub fn current_metadata() -> Option<ClipboardMetadata> {
+        let (content_type, mime) = preferred_type()?;
+        let size_bytes = read(&mime).map(|data| data.len() as u64).unwrap_or(0);
+        Some(ClipboardMetadata { content_type, size_bytes })
+    }
+
+    fn start_watcher() -> Option<Arc<AtomicU64>> {
+        let changes = Arc::new(AtomicU64::new(0));
+        let started = if is_wayland() {
+            watch_wayland(changes.clone())
+        } else {
+            watch_x11(changes.clone())
+        };
+
+        match started {
+            Ok(()) => Some(changes),
+            Err(e) => {
+                eprintln!("Clipboard change notifications unavailable: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Count CLIPBOARD ownership changes reported by XFixes
+    fn watch_x11(changes: Arc<AtomicU64>) -> Result<(), String> {
+        // The connection is moved into the thread, so pass it as an address
+        let (display, event_base) = unsafe {
+            let display = x11::xlib::XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                return Err("failed to open X11 display".to_string());
+            }
+
+            let mut event_base = 0;
+            let mut error_base = 0;
+            if x11::xfixes::XFixesQueryExtension(display, &mut event_base, &mut error_base) == 0 {
+                x11::xlib::XCloseDisplay(display);
+                return Err("XFixes extension not available".to_string());
+            }
+
+            let clipboard = x11::xlib::XInternAtom(display, c"CLIPBOARD".as_ptr(), 0);
+            x11::xfixes::XFixesSelectSelectionInput(
+                display,
+                x11::xlib::XDefaultRootWindow(display),
+                clipboard,
+                SET_SELECTION_OWNER_NOTIFY_MASK
+                    | SELECTION_WINDOW_DESTROY_NOTIFY_MASK
+                    | SELECTION_CLIENT_CLOSE_NOTIFY_MASK,
+            );
+
+            (display as usize, event_base)
+        };
+
+        std::thread::Builder::new()
+            .name("clipboard-xfixes".to_string())
+            .spawn(move || unsafe {
+                let display = display as *mut x11::xlib::Display;
+                loop {
+                    let mut event: x11::xlib::XEvent = std::mem::zeroed();
+                    x11::xlib::XNextEvent(display, &mut event);
+                    if event.get_type() == event_base + XFIXES_SELECTION_NOTIFY {
+                        changes.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| format!("failed to start watcher thread: {}", e))
+    }
+
+    /// Count clipboard changes reported by a long-running `wl-paste --watch`
+    fn watch_wayland(changes: Arc<AtomicU64>) -> Result<(), String> {
+        let mut child = Command::new("wl-paste")
+            .args(["--watch", "echo"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("failed to run wl-paste: {}", e))?;
+        let stdout = child.stdout.take().ok_or("wl-paste has no stdout")?;
+
+        std::thread::Builder::new()
+            .name("clipboard-wl-paste".to_string())
+            .spawn(move || {
+                // wl-paste reports the selection it finds at startup; that one
+                // was copied before we started watching
+                for _ in BufReader::new(stdout).lines().skip(1) {
+                    changes.fetch_add(1, Ordering::SeqCst);
+                }
+                let _ = child.wait();
+            })
+            .map(|_| ())
+            .map_err(|e| format!("failed to start watcher thread: {}", e))
+    }
+
+    fn is_wayland() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    fn list_types() -> Vec<String> {
+        let output = if is_wayland() {
+            Command::new("wl-paste").arg("--list-types").output()
+        } else {
+            Command::new("xclip")
+                .args(["-selection", "clipboard", "-t", "TARGETS", "-o"])
+                .output()
+        };
+
+        match output {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn read(mime: &str) -> Option<Vec<u8>> {
+        let output = if is_wayland() {
+            Command::new("wl-paste")
+                .args(["--no-newline", "--type", mime])
+                .output()
+        } else {
+            Command::new("xclip")
+                .args(["-selection", "clipboard", "-t", mime, "-o"])
+                .output()
+        };
+
+        output.ok().filter(|o| o.status.success()).map(|o| o.stdout)
+    }
+
+    fn preferred_type() -> Option<(ClipboardContentType, String)> {
+        let types = list_types();
+        if types.is_empty() {
+            return None;
+        }
+
+        let find = |pred: &dyn Fn(&str) -> bool| types.iter().find(|t| pred(t)).cloned();
+
+        if let Some(mime) = find(&|t| t == "text/uri-list") {
+            return Some((ClipboardContentType::File, mime));
+        }
+        if let Some(mime) = find(&|t| t.starts_with("image/")) {
+            return Some((ClipboardContentType::Image, mime));
+        }
+        if let Some(mime) = find(&|t| t.starts_with("text/plain") || t == "UTF8_STRING" || t == "STRING") {
+            return Some((ClipboardContentType::Text, mime));
+        }
+
+        Some((ClipboardContentType::Other, types[0].clone()))
+    }
+}
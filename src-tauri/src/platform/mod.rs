@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 pub mod capture;
+pub mod clipboard;
 pub mod power;
 pub mod os_monitor;
 pub mod input;
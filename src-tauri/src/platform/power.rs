@@ -1,23 +1,269 @@
 // Platform-specific power management and sleep detection
 
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
 
+/// How often the battery level is sampled
+const BATTERY_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Battery percentage at or below which `BatteryLow` is sent by default
+pub const DEFAULT_LOW_BATTERY_PERCENT: u8 = 20;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PowerEvent {
     Sleep,
     Wake,
+    /// Running on battery at or below the low-battery threshold
+    BatteryLow,
+    /// Back on AC power, or charged above the threshold, after `BatteryLow`
+    Charging,
+}
+
+/// Deliver system sleep and wake transitions to `on_event`
+///
+/// Notifications keep arriving for the life of the process. `on_event` may be
+/// called from a thread the OS owns, so it must not block.
+pub fn watch_sleep_wake(
+    on_event: impl Fn(PowerEvent) + Send + Sync + 'static,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::watch(Box::new(on_event))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows_power::watch(Box::new(on_event))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::watch(Box::new(on_event))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = on_event;
+        Err("Sleep/wake notifications are not supported on this platform".into())
+    }
+}
+
+type PowerCallback = Box<dyn Fn(PowerEvent) + Send + Sync>;
+
+#[cfg(target_os = "macos")]
+mod macos {
+    // IOKit root power domain notifications, serviced by a CFRunLoop on its own thread
+
+    use super::{PowerCallback, PowerEvent};
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    type IoConnect = u32;
+    type IoObject = u32;
+    type IoNotificationPortRef = *mut c_void;
+    type CfRunLoopRef = *mut c_void;
+    type CfRunLoopSourceRef = *mut c_void;
+    type CfStringRef = *const c_void;
+    type IoServiceInterestCallback =
+        extern "C" fn(refcon: *mut c_void, service: IoObject, message_type: u32, message_argument: *mut c_void);
+
+    // iokit_common_msg(0x270 / 0x280 / 0x300)
+    const IO_MESSAGE_CAN_SYSTEM_SLEEP: u32 = 0xE000_0270;
+    const IO_MESSAGE_SYSTEM_WILL_SLEEP: u32 = 0xE000_0280;
+    const IO_MESSAGE_SYSTEM_HAS_POWERED_ON: u32 = 0xE000_0300;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IORegisterForSystemPower(
+            refcon: *mut c_void,
+            notify_port: *mut IoNotificationPortRef,
+            callback: IoServiceInterestCallback,
+            notifier: *mut IoObject,
+        ) -> IoConnect;
+        fn IONotificationPortGetRunLoopSource(notify: IoNotificationPortRef) -> CfRunLoopSourceRef;
+        fn IOAllowPowerChange(kernel_port: IoConnect, notification_id: isize) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFRunLoopDefaultMode: CfStringRef;
+        fn CFRunLoopGetCurrent() -> CfRunLoopRef;
+        fn CFRunLoopAddSource(run_loop: CfRunLoopRef, source: CfRunLoopSourceRef, mode: CfStringRef);
+        fn CFRunLoopRun();
+    }
+
+    struct Context {
+        on_event: PowerCallback,
+        root_port: AtomicU32,
+    }
+
+    extern "C" fn power_callback(refcon: *mut c_void, _service: IoObject, message_type: u32, message_argument: *mut c_void) {
+        let context = unsafe { &*(refcon as *const Context) };
+        let root_port = context.root_port.load(Ordering::Acquire);
+
+        match message_type {
+            // Sleep is held up until acknowledged, so always allow it
+            IO_MESSAGE_CAN_SYSTEM_SLEEP => unsafe {
+                IOAllowPowerChange(root_port, message_argument as isize);
+            },
+            IO_MESSAGE_SYSTEM_WILL_SLEEP => {
+                (context.on_event)(PowerEvent::Sleep);
+                unsafe {
+                    IOAllowPowerChange(root_port, message_argument as isize);
+                }
+            }
+            IO_MESSAGE_SYSTEM_HAS_POWERED_ON => (context.on_event)(PowerEvent::Wake),
+            _ => {}
+        }
+    }
+
+    pub(super) fn watch(on_event: PowerCallback) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        std::thread::Builder::new()
+            .name("power-events".to_string())
+            .spawn(move || {
+                // Lives as long as the run loop, which is the rest of the process
+                let context: &'static Context = Box::leak(Box::new(Context {
+                    on_event,
+                    root_port: AtomicU32::new(0),
+                }));
+
+                unsafe {
+                    let mut notify_port: IoNotificationPortRef = std::ptr::null_mut();
+                    let mut notifier: IoObject = 0;
+                    let root_port = IORegisterForSystemPower(
+                        context as *const Context as *mut c_void,
+                        &mut notify_port,
+                        power_callback,
+                        &mut notifier,
+                    );
+                    if root_port == 0 {
+                        eprintln!("IORegisterForSystemPower failed");
+                        return;
+                    }
+                    context.root_port.store(root_port, Ordering::Release);
+
+                    CFRunLoopAddSource(
+                        CFRunLoopGetCurrent(),
+                        IONotificationPortGetRunLoopSource(notify_port),
+                        kCFRunLoopDefaultMode,
+                    );
+                    CFRunLoopRun();
+                }
+            })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_power {
+    // Suspend/resume callbacks from powrprof; Windows calls back on its own thread
+
+    use super::{PowerCallback, PowerEvent};
+    use std::ffi::c_void;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Power::{PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS};
+    use windows::Win32::UI::WindowsAndMessaging::{DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND};
+
+    unsafe extern "system" fn suspend_resume_callback(context: *const c_void, event_type: u32, _setting: *const c_void) -> u32 {
+        let on_event = &*(context as *const PowerCallback);
+
+        match event_type {
+            PBT_APMSUSPEND => on_event(PowerEvent::Sleep),
+            // Sent on every resume, whether or not a user is present
+            PBT_APMRESUMEAUTOMATIC => on_event(PowerEvent::Wake),
+            _ => {}
+        }
+
+        0
+    }
+
+    pub(super) fn watch(on_event: PowerCallback) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Both must outlive the registration, which is never removed
+        let context: &'static PowerCallback = Box::leak(Box::new(on_event));
+        let parameters: &'static mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(suspend_resume_callback),
+            Context: context as *const PowerCallback as *mut c_void,
+        }));
+
+        let mut registration: *mut c_void = std::ptr::null_mut();
+        let status = unsafe {
+            PowerRegisterSuspendResumeNotification(
+                DEVICE_NOTIFY_CALLBACK,
+                HANDLE(parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as *mut c_void),
+                &mut registration,
+            )
+        };
+
+        if status.is_err() {
+            return Err(format!("PowerRegisterSuspendResumeNotification failed: {}", status.0).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    // systemd-logind PrepareForSleep signal on the system bus
+
+    use super::{PowerCallback, PowerEvent};
+
+    pub(super) fn watch(on_event: PowerCallback) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        std::thread::Builder::new()
+            .name("power-events".to_string())
+            .spawn(move || {
+                if let Err(e) = listen(&on_event) {
+                    eprintln!("logind sleep notifications unavailable: {}", e);
+                }
+            })?;
+
+        Ok(())
+    }
+
+    fn listen(on_event: &PowerCallback) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let connection = zbus::blocking::Connection::system()?;
+        let proxy = zbus::blocking::Proxy::new(
+            &connection,
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+        )?;
+
+        // The argument is true just before sleeping and false after waking
+        for message in proxy.receive_signal("PrepareForSleep")? {
+            match message.body().deserialize::<bool>() {
+                Ok(true) => on_event(PowerEvent::Sleep),
+                Ok(false) => on_event(PowerEvent::Wake),
+                Err(e) => eprintln!("Malformed PrepareForSleep signal: {}", e),
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Power manager that monitors system sleep/wake events
 pub struct PowerManager {
     event_tx: broadcast::Sender<PowerEvent>,
+    low_battery_percent: Arc<AtomicU8>,
 }
 
 impl PowerManager {
     /// Create a new power manager
     pub fn new() -> Self {
         let (event_tx, _) = broadcast::channel(16);
-        Self { event_tx }
+        Self {
+            event_tx,
+            low_battery_percent: Arc::new(AtomicU8::new(DEFAULT_LOW_BATTERY_PERCENT)),
+        }
+    }
+
+    /// Battery percentage at or below which `BatteryLow` is sent
+    pub fn set_low_battery_threshold(&self, percent: u8) {
+        self.low_battery_percent.store(percent, Ordering::Relaxed);
     }
 
     /// Subscribe to power events
@@ -27,63 +273,152 @@ impl PowerManager {
 
     /// Start monitoring power events
     pub async fn start_monitoring(&self) {
+        let event_tx = self.event_tx.clone();
+        if let Err(e) = watch_sleep_wake(move |event| {
+            let _ = event_tx.send(event);
+        }) {
+            eprintln!("Power monitoring unavailable: {}", e);
+        }
+
+        // No platform pushes battery levels, so sample them
+        let event_tx = self.event_tx.clone();
+        let threshold = Arc::clone(&self.low_battery_percent);
+        tokio::spawn(async move {
+            let mut was_low = false;
+            loop {
+                let (on_battery, percent) = tokio::task::spawn_blocking(|| (Self::is_on_battery(), Self::battery_percent()))
+                    .await
+                    .unwrap_or((false, None));
+                let event = battery_transition(was_low, on_battery, percent, threshold.load(Ordering::Relaxed));
+                if let Some(event) = event {
+                    was_low = event == PowerEvent::BatteryLow;
+                    let _ = event_tx.send(event);
+                }
+                tokio::time::sleep(BATTERY_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Remaining battery charge in percent, if the system has a battery
+    pub fn battery_percent() -> Option<u8> {
         #[cfg(target_os = "macos")]
-        self.start_monitoring_macos().await;
+        {
+            // `pmset -g batt` lists e.g. "-InternalBattery-0 (id=1234)\t85%; discharging; ..."
+            let output = std::process::Command::new("pmset")
+                .args(["-g", "batt"])
+                .output()
+                .ok()?;
+            String::from_utf8_lossy(&output.stdout)
+                .split(|c: char| c.is_whitespace() || c == ';')
+                .find_map(|token| token.strip_suffix('%')?.parse().ok())
+        }
 
         #[cfg(target_os = "windows")]
-        self.start_monitoring_windows().await;
+        {
+            use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+            let mut status = SYSTEM_POWER_STATUS::default();
+            unsafe {
+                GetSystemPowerStatus(&mut status).ok()?;
+            }
+
+            // 255 means the level is unknown (or there is no battery)
+            (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent)
+        }
 
         #[cfg(target_os = "linux")]
-        self.start_monitoring_linux().await;
+        {
+            std::fs::read_dir("/sys/class/power_supply")
+                .ok()?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    std::fs::read_to_string(path.join("type"))
+                        .map(|t| t.trim() == "Battery")
+                        .unwrap_or(false)
+                })
+                .find_map(|path| std::fs::read_to_string(path.join("capacity")).ok()?.trim().parse().ok())
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        {
+            None
+        }
     }
 
-    #[cfg(target_os = "macos")]
-    async fn start_monitoring_macos(&self) {
-        // macOS: Use IOKit's IORegisterForSystemPower
-        // This would require objective-c bindings
-        // For now, this is a placeholder implementation
+    /// Check whether the system is currently running on battery power
+    pub fn is_on_battery() -> bool {
+        #[cfg(target_os = "macos")]
+        {
+            Self::is_on_battery_macos()
+        }
 
-        println!("macOS power monitoring started");
+        #[cfg(target_os = "windows")]
+        {
+            Self::is_on_battery_windows()
+        }
 
-        // In a full implementation, we would:
-        // 1. Use IORegisterForSystemPower to get notifications
-        // 2. Listen for kIOMessageSystemWillSleep and kIOMessageSystemHasPoweredOn
-        // 3. Send PowerEvent::Sleep and PowerEvent::Wake via event_tx
+        #[cfg(target_os = "linux")]
+        {
+            Self::is_on_battery_linux()
+        }
 
-        // Placeholder: Log that monitoring is active
-        // Real implementation would use FFI to IOKit
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        {
+            false
+        }
     }
 
-    #[cfg(target_os = "windows")]
-    async fn start_monitoring_windows(&self) {
-        // Windows: Use RegisterPowerSettingNotification
-        // This would require windows-rs crate
+    #[cfg(target_os = "macos")]
+    fn is_on_battery_macos() -> bool {
+        // `pmset -g batt` reports "Now drawing from 'Battery Power'" when unplugged
+        std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"))
+            .unwrap_or(false)
+    }
 
-        println!("Windows power monitoring started");
+    #[cfg(target_os = "windows")]
+    fn is_on_battery_windows() -> bool {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
 
-        // In a full implementation, we would:
-        // 1. Create a hidden window to receive power messages
-        // 2. Register for GUID_CONSOLE_DISPLAY_STATE notifications
-        // 3. Handle WM_POWERBROADCAST messages
-        // 4. Send PowerEvent::Sleep and PowerEvent::Wake via event_tx
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe {
+            if GetSystemPowerStatus(&mut status).is_err() {
+                return false;
+            }
+        }
 
-        // Placeholder: Log that monitoring is active
+        // ACLineStatus: 0 = offline (battery), 1 = online, 255 = unknown
+        status.ACLineStatus == 0
     }
 
     #[cfg(target_os = "linux")]
-    async fn start_monitoring_linux(&self) {
-        // Linux: Use D-Bus to monitor systemd-logind
-        // This would require dbus crate
+    fn is_on_battery_linux() -> bool {
+        let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+            return false;
+        };
+
+        let mut has_battery = false;
 
-        println!("Linux power monitoring started");
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let supply_type = std::fs::read_to_string(path.join("type")).unwrap_or_default();
 
-        // In a full implementation, we would:
-        // 1. Connect to system D-Bus
-        // 2. Subscribe to org.freedesktop.login1.Manager.PrepareForSleep signal
-        // 3. Signal argument: true = going to sleep, false = waking up
-        // 4. Send PowerEvent::Sleep and PowerEvent::Wake via event_tx
+            match supply_type.trim() {
+                "Mains" | "USB" => {
+                    let online = std::fs::read_to_string(path.join("online")).unwrap_or_default();
+                    if online.trim() == "1" {
+                        return false;
+                    }
+                }
+                "Battery" => has_battery = true,
+                _ => {}
+            }
+        }
 
-        // Placeholder: Log that monitoring is active
+        has_battery
     }
 
     /// Manually trigger a power event (for testing)
@@ -93,10 +428,31 @@ impl PowerManager {
     }
 }
 
+/// Event to send when the battery state crosses the low threshold, if any
+fn battery_transition(was_low: bool, on_battery: bool, percent: Option<u8>, threshold: u8) -> Option<PowerEvent> {
+    let is_low = on_battery && percent.is_some_and(|p| p <= threshold);
+
+    match (was_low, is_low) {
+        (false, true) => Some(PowerEvent::BatteryLow),
+        (true, false) => Some(PowerEvent::Charging),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_battery_transition_fires_once_per_crossing() {
+        assert_eq!(battery_transition(false, true, Some(50), 20), None);
+        assert_eq!(battery_transition(false, true, Some(20), 20), Some(PowerEvent::BatteryLow));
+        assert_eq!(battery_transition(true, true, Some(15), 20), None);
+        // Plugging in counts as recovered even before the level climbs
+        assert_eq!(battery_transition(true, false, Some(15), 20), Some(PowerEvent::Charging));
+        assert_eq!(battery_transition(false, true, None, 20), None);
+    }
+
     #[tokio::test]
     async fn test_power_manager_subscribe() {
         let manager = PowerManager::new();
@@ -6,9 +6,11 @@ interface AppUsageStats {
   bundle_id: string;
   total_focus_duration_ms: number;
   total_background_duration_ms: number;
+  active_focus_duration_ms: number;
   launch_count: number;
   first_launch: number;
   last_terminate: number | null;
+  category: string;
 }
 
 interface AppUsageStatsProps {
@@ -195,7 +197,7 @@ export default function AppUsageStats({
                       <td className="p-2">
                         <div className="font-medium">{stat.app_name}</div>
                         <div className="text-xs text-gray-600 dark:text-gray-400">
-                          {stat.bundle_id}
+                          {stat.bundle_id} · {stat.category}
                         </div>
                       </td>
                       <td className="p-2">
@@ -12,6 +12,8 @@ interface ConsentState {
   mouse_recording: boolean;
   camera_recording: boolean;
   microphone_recording: boolean;
+  clipboard_monitoring: boolean;
+  pose_tracking: boolean;
 }
 
 interface FeatureInfo {
@@ -58,6 +60,18 @@ const FEATURES: FeatureInfo[] = [
     description: "Optional: Record audio from your microphone during sessions",
     icon: "🎤",
   },
+  {
+    key: "clipboard_monitoring",
+    title: "Clipboard Monitoring",
+    description: "Record when the clipboard changes (only the content type and size, never the content)",
+    icon: "📋",
+  },
+  {
+    key: "pose_tracking",
+    title: "Pose Tracking",
+    description: "Optional: Estimate body posture from your camera (only landmarks are stored, never video)",
+    icon: "🧍",
+  },
 ];
 
 export default function ConsentManager() {
@@ -68,6 +82,8 @@ export default function ConsentManager() {
     mouse_recording: false,
     camera_recording: false,
     microphone_recording: false,
+    clipboard_monitoring: false,
+    pose_tracking: false,
   });
   const [loading, setLoading] = useState(true);
   const [updating, setUpdating] = useState<string | null>(null);
@@ -87,6 +103,8 @@ export default function ConsentManager() {
         mouse_recording: allConsents.mouse_recording || false,
         camera_recording: allConsents.camera_recording || false,
         microphone_recording: allConsents.microphone_recording || false,
+        clipboard_monitoring: allConsents.clipboard_monitoring || false,
+        pose_tracking: allConsents.pose_tracking || false,
       });
     } catch (error) {
       console.error("Failed to load consents:", error);
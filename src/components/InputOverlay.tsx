@@ -1,5 +1,6 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { RangePage } from '../types/pagination';
 
 interface InputOverlayProps {
   sessionId: string;
@@ -74,18 +75,18 @@ export function InputOverlay({
       const windowMs = 1000; // Show events ±1 second
 
       // Get keyboard events
-      const kbEvents = await invoke<KeyboardEvent[]>('get_keyboard_events_in_range', {
+      const kbEvents = await invoke<RangePage<KeyboardEvent>>('get_keyboard_events_in_range', {
         sessionId,
         startTime: timestamp - windowMs,
         endTime: timestamp + windowMs
-      }).catch(() => [] as KeyboardEvent[]);
+      }).then(page => page.items).catch(() => [] as KeyboardEvent[]);
 
       // Get mouse events
-      const mouseEvents = await invoke<MouseEvent[]>('get_mouse_events_in_range', {
+      const mouseEvents = await invoke<RangePage<MouseEvent>>('get_mouse_events_in_range', {
         sessionId,
         startTime: timestamp - windowMs,
         endTime: timestamp + windowMs
-      }).catch(() => [] as MouseEvent[]);
+      }).then(page => page.items).catch(() => [] as MouseEvent[]);
 
       processKeyboardEvents(kbEvents, timestamp);
       processMouseEvents(mouseEvents, timestamp);
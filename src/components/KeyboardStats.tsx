@@ -8,6 +8,8 @@ interface KeyboardStats {
   most_used_keys: [string, number][];
   shortcut_usage: [string, number][];
   typing_speed_wpm: number | null;
+  words_per_minute: number;
+  peak_wpm: number;
 }
 
 interface KeyboardStatsProps {
@@ -109,8 +111,13 @@ export default function KeyboardStats({ sessionId }: KeyboardStatsProps) {
         <div className="p-4 bg-purple-50 dark:bg-purple-900 rounded-lg">
           <div className="text-sm text-gray-600 dark:text-gray-400 mb-1">Typing Speed</div>
           <div className="text-2xl font-bold text-purple-700 dark:text-purple-300">
-            {stats.typing_speed_wpm ? `${stats.typing_speed_wpm} WPM` : 'N/A'}
+            {stats.words_per_minute > 0 ? `${stats.words_per_minute.toFixed(0)} WPM` : 'N/A'}
           </div>
+          {stats.peak_wpm > 0 && (
+            <div className="text-xs text-gray-600 dark:text-gray-400 mt-1">
+              Peak {stats.peak_wpm.toFixed(0)} WPM
+            </div>
+          )}
         </div>
       </div>
 
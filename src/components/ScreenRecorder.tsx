@@ -1,5 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
@@ -11,6 +12,8 @@ interface Display {
   width: number;
   height: number;
   is_primary: boolean;
+  x_offset: number;
+  y_offset: number;
 }
 
 interface RecordingStatus {
@@ -18,6 +21,13 @@ interface RecordingStatus {
   display_id: number | null;
   display_name: string | null;
   has_consent: boolean;
+  segment_count: number;
+  total_motion_percentage: number;
+  dropped_frames: number;
+  source_width: number | null;
+  source_height: number | null;
+  active_encoder: string;
+  hardware_fallback: boolean;
 }
 
 export default function ScreenRecorder() {
@@ -31,6 +41,15 @@ export default function ScreenRecorder() {
     loadDisplaysAndStatus();
   }, []);
 
+  useEffect(() => {
+    const unlisten = listen<RecordingStatus>("recording-progress", (event) => {
+      setStatus(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   async function loadDisplaysAndStatus() {
     setLoading(true);
     setError(null);
@@ -1,5 +1,7 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { save } from '@tauri-apps/plugin-dialog';
 import KeyboardMonitor from './KeyboardMonitor';
 import KeyboardStats from './KeyboardStats';
 import { CommandStats } from './CommandStats';
@@ -22,6 +24,13 @@ interface AppUsageStats {
   launch_count: number;
   first_launch: number;
   last_terminate: number | null;
+  category: string;
+}
+
+interface ExportProgress {
+  session_id: string;
+  processed_ms: number;
+  total_ms: number;
 }
 
 interface SessionDetailProps {
@@ -35,6 +44,7 @@ export default function SessionDetail({ sessionId, onClose }: SessionDetailProps
   const [appStats, setAppStats] = useState<AppUsageStats[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [exportProgress, setExportProgress] = useState<number | null>(null);
 
   useEffect(() => {
     loadSessionDetails();
@@ -62,6 +72,30 @@ export default function SessionDetail({ sessionId, onClose }: SessionDetailProps
     }
   };
 
+  const exportVideo = async () => {
+    const outputPath = await save({
+      defaultPath: `session-${sessionId.substring(0, 8)}.mp4`,
+      filters: [{ name: 'Video', extensions: ['mp4'] }],
+    });
+    if (!outputPath) return;
+
+    const unlisten = await listen<ExportProgress>('export-progress', (event) => {
+      if (event.payload.session_id === sessionId && event.payload.total_ms > 0) {
+        setExportProgress(Math.min(event.payload.processed_ms / event.payload.total_ms, 1));
+      }
+    });
+
+    try {
+      setExportProgress(0);
+      await invoke('export_session', { sessionId, outputPath });
+    } catch (err) {
+      setError(`Error exporting session: ${err}`);
+    } finally {
+      unlisten();
+      setExportProgress(null);
+    }
+  };
+
   const formatDuration = (ms: number): string => {
     const seconds = Math.floor(ms / 1000);
     const minutes = Math.floor(seconds / 60);
@@ -134,14 +168,25 @@ export default function SessionDetail({ sessionId, onClose }: SessionDetailProps
     <div className="p-4 border border-gray-300 dark:border-gray-700 rounded-lg">
       <div className="flex justify-between items-center mb-4">
         <h2 className="text-xl font-semibold">Session Details</h2>
-        {onClose && (
+        <div className="flex gap-2">
           <button
-            onClick={onClose}
-            className="px-3 py-1 bg-gray-500 text-white rounded hover:bg-gray-600 text-sm"
+            onClick={exportVideo}
+            disabled={exportProgress !== null}
+            className="px-3 py-1 bg-blue-500 text-white rounded hover:bg-blue-600 disabled:opacity-50 text-sm"
           >
-            Close
+            {exportProgress !== null
+              ? `Exporting ${formatPercentage(exportProgress)}`
+              : 'Export Video'}
           </button>
-        )}
+          {onClose && (
+            <button
+              onClick={onClose}
+              className="px-3 py-1 bg-gray-500 text-white rounded hover:bg-gray-600 text-sm"
+            >
+              Close
+            </button>
+          )}
+        </div>
       </div>
 
       {/* Session Type Badge */}
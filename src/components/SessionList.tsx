@@ -1,5 +1,6 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { RangePage } from '../types/pagination';
 
 interface Session {
   id: string;
@@ -53,10 +54,18 @@ export default function SessionList({ onSelectSession }: SessionListProps) {
           start = 0;
       }
 
-      const fetchedSessions = await invoke<Session[]>('get_session_history', {
-        start,
-        end: now,
-      });
+      // The backend pages sessions; fetch every page so long ranges aren't cut off
+      const fetchedSessions: Session[] = [];
+      let offset: number | null = 0;
+      while (offset !== null) {
+        const page: RangePage<Session> = await invoke<RangePage<Session>>('get_session_history', {
+          start,
+          end: now,
+          offset,
+        });
+        fetchedSessions.push(...page.items);
+        offset = page.nextOffset;
+      }
 
       // Enhance sessions with computed properties
       const enhanced = fetchedSessions.map((session) => {
@@ -12,13 +12,14 @@ import { Switch } from "@/components/ui/switch";
 import { FolderOpen, Lock, AlertCircle, CheckCircle2 } from "lucide-react";
 
 interface Config {
-  storage_path: string;
+  data_directory: string | null;
   retention_days: Record<string, number>;
   recording_quality: "High" | "Medium" | "Low";
   auto_start: boolean;
   motion_detection_threshold: number;
   ocr_enabled: boolean;
   default_recording_fps: number;
+  redact_sensitive_input: boolean;
 }
 
 export default function Settings() {
@@ -78,7 +79,7 @@ export default function Settings() {
     }
   }
 
-  async function selectStoragePath() {
+  async function selectDataDirectory() {
     try {
       const selected = await open({
         directory: true,
@@ -86,7 +87,7 @@ export default function Settings() {
       });
 
       if (selected && typeof selected === "string") {
-        updateConfig({ storage_path: selected });
+        updateConfig({ data_directory: selected });
       }
     } catch (error) {
       console.error("Failed to select directory:", error);
@@ -276,15 +277,20 @@ export default function Settings() {
           <Card>
             <CardHeader>
               <CardTitle>Storage Location</CardTitle>
-              <CardDescription>Where recordings and data are stored</CardDescription>
+              <CardDescription>Where recordings and data are stored; takes effect after a restart</CardDescription>
             </CardHeader>
             <CardContent>
               <div className="flex flex-col gap-3">
-                <Input value={config.storage_path} readOnly />
-                <Button onClick={selectStoragePath} variant="outline" className="gap-2 w-full">
+                <Input value={config.data_directory ?? ""} placeholder="Default location" readOnly />
+                <Button onClick={selectDataDirectory} variant="outline" className="gap-2 w-full">
                   <FolderOpen className="h-4 w-4" />
                   Browse
                 </Button>
+                {config.data_directory && (
+                  <Button onClick={() => updateConfig({ data_directory: null })} variant="ghost" className="w-full">
+                    Use Default Location
+                  </Button>
+                )}
               </div>
             </CardContent>
           </Card>
@@ -333,6 +339,19 @@ export default function Settings() {
               <p className="text-sm text-muted-foreground">
                 All recording features require explicit consent. To manage feature consents, please use the Privacy & Consent tab.
               </p>
+              <div className="flex items-center justify-between">
+                <div className="space-y-0.5">
+                  <Label htmlFor="redact-sensitive">Redact sensitive input</Label>
+                  <p className="text-sm text-muted-foreground">
+                    Don't store characters typed into password fields
+                  </p>
+                </div>
+                <Switch
+                  id="redact-sensitive"
+                  checked={config.redact_sensitive_input}
+                  onCheckedChange={(checked) => updateConfig({ redact_sensitive_input: checked })}
+                />
+              </div>
             </CardContent>
           </Card>
 
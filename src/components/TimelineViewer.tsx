@@ -22,10 +22,22 @@ export function TimelineViewer() {
   const loadTimelineData = async () => {
     setLoading(true);
     try {
-      const timelineData = await invoke<TimelineData>('get_timeline_data', {
+      // The backend pages sessions; fetch every page so long ranges aren't cut off
+      let timelineData = await invoke<TimelineData>('get_timeline_data', {
         startTimestamp: dateRange.start,
         endTimestamp: dateRange.end
       });
+      while (timelineData.nextOffset !== null) {
+        const page = await invoke<TimelineData>('get_timeline_data', {
+          startTimestamp: dateRange.start,
+          endTimestamp: dateRange.end,
+          offset: timelineData.nextOffset
+        });
+        timelineData = {
+          ...page,
+          sessions: [...timelineData.sessions, ...page.sessions]
+        };
+      }
       setData(timelineData);
     } catch (error) {
       console.error('Failed to load timeline data:', error);
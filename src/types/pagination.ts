@@ -0,0 +1,7 @@
+// Paging shared by the range commands
+
+export interface RangePage<T> {
+  items: T[];
+  hasMore: boolean;
+  nextOffset: number | null; // offset of the next page, null on the last one
+}
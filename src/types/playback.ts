@@ -19,6 +19,7 @@ export interface VideoSegmentInfo {
 
 export interface SeekInfo {
   videoPath: string;
+  segmentPath: string | null;
   offsetMs: number;
   segmentStart: number;
 }
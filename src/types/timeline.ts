@@ -3,6 +3,9 @@
 export interface TimelineData {
   sessions: TimelineSession[];
   totalDuration: number;
+  totalCount: number; // sessions in the range, across all pages
+  hasMore: boolean;
+  nextOffset: number | null; // offset of the next page, null on the last one
   dateRange: {
     start: number;
     end: number;